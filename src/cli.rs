@@ -0,0 +1,190 @@
+//! The interactive tasks of the `rudle` binary
+
+use crate::CommonArgs;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rudle::solver::get_scores;
+use rudle::{Hint, Word};
+use std::env;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub mod attest;
+pub mod audit;
+pub mod bench;
+pub mod boards;
+pub mod cache;
+pub mod compose;
+pub mod difficulty;
+pub mod drill;
+pub mod experiment;
+pub mod export;
+pub mod filter;
+pub mod fit;
+pub mod grade;
+pub mod heatmap;
+pub mod input;
+pub mod optimal;
+pub mod overlay;
+pub mod persist;
+pub mod play;
+pub mod rating;
+pub mod reload;
+pub mod render;
+pub mod results;
+pub mod review;
+pub mod schema;
+pub mod selftest;
+pub mod settings;
+pub mod solve;
+pub mod spectate;
+pub mod stats;
+pub mod tournament;
+pub mod tui;
+pub mod update;
+
+/// Create the random number generator for the game, seeded if output must be reproducible
+pub fn make_rng(common: &CommonArgs) -> StdRng {
+    match rng_seed(common) {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// The seed of the random number generator: --seed, or 0 with --deterministic
+pub fn rng_seed(common: &CommonArgs) -> Option<u64> {
+    common.seed.or(common.deterministic.then_some(0))
+}
+
+/// Print a meter of how the guess scores relative to the best available guess
+pub fn print_guess_meter(guess: &Word, guesses: &[Word], answers: &[Word], common: &CommonArgs) {
+    let scores = get_scores(guesses, answers, !common.deterministic);
+    let (best_word, best_score, _) = &scores[0];
+    let guess_score = scores
+        .iter()
+        .find(|(w, _, _)| w == guess)
+        .map_or(0.0, |(_, score, _)| *score);
+
+    // When no guess can narrow the answers any further, every guess is as good as the best
+    let quality = if *best_score > 0.0 {
+        (guess_score / best_score).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+    let filled = (quality * 10.0).round() as usize;
+    println!(
+        "{} [{}{}] {:>3.0}% of best ({:.3}% vs {} {:.3}%)",
+        guess,
+        "#".repeat(filled),
+        "-".repeat(10 - filled),
+        quality * 100.0,
+        guess_score,
+        best_word,
+        best_score
+    );
+}
+
+/// Print the start of a stage of a long computation, such as "[2/3] Playing 2315 games"
+pub fn print_stage(stage: usize, n_stages: usize, name: &str) {
+    println!("[{}/{}] {}", stage, n_stages, name);
+}
+
+/// The directory where Rudle keeps its persistent data
+pub fn data_dir() -> Option<PathBuf> {
+    let base = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))?;
+    Some(base.join("rudle"))
+}
+
+/// The number of days since the Unix epoch
+pub fn current_day() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() / 86_400)
+}
+
+/// A day since the Unix epoch as a `YYYY-MM-DD` date
+pub fn format_day(day: u64) -> String {
+    // Count from 0000-03-01, so that leap days end their year
+    let days = day as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day_of_month = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day_of_month)
+}
+
+/// The day since the Unix epoch of the first day of a month
+fn first_day_of(year: i64, month: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year.rem_euclid(400);
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// Read a `YYYY-MM` month
+/// # Returns
+/// The first day of the month and the first day after it, counted since the Unix epoch
+pub fn parse_month(month: &str) -> Result<(u64, u64), String> {
+    let invalid = || format!("'{}' is not a month like 2026-09", month);
+    let (year, number) = month.trim().split_once('-').ok_or_else(invalid)?;
+    let year: i64 = year.parse().map_err(|_| invalid())?;
+    let number: i64 = number.parse().map_err(|_| invalid())?;
+    if !(1970..=9999).contains(&year) || !(1..=12).contains(&number) {
+        return Err(invalid());
+    }
+    let next = match number {
+        12 => first_day_of(year + 1, 1),
+        _ => first_day_of(year, number + 1),
+    };
+    Ok((first_day_of(year, number) as u64, next as u64))
+}
+
+/// Print a guess marked with its hint, in the format selected with `--format`
+pub fn print_hint(hint: &Hint, guess: &Word) {
+    print!("{}", render::renderer().hint(hint, guess));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn days_are_written_as_dates() {
+        assert_eq!(format_day(0), "1970-01-01");
+        assert_eq!(format_day(stats::WORDLE_EPOCH_DAY), "2021-06-19");
+        assert_eq!(format_day(19_782), "2024-02-29");
+        assert_eq!(format_day(20_740), "2026-10-14");
+    }
+
+    #[test]
+    fn months_span_their_days() {
+        assert_eq!(parse_month("1970-01"), Ok((0, 31)));
+        let (first, next) = parse_month("2024-02").unwrap();
+        assert_eq!(
+            (format_day(first), next - first),
+            ("2024-02-01".to_string(), 29)
+        );
+        let (first, next) = parse_month("2026-12").unwrap();
+        assert_eq!(
+            (format_day(first), format_day(next)),
+            ("2026-12-01".to_string(), "2027-01-01".to_string())
+        );
+        assert!(parse_month("2026-13").is_err());
+        assert!(parse_month("september").is_err());
+    }
+}
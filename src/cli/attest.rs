@@ -0,0 +1,92 @@
+//! Attestations of played games, which tournament organizers can check submissions against
+
+use crate::PlayArgs;
+use rudle::hash::{fnv1a, word_list_hash, FNV_OFFSET};
+use rudle::picker::PickerKind;
+use rudle::{Hint, Word};
+use serde_json::{json, Value};
+use std::fs;
+use std::io;
+use std::time::Duration;
+
+/// The version of the attestation format, described by `rudle schema attestation`
+pub const ATTESTATION_VERSION: u64 = 1;
+
+/// What an attestation records about a finished game
+pub struct AttestedGame<'a> {
+    pub board: &'a [(Word, Hint)],
+    /// The time from the start of the game to each guess
+    pub guess_times: &'a [Duration],
+    pub answers: &'a [Word],
+    /// The seed the secret word was picked with, if it can be reproduced
+    pub seed: Option<u64>,
+    pub solved: bool,
+}
+
+/// Write the attestation of a game as JSON
+///
+/// The puzzle is identified by a hash of the answer list and the seed, so that games of the
+/// same daily puzzle can be merged without giving the secret word away. The digest is a hash
+/// of the rest of the document: it catches hand edits that do not recompute it, but anyone
+/// can recompute it, so it is no proof against a determined forger.
+pub fn write_attestation(path: &str, game: &AttestedGame, options: &PlayArgs) -> io::Result<()> {
+    let seed_hash = game.seed.map(|seed| {
+        let hash = fnv1a(word_list_hash(game.answers), &seed.to_le_bytes());
+        format!("{:016x}", hash)
+    });
+    let guesses: Vec<Value> = game
+        .board
+        .iter()
+        .zip(game.guess_times.iter())
+        .map(|((guess, hint), time)| {
+            json!({
+                "guess": guess.to_string(),
+                "hint": hint.to_pattern(guess),
+                "elapsed_ms": time.as_millis() as u64,
+            })
+        })
+        .collect();
+
+    let mut document = json!({
+        "version": ATTESTATION_VERSION,
+        "rudle_version": env!("CARGO_PKG_VERSION"),
+        "puzzle": {
+            "pick": options.pick.name(),
+            "seed_hash": seed_hash,
+        },
+        "mode": options.mode.name(),
+        "max_attempts": options.max_attempts,
+        "guesses": guesses,
+        "result": game.solved.then_some(game.board.len()),
+        "assists": {
+            "meter": options.meter,
+            "post_mortem": options.post_mortem,
+            "assist": options.assist,
+        },
+    });
+    document["digest"] = json!(digest(&document));
+    fs::write(path, format!("{:#}\n", document))
+}
+
+/// The digest of an attestation: FNV-1a over its compact JSON without the digest field
+fn digest(document: &Value) -> String {
+    let mut document = document.clone();
+    if let Some(fields) = document.as_object_mut() {
+        fields.remove("digest");
+    }
+    format!(
+        "{:016x}",
+        fnv1a(FNV_OFFSET, document.to_string().as_bytes())
+    )
+}
+
+/// The seed a picker picks the secret word with, when the same seed picks it again
+/// # Arguments
+/// * `rng_seed` - The seed of the random number generator, if it was seeded
+pub fn reproducible_seed(pick: PickerKind, day: u64, rng_seed: Option<u64>) -> Option<u64> {
+    match pick {
+        PickerKind::Daily => Some(day),
+        PickerKind::Uniform | PickerKind::Weighted => rng_seed,
+        _ => None,
+    }
+}
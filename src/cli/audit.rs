@@ -0,0 +1,207 @@
+use super::render::print_table;
+use crate::CommonArgs;
+use rudle::solver::get_scores;
+use rudle::Word;
+use std::collections::{HashMap, HashSet};
+
+/// The number of openers whose change `compare` reports
+const COMPARED_OPENERS: usize = 10;
+
+/// Recover the first-guess entropy in bits from an expected score: score = (1 - e^-H) * 100
+fn bits(score: f32) -> f32 {
+    -f32::ln(1.0 - score / 100.0) / std::f32::consts::LN_2
+}
+
+/// Print statistics that help curators compare candidate word lists
+pub fn audit(word_list: Vec<Word>, path: &str, common: &CommonArgs) {
+    let n_words = word_list.len();
+    println!(
+        "{}: {} words of {} letters\n",
+        path, n_words, common.word_size
+    );
+    if n_words == 0 {
+        return;
+    }
+
+    // Letter frequency balance
+    let mut containing: HashMap<char, usize> = HashMap::new();
+    let mut occurrences: HashMap<char, usize> = HashMap::new();
+    for word in word_list.iter() {
+        for c in word.iter() {
+            *occurrences.entry(c).or_insert(0) += 1;
+        }
+        for c in word.iter().collect::<HashSet<_>>() {
+            *containing.entry(c).or_insert(0) += 1;
+        }
+    }
+    let mut letters: Vec<(char, usize)> = containing.into_iter().collect();
+    letters.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    println!("Share of words containing each letter:");
+    for row in letters.chunks(7) {
+        let row: Vec<String> = row
+            .iter()
+            .map(|(c, count)| format!("{} {:>5.1}%", c, *count as f32 * 100.0 / n_words as f32))
+            .collect();
+        println!("  {}", row.join("  "));
+    }
+    let total_letters = (n_words * common.word_size) as f32;
+    let letter_entropy = -occurrences
+        .values()
+        .map(|&c| c as f32 / total_letters)
+        .map(|p| p * f32::ln(p))
+        .sum::<f32>();
+    println!(
+        "Letter balance: {:.3} (1.000 means all 26 letters are equally common)\n",
+        letter_entropy / f32::ln(26.0)
+    );
+
+    // Words with repeated letters
+    let repeats = |w: &Word| w.len() - w.iter().collect::<HashSet<_>>().len();
+    let mut repetitive: Vec<&Word> = word_list.iter().filter(|w| repeats(w) > 0).collect();
+    repetitive.sort_by(|a, b| repeats(b).cmp(&repeats(a)).then(a.cmp(b)));
+    println!(
+        "Words with repeated letters: {} ({:.1}%)",
+        repetitive.len(),
+        repetitive.len() as f32 * 100.0 / n_words as f32
+    );
+    if !repetitive.is_empty() {
+        let examples: Vec<String> = repetitive.iter().take(10).map(|w| w.to_string()).collect();
+        println!("Most repetitive: {}", examples.join(", "));
+    }
+    println!();
+
+    // Families of words that only differ in a single position. The feedback cannot tell
+    // these apart except by trying their differing letters one guess at a time.
+    let mut families: HashMap<String, Vec<&Word>> = HashMap::new();
+    for word in word_list.iter() {
+        for i in 0..word.len() {
+            let pattern: String = word
+                .iter()
+                .enumerate()
+                .map(|(j, c)| if i == j { '_' } else { c })
+                .collect();
+            families.entry(pattern).or_default().push(word);
+        }
+    }
+    let mut families: Vec<(String, Vec<&Word>)> = families
+        .into_iter()
+        .filter(|(_, words)| words.len() > 2)
+        .collect();
+    families.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+    println!(
+        "Families of 3+ words differing in one position: {}",
+        families.len()
+    );
+    for (pattern, words) in families.iter().take(5) {
+        let words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        println!("  {} ({}): {}", pattern, words.len(), words.join(", "));
+    }
+    println!();
+
+    let scores = get_scores(&word_list, &word_list, !common.deterministic);
+    let average = scores.iter().map(|(_, s, _)| bits(*s)).sum::<f32>() / n_words as f32;
+    let (best_word, best_score, _) = &scores[0];
+    println!("Average first-guess entropy: {:.3} bits", average);
+    println!(
+        "Best opener: {} ({:.3} bits, {:.3}% expected reduction)",
+        best_word,
+        bits(*best_score),
+        best_score
+    );
+}
+
+/// Print the words added and removed between two word lists, and how the change would affect
+/// the solver's first guess
+pub fn compare(old: Vec<Word>, new: Vec<Word>, paths: (&str, &str), common: &CommonArgs) {
+    let (old_path, new_path) = paths;
+    let old_set: HashSet<&Word> = old.iter().collect();
+    let new_set: HashSet<&Word> = new.iter().collect();
+    let added: Vec<String> = new
+        .iter()
+        .filter(|w| !old_set.contains(w))
+        .map(|w| w.to_string())
+        .collect();
+    let removed: Vec<String> = old
+        .iter()
+        .filter(|w| !new_set.contains(w))
+        .map(|w| w.to_string())
+        .collect();
+    println!(
+        "{}: {} words, {}: {} words\n",
+        old_path,
+        old.len(),
+        new_path,
+        new.len()
+    );
+    for (label, words) in [("Added", &added), ("Removed", &removed)] {
+        print!("{} ({})", label, words.len());
+        match words.len() {
+            0 => println!(),
+            n if n <= 20 => println!(": {}", words.join(", ")),
+            _ => println!(": {}, ...", words[..20].join(", ")),
+        }
+    }
+    println!();
+    if old.is_empty() || new.is_empty() {
+        return;
+    }
+
+    let old_scores = get_scores(&old, &old, !common.deterministic);
+    let new_scores = get_scores(&new, &new, !common.deterministic);
+    let average = |scores: &[(Word, f32, f32)]| {
+        scores.iter().map(|(_, s, _)| bits(*s)).sum::<f32>() / scores.len() as f32
+    };
+    let (old_average, new_average) = (average(&old_scores), average(&new_scores));
+    println!(
+        "Average first-guess entropy: {:.3} -> {:.3} bits ({:+.3})",
+        old_average,
+        new_average,
+        new_average - old_average
+    );
+    println!("Best opener: {} -> {}\n", old_scores[0].0, new_scores[0].0);
+
+    // The best openers of either list, with their entropy and rank in both
+    let mut openers: Vec<&Word> = vec![];
+    for (word, _, _) in old_scores
+        .iter()
+        .take(COMPARED_OPENERS)
+        .chain(new_scores.iter().take(COMPARED_OPENERS))
+    {
+        if !openers.contains(&word) {
+            openers.push(word);
+        }
+    }
+    let find = |scores: &[(Word, f32, f32)], word: &Word| {
+        scores
+            .iter()
+            .position(|(w, _, _)| w == word)
+            .map(|i| (i + 1, bits(scores[i].1)))
+    };
+    let mut rows = vec![];
+    for opener in openers {
+        let old = find(&old_scores, opener);
+        let new = find(&new_scores, opener);
+        let format_bits =
+            |entry: Option<(usize, f32)>| entry.map_or("-".to_string(), |e| format!("{:.3}", e.1));
+        let format_rank =
+            |entry: Option<(usize, f32)>| entry.map_or("-".to_string(), |e| e.0.to_string());
+        let change = match (old, new) {
+            (Some(o), Some(n)) => format!("{:+.3}", n.1 - o.1),
+            _ => "-".to_string(),
+        };
+        rows.push(vec![
+            opener.to_string(),
+            format_bits(old),
+            format_bits(new),
+            change,
+            format_rank(old),
+            format_rank(new),
+        ]);
+    }
+    print_table(
+        &[
+            "Opener", "Old bits", "New bits", "Change", "Old rank", "New rank",
+        ],
+        &rows,
+    );
+}
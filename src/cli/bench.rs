@@ -0,0 +1,275 @@
+use super::cache::opening_scores;
+use super::results::{create_results, Entrant, GameResult, ResultsWriter, RunInfo};
+use super::{input, make_rng, print_stage, rng_seed};
+use crate::{BenchArgs, CommonArgs};
+use indicatif::{ProgressBar, ProgressStyle};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use rudle::solver::best_guess;
+use rudle::strategy::{auto_play_noisy, Strategy, StrategyKind};
+use rudle::{Word, WordLists};
+use serde_json::json;
+use std::time::Instant;
+
+/// Let a strategy play against every possible answer and summarize how many guesses it needs
+pub fn bench(words: WordLists, common: &CommonArgs, options: &BenchArgs) {
+    if words.answers.is_empty() {
+        println!("The answer list is empty.");
+        return;
+    }
+
+    print_stage(1, 2, "Choosing the opener");
+    let trial = Trial::new(
+        options.strategy.to_string(),
+        options.strategy,
+        options.noise,
+        &mut make_rng(common),
+        &words,
+        common,
+    )
+    .expect("No guesses to play");
+    println!(
+        "Opening with {} ({} strategy)",
+        trial.opener, options.strategy
+    );
+    if options.noise > 0.0 {
+        println!(
+            "Each hint letter is entered wrong with probability {}",
+            options.noise
+        );
+    }
+
+    let run = RunInfo::new(
+        "bench",
+        &words,
+        options.max_attempts,
+        rng_seed(common),
+        json!({ "strategy": options.strategy.name(), "noise": options.noise }),
+    );
+    let file = create_results(options.output.as_deref(), &run);
+    print_stage(2, 2, &format!("Playing {} games", words.answers.len()));
+    input::stop_between_steps();
+    let games = trial.play(&words, options.max_attempts, file.as_ref(), common);
+
+    let complete = games.len() == words.answers.len();
+    if let Some(file) = &file {
+        file.finish(complete);
+    }
+    if !complete {
+        println!(
+            "Interrupted after {} of {} games.",
+            games.len(),
+            words.answers.len()
+        );
+        if games.is_empty() {
+            return;
+        }
+    }
+    trial.print_results(&games, options.max_attempts);
+}
+
+/// A strategy ready to play every answer, as `bench` plays them
+pub struct Trial {
+    /// The name its games are written under in a results file
+    pub name: String,
+    strategy: Box<dyn Strategy>,
+    pub opener: Word,
+    /// The probability that each letter of a hint is entered wrong
+    noise: f64,
+    /// The seed of the noise of the first game, which the other games add their index to
+    noise_seed: u64,
+}
+
+impl Trial {
+    /// Set up a strategy with seeds drawn from `rng`, as `bench` does from its seed, and
+    /// choose its opener
+    /// # Returns
+    /// The trial, or None if there is no guess to play
+    pub fn new(
+        name: String,
+        kind: StrategyKind,
+        noise: f64,
+        rng: &mut StdRng,
+        words: &WordLists,
+        common: &CommonArgs,
+    ) -> Option<Self> {
+        let strategy = kind.build(rng.gen());
+        let noise_seed = rng.gen();
+        let opener = choose_opener(kind, strategy.as_ref(), words, common)?;
+        Some(Self {
+            name,
+            strategy,
+            opener,
+            noise,
+            noise_seed,
+        })
+    }
+
+    /// Play every answer, writing the trial and each game to a results file as they finish
+    /// An interrupt stops it after the games being played.
+    /// # Returns
+    /// Each answer played, with the guesses it took or None if it was not solved, and the
+    /// number of contradictions the noisy hints caused
+    pub fn play<'a>(
+        &self,
+        words: &'a WordLists,
+        max_attempts: usize,
+        file: Option<&ResultsWriter>,
+        common: &CommonArgs,
+    ) -> Vec<(&'a Word, Option<usize>, usize)> {
+        if let Some(file) = file {
+            file.entrant(&Entrant {
+                name: self.name.clone(),
+                kind: "strategy".to_string(),
+                opener: Some(self.opener.clone()),
+            });
+        }
+        let pb = if common.deterministic {
+            ProgressBar::hidden()
+        } else {
+            ProgressBar::new(words.answers.len() as u64)
+        };
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+                .expect("Invalid progress bar template")
+                .progress_chars("##-"),
+        );
+
+        let games = words
+            .answers
+            .par_iter()
+            .enumerate()
+            .filter_map(|(i, secret)| {
+                if input::is_interrupted() {
+                    return None;
+                }
+                let started = Instant::now();
+                // Each game gets its own generator, so the results do not depend on the
+                // scheduling
+                let mut rng = StdRng::seed_from_u64(self.noise_seed.wrapping_add(i as u64));
+                let game = auto_play_noisy(
+                    self.strategy.as_ref(),
+                    &words.guesses,
+                    &words.answers,
+                    secret,
+                    &self.opener,
+                    max_attempts,
+                    self.noise,
+                    &mut rng,
+                );
+                pb.inc(1);
+                let history = game.history;
+                let solved = history.last().is_some_and(|(guess, _, _)| guess == secret);
+                let guesses = solved.then_some(history.len());
+                if let Some(file) = file {
+                    let elapsed_ms = started.elapsed().as_millis() as u64;
+                    file.game(&GameResult {
+                        entrant: self.name.clone(),
+                        answer: secret.clone(),
+                        guesses,
+                        elapsed_ms: (!common.deterministic).then_some(elapsed_ms),
+                    });
+                }
+                Some((secret, guesses, game.n_contradictions))
+            })
+            .collect();
+        pb.finish_and_clear();
+        games
+    }
+
+    /// Print the report of the games played, with how often noisy hints contradicted
+    pub fn print_results(&self, games: &[(&Word, Option<usize>, usize)], max_attempts: usize) {
+        if self.noise > 0.0 {
+            let n_contradicted = games.iter().filter(|(_, _, n)| *n > 0).count();
+            let n_contradictions: usize = games.iter().map(|(_, _, n)| n).sum();
+            println!(
+                "The hints contradicted each other {} times, in {} of {} games.",
+                n_contradictions,
+                n_contradicted,
+                games.len()
+            );
+        }
+        let results: Vec<(&Word, Option<usize>)> = games.iter().map(|&(w, n, _)| (w, n)).collect();
+        print_results(&results, max_attempts);
+    }
+}
+
+/// Choose a strategy's first guess
+/// The opener is the same in every game, so it is only computed once, and for the entropy
+/// strategy it comes from the same cache as the solver's opening scores.
+pub fn choose_opener(
+    kind: StrategyKind,
+    strategy: &dyn Strategy,
+    words: &WordLists,
+    common: &CommonArgs,
+) -> Option<Word> {
+    match kind {
+        StrategyKind::Entropy => {
+            best_guess(&opening_scores(words, common), &words.answers).cloned()
+        }
+        _ => strategy.choose(&words.guesses, &words.answers, &[]),
+    }
+}
+
+/// Print the distribution of guesses, the average, the failure rate and the hardest answers
+/// # Arguments
+/// * `results` - Each answer and the number of guesses it took, or None if it was not solved
+/// * `max_attempts` - The number of guesses after which a game counts as failed
+pub fn print_results(results: &[(&Word, Option<usize>)], max_attempts: usize) {
+    let n_games = results.len();
+    let mut distribution = vec![0; max_attempts + 1];
+    let mut n_failed = 0;
+    for (_, guesses_used) in results.iter() {
+        match guesses_used {
+            Some(n) => distribution[*n] += 1,
+            None => n_failed += 1,
+        }
+    }
+
+    println!("\nGuesses | Games");
+    println!("--------|------");
+    let most_common = distribution
+        .iter()
+        .max()
+        .copied()
+        .unwrap_or(0)
+        .max(n_failed);
+    let bar = |count: usize| "#".repeat((count * 40).div_ceil(most_common.max(1)));
+    for (n, &count) in distribution.iter().enumerate().skip(1) {
+        println!("{:>7} | {:>5} {}", n, count, bar(count));
+    }
+    println!("{:>7} | {:>5} {}", "X", n_failed, bar(n_failed));
+
+    let solved: Vec<usize> = results.iter().filter_map(|(_, n)| *n).collect();
+    if !solved.is_empty() {
+        println!(
+            "\nAverage: {:.3} guesses over {} solved games",
+            solved.iter().sum::<usize>() as f32 / solved.len() as f32,
+            solved.len()
+        );
+    }
+    println!(
+        "Failure rate: {:.2}% ({} of {})",
+        n_failed as f32 * 100.0 / n_games as f32,
+        n_failed,
+        n_games
+    );
+
+    // Failures first, then the answers that took the most guesses
+    let mut hardest: Vec<&(&Word, Option<usize>)> = results.iter().collect();
+    hardest.sort_by(|a, b| {
+        let guesses_used = |r: &(&Word, Option<usize>)| r.1.unwrap_or(usize::MAX);
+        guesses_used(b).cmp(&guesses_used(a)).then(a.0.cmp(b.0))
+    });
+    let worst: Vec<String> = hardest
+        .iter()
+        .take(10)
+        .map(|(w, n)| match n {
+            Some(n) => format!("{} ({})", w, n),
+            None => format!("{} (X)", w),
+        })
+        .collect();
+    println!("Hardest answers: {}", worst.join(", "));
+}
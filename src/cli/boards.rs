@@ -0,0 +1,206 @@
+//! Games of several boards at once, in the style of Dordle and Quordle
+
+use super::input::LineReader;
+use super::play::{parse_guess, record_played};
+use super::render::{renderer, visible_width};
+use super::stats::{record_game, GameEnd};
+use crate::{CommonArgs, PlayArgs};
+use colored::*;
+use rudle::picker::AnswerPicker;
+use rudle::{Hint, Word, WordLists};
+
+/// A board of a game, with its own secret word and hints
+struct Board {
+    secret: Word,
+    rows: Vec<(Word, Hint)>,
+    remaining: Vec<Word>,
+    solved: bool,
+}
+
+/// Play a game where every guess is scored against several secret words at once
+///
+/// Each board gets every guess until it is solved, and the game is won once all of them are.
+/// Every board after the first adds one attempt to --max-attempts, which gives the 7 attempts
+/// of Dordle for 2 boards and the 9 of Quordle for 4.
+/// # Returns
+/// Each secret word, and the number of guesses it took to find it or None if it was not found
+pub fn play_boards(
+    words: &WordLists,
+    picker: &mut dyn AnswerPicker,
+    common: &CommonArgs,
+    options: &PlayArgs,
+) -> Vec<(Word, Option<usize>)> {
+    if options.obs_output.is_some()
+        || options.host.is_some()
+        || options.meter != "off"
+        || options.post_mortem
+        || options.assist
+        || options.attest.is_some()
+    {
+        println!(
+            "Warning: --obs-output, --host, --meter, --post-mortem, --assist and --attest only apply to \
+             games of one board, so they are ignored."
+        );
+    }
+
+    // Every board gets a different secret word
+    let mut pool = words.answers.clone();
+    let mut boards: Vec<Board> = vec![];
+    while boards.len() < options.boards as usize {
+        let Some(secret) = picker.pick(&pool) else {
+            break;
+        };
+        pool.retain(|w| w != &secret);
+        boards.push(Board {
+            secret,
+            rows: vec![],
+            remaining: words.answers.clone(),
+            solved: false,
+        });
+    }
+    assert!(!boards.is_empty(), "Word list is empty");
+    let max_attempts = options.max_attempts + boards.len() - 1;
+
+    println!(
+        "Welcome to Wordle! Guess {} {}-letter words at once. You have {} attempts.\n",
+        boards.len(),
+        common.word_size,
+        max_attempts
+    );
+    println!("Every guess is marked on each board that is not solved yet.\n");
+
+    let mut attempts = 0;
+    let mut gave_up = false;
+    let mut input = LineReader::new();
+    while attempts < max_attempts && !boards.iter().all(|b| b.solved) {
+        println!("You have {} attempts left.", max_attempts - attempts);
+        let Some(guess) = input.read_line("Enter your guess: ") else {
+            println!();
+            break;
+        };
+
+        if guess.trim() == "!guesses" {
+            if attempts == 0 {
+                println!("No guesses yet.");
+            }
+            for row in 0..attempts {
+                print_row(&boards, row);
+            }
+            println!();
+            continue;
+        }
+        if guess.trim() == "!giveup" {
+            gave_up = true;
+            break;
+        }
+
+        let guess = match parse_guess(&guess, words, common.word_size) {
+            Ok(guess) => guess,
+            Err(e) => {
+                println!("{}\n", e);
+                continue;
+            }
+        };
+
+        let violation = boards
+            .iter()
+            .enumerate()
+            .filter(|(_, board)| !board.solved)
+            .find_map(|(i, board)| Some((i, options.mode.check(&guess, &board.rows).err()?)));
+        if let Some((i, e)) = violation {
+            println!(
+                "Not allowed in {} mode on board {}: {}.\n",
+                options.mode,
+                i + 1,
+                e
+            );
+            continue;
+        }
+
+        input.add_history(&guess.to_string().to_lowercase());
+        for board in boards.iter_mut().filter(|board| !board.solved) {
+            board.secret = picker.respond(&board.secret, &guess, &board.remaining);
+            let hint = Hint::from_guess_and_answer(&guess, &board.secret).unwrap();
+            board
+                .remaining
+                .retain(|w| Hint::from_guess_and_answer(&guess, w).as_ref() == Ok(&hint));
+            board.rows.push((guess.clone(), hint));
+            board.solved = guess == board.secret;
+        }
+        print_row(&boards, attempts);
+        attempts += 1;
+        if let Some(i) = boards
+            .iter()
+            .position(|b| b.solved && b.rows.len() == attempts)
+        {
+            println!("{}", format!("You solved board {}!", i + 1).green());
+        }
+        println!();
+    }
+
+    for board in boards.iter() {
+        if let Err(e) = record_played(&board.secret) {
+            println!("Warning: failed to record the played word: {}", e);
+        }
+        let end = match () {
+            _ if board.solved => GameEnd::Solved,
+            _ if gave_up => GameEnd::GaveUp,
+            _ if attempts == max_attempts => GameEnd::OutOfAttempts,
+            _ => GameEnd::Quit,
+        };
+        if let Err(e) = record_game(&board.secret, &board.rows, 0, end) {
+            println!("Warning: failed to record the game: {}", e);
+        }
+    }
+
+    if boards.iter().all(|b| b.solved) {
+        println!(
+            "{}",
+            format!("Congratulations! You solved all {} boards!", boards.len()).green()
+        );
+    } else {
+        let secrets: Vec<String> = boards
+            .iter()
+            .map(|b| match b.solved {
+                true => b.secret.to_string().green().to_string(),
+                false => b.secret.to_string().red().to_string(),
+            })
+            .collect();
+        println!(
+            "{}{} The correct words were: {}",
+            "Game Over!".red(),
+            if gave_up { " You gave up." } else { "" },
+            secrets.join(", ")
+        );
+    }
+
+    boards
+        .into_iter()
+        .map(|b| {
+            let guesses_used = b.rows.len();
+            (b.secret, b.solved.then_some(guesses_used))
+        })
+        .collect()
+}
+
+/// Print the hints of one guess on every board side by side, leaving solved boards blank
+fn print_row(boards: &[Board], row: usize) {
+    let cells: Vec<Option<String>> = boards
+        .iter()
+        .map(|board| {
+            let (guess, hint) = board.rows.get(row)?;
+            Some(renderer().hint(hint, guess))
+        })
+        .collect();
+    let width = cells
+        .iter()
+        .flatten()
+        .map(|c| visible_width(c))
+        .max()
+        .unwrap_or(0);
+    let cells: Vec<String> = cells
+        .into_iter()
+        .map(|cell| cell.unwrap_or_else(|| " ".repeat(width)))
+        .collect();
+    println!("{}", cells.join("   ").trim_end());
+}
@@ -0,0 +1,104 @@
+//! Opening-round scores saved between sessions
+
+use super::data_dir;
+use super::persist::{self, Format};
+use crate::CommonArgs;
+use rudle::hash::{fnv1a, FNV_OFFSET};
+use rudle::solver::get_scores;
+use rudle::Word;
+use rudle::WordLists;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Bumped whenever the scoring changes, so stale caches are never read
+/// Unlike the file format, which is upgraded on load, scores from older versions are
+/// recalculated, because they would not match what the solver computes now.
+const CACHE_VERSION: u64 = 1;
+
+/// The format of a cache file
+const CACHE_FORMAT: Format = Format {
+    name: "scores",
+    migrations: &[persist::unchanged],
+};
+
+/// The scores of every guess before any hint, which are the same in every session that uses
+/// the same word lists
+///
+/// Each cache is stored as one `WORD EXPECTED WORST_CASE` line per guess, in a file named
+/// after a hash of the guesses and answers it was computed for.
+pub struct ScoreCache {
+    path: Option<PathBuf>,
+}
+
+impl ScoreCache {
+    pub fn new(guesses: &[Word], answers: &[Word]) -> Self {
+        let key = word_list_hash(guesses, answers);
+        let path = data_dir().map(|dir| dir.join("cache").join(format!("scores-{:016x}.txt", key)));
+        Self { path }
+    }
+
+    /// Load the cached scores, treating a missing or unreadable file as no cache
+    pub fn load(&self) -> Option<Vec<(Word, f32, f32)>> {
+        let contents = CACHE_FORMAT.load(self.path.as_ref()?)?;
+        contents
+            .lines()
+            .map(|line| {
+                let mut fields = line.split_whitespace();
+                let word = Word::from_string(fields.next()?).ok()?;
+                let avg_score = fields.next()?.parse().ok()?;
+                let min_score = fields.next()?.parse().ok()?;
+                Some((word, avg_score, min_score))
+            })
+            .collect()
+    }
+
+    pub fn save(&self, scores: &[(Word, f32, f32)]) -> io::Result<()> {
+        let path = self
+            .path
+            .as_ref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+
+        let contents: String = scores
+            .iter()
+            .map(|(word, avg_score, min_score)| format!("{} {} {}\n", word, avg_score, min_score))
+            .collect();
+        CACHE_FORMAT.save(path, &contents)
+    }
+
+    /// Delete the cached scores, treating a missing file as already deleted
+    pub fn remove(&self) -> io::Result<()> {
+        match self.path.as_ref().map(fs::remove_file) {
+            Some(Err(e)) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// The scores of every guess before any hint, from the cache or calculated and then cached
+pub fn opening_scores(words: &WordLists, common: &CommonArgs) -> Vec<(Word, f32, f32)> {
+    let cache = ScoreCache::new(&words.guesses, &words.answers);
+    cache.load().unwrap_or_else(|| {
+        let scores = get_scores(&words.guesses, &words.answers, !common.deterministic);
+        if let Err(e) = cache.save(&scores) {
+            println!("Warning: failed to save the score cache: {}", e);
+        }
+        scores
+    })
+}
+
+/// FNV-1a over both word lists
+fn word_list_hash(guesses: &[Word], answers: &[Word]) -> u64 {
+    let mut hash = FNV_OFFSET;
+    let mut feed = |bytes: &[u8]| hash = fnv1a(hash, bytes);
+
+    feed(&CACHE_VERSION.to_le_bytes());
+    for list in [guesses, answers] {
+        feed(&(list.len() as u64).to_le_bytes());
+        for word in list.iter() {
+            feed(word.as_bytes());
+            feed(b"\n");
+        }
+    }
+    hash
+}
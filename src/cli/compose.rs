@@ -0,0 +1,84 @@
+use super::make_rng;
+use super::render::print_table;
+use crate::{CommonArgs, ComposeArgs};
+use indicatif::ProgressBar;
+use rand::Rng;
+use rayon::prelude::*;
+use rudle::strategy::auto_play;
+use rudle::{Word, WordLists};
+
+/// List the secret words that take a player with a known opener and strategy the most guesses
+///
+/// Every answer is played with the strategy after the opener, so for the deterministic
+/// strategies the guesses listed are exactly the ones the player needs. The random strategy
+/// is played once per answer.
+pub fn compose(words: WordLists, common: &CommonArgs, options: &ComposeArgs) {
+    let opener = match Word::from_string(&options.against) {
+        Ok(opener) if opener.len() == common.word_size => opener,
+        Ok(_) => {
+            println!("Warning: The opener must have {} letters", common.word_size);
+            return;
+        }
+        Err(e) => {
+            println!("Warning: Invalid opener: {}", e);
+            return;
+        }
+    };
+    if words.answers.is_empty() {
+        println!("The answer list is empty.");
+        return;
+    }
+
+    let strategy = options.strategy.build(make_rng(common).gen());
+    let pb = match common.deterministic {
+        true => ProgressBar::hidden(),
+        false => ProgressBar::new(words.answers.len() as u64),
+    };
+    let mut games: Vec<(&Word, Vec<Word>, bool)> = words
+        .answers
+        .par_iter()
+        .map(|secret| {
+            let history = auto_play(
+                strategy.as_ref(),
+                &words.guesses,
+                &words.answers,
+                secret,
+                &opener,
+                options.max_attempts,
+            );
+            pb.inc(1);
+            let solved = history.last().is_some_and(|(guess, _, _)| guess == secret);
+            let guesses = history.into_iter().map(|(guess, _, _)| guess).collect();
+            (secret, guesses, solved)
+        })
+        .collect();
+    pb.finish_and_clear();
+
+    // Lost games are the hardest, then the games that took the most guesses
+    games.sort_by(|(a, a_guesses, a_solved), (b, b_guesses, b_solved)| {
+        a_solved
+            .cmp(b_solved)
+            .then(b_guesses.len().cmp(&a_guesses.len()))
+            .then(a.cmp(b))
+    });
+
+    println!(
+        "Hardest secret words against {} ({} strategy, {} answers):\n",
+        opener,
+        options.strategy,
+        words.answers.len()
+    );
+    let rows: Vec<Vec<String>> = games
+        .iter()
+        .take(options.count)
+        .map(|(secret, guesses, solved)| {
+            let result = match solved {
+                true => guesses.len().to_string(),
+                false => "X".to_string(),
+            };
+            let play: Vec<String> = guesses.iter().map(|w| w.to_string()).collect();
+            vec![secret.to_string(), result, play.join(" ")]
+        })
+        .collect();
+    print_table(&["Secret", "Guesses", "Play"], &rows);
+}
@@ -0,0 +1,123 @@
+//! Difficulty tiers of an answer list, measured by letting the solver play every answer
+
+use super::bench::choose_opener;
+use super::render::print_table;
+use crate::CommonArgs;
+use indicatif::ProgressBar;
+use rayon::prelude::*;
+use rudle::strategy::{auto_play, StrategyKind};
+use rudle::{Word, WordLists};
+use std::fs;
+use std::io;
+
+/// The tiers answers are sorted into, with the most guesses each tier's answers take
+const TIERS: [(&str, usize); 3] = [("easy", 2), ("medium", 3), ("hard", 4)];
+
+/// The tier of answers that take more guesses than every tier in `TIERS`, or are not solved
+const HARDEST_TIER: &str = "expert";
+
+/// The tier of an answer from the guesses the solver needed to find it
+fn tier(guesses_needed: usize) -> &'static str {
+    TIERS
+        .iter()
+        .find(|(_, most)| guesses_needed <= *most)
+        .map_or(HARDEST_TIER, |(name, _)| name)
+}
+
+/// Let the default strategy play every answer and sort the answers into difficulty tiers
+///
+/// The tiers are written as `WORD GUESSES TIER` lines, from the easiest answer, where answers
+/// that were not solved count one guess more than `max_attempts`. `play --pick adaptive
+/// --tiers` reads the file to pick answers by the guesses they need.
+pub fn difficulty(
+    words: WordLists,
+    common: &CommonArgs,
+    output: Option<&str>,
+    max_attempts: usize,
+) {
+    if words.answers.is_empty() {
+        println!("The answer list is empty.");
+        return;
+    }
+
+    let kind = StrategyKind::Entropy;
+    let strategy = kind.build(0);
+    let opener = choose_opener(kind, strategy.as_ref(), &words, common).expect("No guesses");
+    println!(
+        "Playing every answer with the {} strategy, opening with {}.",
+        kind, opener
+    );
+
+    let pb = match common.deterministic {
+        true => ProgressBar::hidden(),
+        false => ProgressBar::new(words.answers.len() as u64),
+    };
+    let mut results: Vec<(&Word, usize)> = words
+        .answers
+        .par_iter()
+        .map(|secret| {
+            let history = auto_play(
+                strategy.as_ref(),
+                &words.guesses,
+                &words.answers,
+                secret,
+                &opener,
+                max_attempts,
+            );
+            pb.inc(1);
+            let solved = history.last().is_some_and(|(guess, _, _)| guess == secret);
+            (
+                secret,
+                if solved {
+                    history.len()
+                } else {
+                    max_attempts + 1
+                },
+            )
+        })
+        .collect();
+    pb.finish_and_clear();
+    results.sort_by(|a, b| a.1.cmp(&b.1).then(a.0.cmp(b.0)));
+
+    let names = TIERS.iter().map(|(name, _)| *name).chain([HARDEST_TIER]);
+    let rows: Vec<Vec<String>> = names
+        .map(|name| {
+            let answers: Vec<&Word> = results
+                .iter()
+                .filter(|(_, n)| tier(*n) == name)
+                .map(|(w, _)| *w)
+                .collect();
+            let examples: Vec<String> = answers.iter().take(5).map(|w| w.to_string()).collect();
+            vec![
+                name.to_string(),
+                answers.len().to_string(),
+                format!(
+                    "{:.1}%",
+                    answers.len() as f32 * 100.0 / results.len() as f32
+                ),
+                examples.join(" "),
+            ]
+        })
+        .collect();
+    print_table(&["Tier", "Answers", "Share", "Examples"], &rows);
+
+    if let Some(path) = output {
+        match write_tiers(path, &results) {
+            Ok(()) => println!(
+                "\nWrote the tiers of {} answers to {}.",
+                results.len(),
+                path
+            ),
+            Err(e) => println!("\nError: failed to write {}: {}", path, e),
+        }
+    }
+}
+
+/// Write each answer with the guesses it needed and its tier, one per line
+fn write_tiers(path: &str, results: &[(&Word, usize)]) -> io::Result<()> {
+    let lines: String = results
+        .iter()
+        .map(|(w, n)| format!("{} {} {}\n", w, n, tier(*n)))
+        .collect();
+    fs::write(path, lines)
+}
@@ -0,0 +1,113 @@
+use super::{make_rng, print_guess_meter, print_hint};
+use crate::{CommonArgs, DrillArgs};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rudle::{Hint, Word, WordLists};
+use std::io::{self, Write};
+
+/// Present practice positions and score the user's guess against the solver's best
+pub fn drill(words: WordLists, common: &CommonArgs, options: &DrillArgs) {
+    let mut rng = make_rng(common);
+    let (min_answers, max_answers, attempts_left) = match options.scenario.as_str() {
+        "midgame" => (10, 50, 3),
+        _ => (2, 5, 2),
+    };
+
+    println!(
+        "Practicing {} positions. Type 'exit' to stop.\n",
+        options.scenario
+    );
+
+    loop {
+        let Some(Position { history, answers }) =
+            generate_position(&words, min_answers, max_answers, &mut rng)
+        else {
+            println!("Could not generate a position from this word list.");
+            return;
+        };
+
+        for (guess, hint) in history.iter() {
+            print_hint(hint, guess);
+            println!();
+        }
+        println!(
+            "{} possible answers remain and you have {} guesses left.",
+            answers.len(),
+            attempts_left
+        );
+        if options.scenario == "endgame" {
+            let answers: Vec<String> = answers.iter().map(Word::to_string).collect();
+            println!("Possible answers: {}", answers.join(", "));
+        }
+
+        let guess = loop {
+            print!("Your guess: ");
+            io::stdout().flush().unwrap();
+
+            let mut input = String::new();
+            if io::stdin()
+                .read_line(&mut input)
+                .expect("Failed to read input")
+                == 0
+            {
+                return;
+            }
+            let input = input.trim();
+            if input == "exit" {
+                return;
+            }
+
+            match Word::from_string(input) {
+                Ok(w) if words.guesses.binary_search(&w).is_ok() => break w,
+                Ok(_) => println!("Invalid word. Please try again."),
+                Err(e) => println!("Error: {}", e),
+            }
+        };
+
+        print_guess_meter(&guess, &words.guesses, &answers, common);
+        if answers.contains(&guess) {
+            println!(
+                "{} is a possible answer and wins right away {:.0}% of the time.",
+                guess,
+                100.0 / answers.len() as f32
+            );
+        }
+        println!();
+    }
+}
+
+/// A practice position: the guesses played so far and the answers they leave
+struct Position {
+    history: Vec<(Word, Hint)>,
+    answers: Vec<Word>,
+}
+
+/// Play random guesses against a random secret until few enough answers remain
+/// # Returns
+/// The generated position, or None if no position
+/// with between `min_answers` and `max_answers` remaining answers could be found
+fn generate_position(
+    words: &WordLists,
+    min_answers: usize,
+    max_answers: usize,
+    rng: &mut StdRng,
+) -> Option<Position> {
+    for _ in 0..1000 {
+        let secret = words.answers.choose(rng)?;
+        let mut answers = words.answers.clone();
+        let mut history = vec![];
+
+        while answers.len() > max_answers && history.len() < 6 {
+            let guess = words.guesses.choose(rng)?;
+            let hint = Hint::from_guess_and_answer(guess, secret).ok()?;
+            answers.retain(|w| Hint::from_guess_and_answer(guess, w).as_ref() == Ok(&hint));
+            history.push((guess.clone(), hint));
+        }
+
+        if (min_answers..=max_answers).contains(&answers.len()) {
+            return Some(Position { history, answers });
+        }
+    }
+
+    None
+}
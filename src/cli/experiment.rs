@@ -0,0 +1,667 @@
+//! Experiments: comparisons of strategies pinned down by a manifest, so that anyone can run
+//! them again and get the same games
+//!
+//! A manifest is a TOML file naming the word lists with their hashes, the seed and the
+//! strategies to play:
+//!
+//! ```toml
+//! name = "entropy-vs-minimax"
+//! seed = 7
+//! max_attempts = 6
+//!
+//! [words]
+//! guesses = "allowed.txt"
+//! guesses_hash = "8b2b3c265e02b0f3"
+//! answers = "answers.txt"
+//! answers_hash = "c0a2e1f5b4d39e87"
+//!
+//! [[strategies]]
+//! strategy = "entropy"
+//!
+//! [[strategies]]
+//! name = "noisy-minimax"
+//! strategy = "minimax"
+//! noise = 0.05
+//! seed = 11
+//! ```
+//!
+//! Only as much of TOML is read as manifests need: `[tables]`, `[[arrays of tables]]`, and
+//! keys set to strings, integers, floats and booleans, with `#` comments.
+
+use super::bench::Trial;
+use super::render::print_table;
+use super::results::{create_results, environment, RunInfo};
+use super::{input, print_stage};
+use crate::CommonArgs;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rudle::hash::{fnv1a, word_list_hash, FNV_OFFSET};
+use rudle::strategy::{Objective, StrategyKind};
+use rudle::{word, WordLists};
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+
+/// Run the experiment of a manifest and write its games to a results file
+/// A manifest that cannot be run, such as one whose word lists do not have their pinned
+/// hashes, is reported and the program exits with status 1.
+/// The results file is `output`, or else the manifest's `output`, or else the manifest's
+/// path with the extension `.jsonl`.
+pub fn run(path: &str, output: Option<&str>, common: &CommonArgs) -> io::Result<()> {
+    let text = fs::read_to_string(path)?;
+    let loaded = Manifest::parse(&text, Path::new(path))
+        .and_then(|manifest| manifest.load_words().map(|words| (manifest, words)));
+    let (manifest, words) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            println!("{}: {}", path, e);
+            process::exit(1);
+        }
+    };
+    if words.answers.is_empty() {
+        println!("The answer list is empty.");
+        return Ok(());
+    }
+    let output = match (&manifest.output, output) {
+        (_, Some(output)) => PathBuf::from(output),
+        (Some(output), None) => output.clone(),
+        (None, None) => Path::new(path).with_extension("jsonl"),
+    };
+
+    println!(
+        "Experiment {}: {} strategies over {} answers, seed {}",
+        manifest.name,
+        manifest.entries.len(),
+        words.answers.len(),
+        manifest.seed
+    );
+    let entries: Vec<Value> = manifest
+        .entries
+        .iter()
+        .map(|entry| {
+            json!({
+                "name": entry.name,
+                "strategy": entry.kind.name(),
+                "noise": entry.noise,
+                "seed": entry.seed,
+            })
+        })
+        .collect();
+    let run = RunInfo::new(
+        "experiment",
+        &words,
+        manifest.max_attempts,
+        Some(manifest.seed),
+        json!({
+            "name": manifest.name,
+            "manifest_hash": format!("{:016x}", fnv1a(FNV_OFFSET, text.as_bytes())),
+            "strategies": entries,
+        }),
+    );
+    let file = create_results(output.to_str(), &run);
+    if let Some(file) = &file {
+        file.environment(&environment());
+    }
+
+    input::stop_between_steps();
+    let n_stages = manifest.entries.len();
+    let mut rows: Vec<Vec<String>> = vec![];
+    for (i, entry) in manifest.entries.iter().enumerate() {
+        if input::is_interrupted() {
+            break;
+        }
+        print_stage(
+            i + 1,
+            n_stages,
+            &format!(
+                "Playing {} ({} strategy, seed {}, noise {})",
+                entry.name, entry.kind, entry.seed, entry.noise
+            ),
+        );
+        // Seeded like `bench --seed`, so that bench can play any entry on its own
+        let mut rng = StdRng::seed_from_u64(entry.seed);
+        let Some(trial) = Trial::new(
+            entry.name.clone(),
+            entry.kind,
+            entry.noise,
+            &mut rng,
+            &words,
+            common,
+        ) else {
+            println!("No guesses to play.");
+            return Ok(());
+        };
+        println!("Opening with {}", trial.opener);
+        let games = trial.play(&words, manifest.max_attempts, file.as_ref(), common);
+        if games.is_empty() {
+            break;
+        }
+        trial.print_results(&games, manifest.max_attempts);
+        println!();
+
+        let solved: Vec<usize> = games.iter().filter_map(|(_, n, _)| *n).collect();
+        let average = match solved.is_empty() {
+            true => "-".to_string(),
+            false => format!(
+                "{:.3}",
+                solved.iter().sum::<usize>() as f32 / solved.len() as f32
+            ),
+        };
+        rows.push(vec![
+            entry.name.clone(),
+            trial.opener.to_string(),
+            games.len().to_string(),
+            average,
+            (games.len() - solved.len()).to_string(),
+        ]);
+    }
+
+    let complete = !input::is_interrupted();
+    if let Some(file) = &file {
+        file.finish(complete);
+        println!("Wrote {}", output.display());
+    }
+    if !complete {
+        println!("Interrupted, so the results are over the games played before it stopped.");
+    }
+    if !rows.is_empty() {
+        print_table(&["Strategy", "Opener", "Games", "Average", "Failed"], &rows);
+    }
+    Ok(())
+}
+
+/// An experiment read from a manifest
+#[derive(Debug)]
+struct Manifest {
+    name: String,
+    seed: u64,
+    word_size: usize,
+    max_attempts: usize,
+    guesses: PinnedList,
+    answers: Option<PinnedList>,
+    /// The results file, relative to the manifest
+    output: Option<PathBuf>,
+    entries: Vec<Entry>,
+}
+
+/// A word list and the hash it must have
+#[derive(Debug)]
+struct PinnedList {
+    /// The list's path, relative to the manifest
+    path: PathBuf,
+    /// The `word_list_hash` of the list, or None if the manifest does not pin it
+    hash: Option<u64>,
+}
+
+/// A strategy to play every answer with
+#[derive(Debug)]
+struct Entry {
+    /// The name its games are written under, which must be unique
+    name: String,
+    kind: StrategyKind,
+    noise: f64,
+    seed: u64,
+}
+
+impl Manifest {
+    /// Read a manifest, with paths taken relative to the manifest's own directory
+    fn parse(text: &str, path: &Path) -> Result<Self, String> {
+        let dir = path.parent().unwrap_or(Path::new(""));
+        let mut root = parse_toml(text)?;
+        let name = take_string(&mut root, "name", "the manifest")?.unwrap_or_else(|| {
+            path.file_stem().map_or("experiment".to_string(), |stem| {
+                stem.to_string_lossy().into()
+            })
+        });
+        let seed = take_integer(&mut root, "seed", "the manifest")?
+            .ok_or("the manifest has no seed, which every experiment needs to be reproduced")?;
+        let word_size = take_integer(&mut root, "word_size", "the manifest")?.unwrap_or(5);
+        if !(1..=word::MAX_WORD_SIZE as u64).contains(&word_size) {
+            return Err(format!(
+                "word_size must be between 1 and {}",
+                word::MAX_WORD_SIZE
+            ));
+        }
+        let max_attempts = take_integer(&mut root, "max_attempts", "the manifest")?.unwrap_or(6);
+        if max_attempts == 0 {
+            return Err("max_attempts must be at least 1".to_string());
+        }
+        let output = take_string(&mut root, "output", "the manifest")?.map(|o| dir.join(o));
+
+        let mut words = match root.remove("words") {
+            Some(Value::Object(words)) => words,
+            Some(_) => return Err("words must be a [words] table".to_string()),
+            None => return Err("the manifest has no [words] table".to_string()),
+        };
+        let mut pinned = |key: &str| -> Result<Option<PinnedList>, String> {
+            let Some(path) = take_string(&mut words, key, "[words]")? else {
+                return Ok(None);
+            };
+            let hash_key = format!("{}_hash", key);
+            let hash = take_string(&mut words, &hash_key, "[words]")?
+                .map(|hash| {
+                    u64::from_str_radix(&hash, 16)
+                        .ok()
+                        .filter(|_| hash.len() == 16)
+                        .ok_or(format!("{} '{}' is not 16 hex digits", hash_key, hash))
+                })
+                .transpose()?;
+            Ok(Some(PinnedList {
+                path: dir.join(path),
+                hash,
+            }))
+        };
+        let guesses = pinned("guesses")?.ok_or("[words] has no guesses list")?;
+        let answers = pinned("answers")?;
+        reject_unknown(&words, "[words]")?;
+
+        let entries = match root.remove("strategies") {
+            Some(Value::Array(entries)) => entries,
+            Some(_) => return Err("strategies must be [[strategies]] tables".to_string()),
+            None => return Err("the manifest has no [[strategies]]".to_string()),
+        };
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let Value::Object(mut entry) = entry else {
+                    unreachable!("Arrays of tables only hold tables")
+                };
+                Entry::parse(&mut entry, i + 1, seed)
+            })
+            .collect::<Result<Vec<Entry>, String>>()?;
+        for (i, entry) in entries.iter().enumerate() {
+            if entries[..i].iter().any(|e| e.name == entry.name) {
+                return Err(format!(
+                    "two strategies are named '{}'; give them different names",
+                    entry.name
+                ));
+            }
+        }
+        reject_unknown(&root, "the manifest")?;
+
+        Ok(Self {
+            name,
+            seed,
+            word_size: word_size as usize,
+            max_attempts: max_attempts as usize,
+            guesses,
+            answers,
+            output,
+            entries,
+        })
+    }
+
+    /// Load the word lists, checking that they have the hashes the manifest pins
+    fn load_words(&self) -> Result<WordLists, String> {
+        let load = |list: &PinnedList, key: &str| {
+            let words = word::load_words(&list.path, self.word_size)
+                .map_err(|e| format!("{}: {}", list.path.display(), e))?;
+            let hash = word_list_hash(&words);
+            match list.hash {
+                Some(pinned) if pinned == hash => Ok(words),
+                Some(pinned) => Err(format!(
+                    "{} has the hash {:016x}, but the manifest pins {:016x}",
+                    list.path.display(),
+                    hash,
+                    pinned
+                )),
+                None => Err(format!(
+                    "the manifest does not pin the hash of {}; add {}_hash = \"{:016x}\" to \
+                     [words]",
+                    list.path.display(),
+                    key,
+                    hash
+                )),
+            }
+        };
+        let guesses = load(&self.guesses, "guesses")?;
+        println!("Loaded {} unique words", guesses.len());
+        match &self.answers {
+            Some(answers) => {
+                let answers = load(answers, "answers")?;
+                println!("Loaded {} unique answers", answers.len());
+                Ok(WordLists::new(guesses, answers))
+            }
+            None => Ok(WordLists::single(guesses)),
+        }
+    }
+}
+
+impl Entry {
+    /// Read the `n`th [[strategies]] table, whose seed defaults to the manifest's
+    fn parse(table: &mut Map<String, Value>, n: usize, seed: u64) -> Result<Self, String> {
+        let context = format!("strategy {}", n);
+        let strategy = take_string(table, "strategy", &context)?;
+        let objective = take_string(table, "objective", &context)?;
+        let kind = match (strategy, objective) {
+            (Some(strategy), None) => strategy.parse::<StrategyKind>()?,
+            (None, Some(objective)) => objective.parse::<Objective>()?.strategy(),
+            (Some(_), Some(_)) => {
+                return Err(format!("{} has both a strategy and an objective", context))
+            }
+            (None, None) => return Err(format!("{} has no strategy", context)),
+        };
+        let noise = take_float(table, "noise", &context)?.unwrap_or(0.0);
+        if !(0.0..=1.0).contains(&noise) {
+            return Err(format!("the noise of {} must be between 0 and 1", context));
+        }
+        let entry = Self {
+            name: take_string(table, "name", &context)?.unwrap_or(kind.to_string()),
+            kind,
+            noise,
+            seed: take_integer(table, "seed", &context)?.unwrap_or(seed),
+        };
+        reject_unknown(table, &context)?;
+        Ok(entry)
+    }
+}
+
+/// Take a string out of a table
+fn take_string(
+    table: &mut Map<String, Value>,
+    key: &str,
+    context: &str,
+) -> Result<Option<String>, String> {
+    match table.remove(key) {
+        None => Ok(None),
+        Some(Value::String(s)) => Ok(Some(s)),
+        Some(_) => Err(format!("{} in {} must be a string", key, context)),
+    }
+}
+
+/// Take a non-negative integer out of a table
+fn take_integer(
+    table: &mut Map<String, Value>,
+    key: &str,
+    context: &str,
+) -> Result<Option<u64>, String> {
+    match table.remove(key) {
+        None => Ok(None),
+        Some(value) => value.as_u64().map(Some).ok_or(format!(
+            "{} in {} must be a non-negative integer",
+            key, context
+        )),
+    }
+}
+
+/// Take a number out of a table
+fn take_float(
+    table: &mut Map<String, Value>,
+    key: &str,
+    context: &str,
+) -> Result<Option<f64>, String> {
+    match table.remove(key) {
+        None => Ok(None),
+        Some(value) => value
+            .as_f64()
+            .map(Some)
+            .ok_or(format!("{} in {} must be a number", key, context)),
+    }
+}
+
+/// Fail on the keys left in a table once every known one is taken, which are likely typos
+fn reject_unknown(table: &Map<String, Value>, context: &str) -> Result<(), String> {
+    match table.keys().next() {
+        Some(key) => Err(format!("unknown key '{}' in {}", key, context)),
+        None => Ok(()),
+    }
+}
+
+/// Where the keys of a TOML line go
+enum Section {
+    Root,
+    Table(String),
+    /// The last table of an array of tables
+    ArrayItem(String),
+}
+
+/// Read the subset of TOML that manifests use into a JSON object
+fn parse_toml(text: &str) -> Result<Map<String, Value>, String> {
+    let mut root = Map::new();
+    let mut section = Section::Root;
+    for (i, line) in text.lines().enumerate() {
+        let error = |e: String| format!("line {}: {}", i + 1, e);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(header) = line.strip_prefix("[[") {
+            let name = header_name(header, "]]").map_err(error)?;
+            match root
+                .entry(name.clone())
+                .or_insert_with(|| Value::Array(vec![]))
+            {
+                Value::Array(items) => items.push(Value::Object(Map::new())),
+                _ => return Err(error(format!("{} is not an array of tables", name))),
+            }
+            section = Section::ArrayItem(name);
+            continue;
+        }
+        if let Some(header) = line.strip_prefix('[') {
+            let name = header_name(header, "]").map_err(error)?;
+            if root.contains_key(&name) {
+                return Err(error(format!("{} is defined twice", name)));
+            }
+            root.insert(name.clone(), Value::Object(Map::new()));
+            section = Section::Table(name);
+            continue;
+        }
+
+        let (key, rest) = line
+            .split_once('=')
+            .ok_or_else(|| error(format!("expected 'key = value', found '{}'", line)))?;
+        let key = key.trim();
+        let key = match key.strip_prefix('"').and_then(|k| k.strip_suffix('"')) {
+            Some(quoted) => quoted,
+            None if is_bare_key(key) => key,
+            None => return Err(error(format!("'{}' is not a key", key))),
+        };
+        let value = parse_value(rest.trim()).map_err(error)?;
+        let table = match &section {
+            Section::Root => &mut root,
+            Section::Table(name) => match root.get_mut(name) {
+                Some(Value::Object(table)) => table,
+                _ => unreachable!("The section's table was just made"),
+            },
+            Section::ArrayItem(name) => match root.get_mut(name) {
+                Some(Value::Array(items)) => match items.last_mut() {
+                    Some(Value::Object(table)) => table,
+                    _ => unreachable!("The section's table was just made"),
+                },
+                _ => unreachable!("The section's array was just made"),
+            },
+        };
+        if table.insert(key.to_string(), value).is_some() {
+            return Err(error(format!("{} is set twice", key)));
+        }
+    }
+    Ok(root)
+}
+
+/// The name of a table header, given what follows its opening bracket
+fn header_name(header: &str, close: &str) -> Result<String, String> {
+    let (name, rest) = header
+        .split_once(close)
+        .ok_or_else(|| format!("the header '{}' is not closed", header))?;
+    let rest = rest.trim();
+    let name = name.trim();
+    if !rest.is_empty() && !rest.starts_with('#') {
+        return Err(format!("unexpected '{}' after the header", rest));
+    }
+    match is_bare_key(name) {
+        true => Ok(name.to_string()),
+        false => Err(format!(
+            "'{}' is not a table name; nested tables are not supported",
+            name
+        )),
+    }
+}
+
+fn is_bare_key(key: &str) -> bool {
+    !key.is_empty()
+        && key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Read a string, integer, float or boolean, and the comment after it if there is one
+fn parse_value(text: &str) -> Result<Value, String> {
+    let (value, rest) = match text.strip_prefix('"') {
+        Some(quoted) => {
+            let mut value = String::new();
+            let mut chars = quoted.char_indices();
+            let end = loop {
+                match chars.next() {
+                    Some((i, '"')) => break i + 1,
+                    Some((_, '\\')) => match chars.next() {
+                        Some((_, '"')) => value.push('"'),
+                        Some((_, '\\')) => value.push('\\'),
+                        Some((_, 'n')) => value.push('\n'),
+                        Some((_, 't')) => value.push('\t'),
+                        Some((_, c)) => return Err(format!("unknown escape '\\{}'", c)),
+                        None => return Err("the string is not closed".to_string()),
+                    },
+                    Some((_, c)) => value.push(c),
+                    None => return Err("the string is not closed".to_string()),
+                }
+            };
+            (Value::String(value), &quoted[end..])
+        }
+        None => {
+            let end = text.find('#').unwrap_or(text.len());
+            let (token, rest) = text.split_at(end);
+            let token = token.trim();
+            let number = token.replace('_', "");
+            let value = match token {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => {
+                    if let Ok(n) = number.parse::<u64>() {
+                        json!(n)
+                    } else if let Ok(n) = number.parse::<i64>() {
+                        json!(n)
+                    } else {
+                        match number.parse::<f64>() {
+                            Ok(x) if x.is_finite() => json!(x),
+                            _ => {
+                                return Err(format!(
+                                    "'{}' is not a string, number or boolean",
+                                    token
+                                ))
+                            }
+                        }
+                    }
+                }
+            };
+            (value, rest)
+        }
+    };
+    let rest = rest.trim();
+    match rest.is_empty() || rest.starts_with('#') {
+        true => Ok(value),
+        false => Err(format!("unexpected '{}' after the value", rest)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"
+# Entropy against a noisy minimax
+name = "entropy-vs-minimax"
+seed = 7
+
+[words]
+guesses = "allowed.txt"  # every guess
+guesses_hash = "8b2b3c265e02b0f3"
+
+[[strategies]]
+strategy = "entropy"
+
+[[strategies]]
+name = "noisy # minimax"
+objective = "min-worst"
+noise = 0.05
+seed = 1_000
+"#;
+
+    #[test]
+    fn manifests_are_read_with_defaults() {
+        let manifest = Manifest::parse(MANIFEST, Path::new("runs/exp.toml")).unwrap();
+        assert_eq!(manifest.name, "entropy-vs-minimax");
+        assert_eq!(
+            (manifest.seed, manifest.word_size, manifest.max_attempts),
+            (7, 5, 6)
+        );
+        assert_eq!(manifest.guesses.path, Path::new("runs/allowed.txt"));
+        assert_eq!(manifest.guesses.hash, Some(0x8b2b3c265e02b0f3));
+        assert!(manifest.answers.is_none() && manifest.output.is_none());
+
+        let entries = &manifest.entries;
+        assert_eq!(entries.len(), 2);
+        assert_eq!(
+            (entries[0].name.as_str(), entries[0].kind),
+            ("entropy", StrategyKind::Entropy)
+        );
+        assert_eq!((entries[0].noise, entries[0].seed), (0.0, 7));
+        assert_eq!(entries[1].name, "noisy # minimax");
+        assert_eq!(entries[1].kind, StrategyKind::Minimax);
+        assert_eq!((entries[1].noise, entries[1].seed), (0.05, 1000));
+    }
+
+    #[test]
+    fn mistakes_in_manifests_are_reported() {
+        let error = |text: &str| Manifest::parse(text, Path::new("exp.toml")).unwrap_err();
+        let seedless = MANIFEST.replace("seed = 7", "");
+        assert!(error(&seedless).contains("no seed"));
+        let typo = MANIFEST.replace("noise = 0.05", "nosie = 0.05");
+        assert_eq!(error(&typo), "unknown key 'nosie' in strategy 2");
+        let twice = format!("{}[[strategies]]\nstrategy = \"entropy\"\n", MANIFEST);
+        assert!(error(&twice).contains("two strategies are named 'entropy'"));
+        let unknown = MANIFEST.replace("\"entropy\"", "\"guessing\"");
+        assert!(error(&unknown).starts_with("unknown strategy 'guessing'"));
+        assert_eq!(error("seed = 7\nseed = 8"), "line 2: seed is set twice");
+        assert_eq!(
+            error("[words.extra]"),
+            "line 1: 'words.extra' is not a table name; nested tables are not supported"
+        );
+        assert_eq!(error("name = \"open"), "line 1: the string is not closed");
+    }
+
+    #[test]
+    fn word_lists_must_have_their_pinned_hash() {
+        let dir = std::env::temp_dir().join(format!("rudle-experiment-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let list = dir.join("allowed.txt");
+        fs::write(&list, "crane\nslate\n").unwrap();
+        let words = word::load_words(&list, 5).unwrap();
+        let hash = word_list_hash(&words);
+
+        let manifest = |hash: &str| {
+            let text = format!(
+                "seed = 1\n[words]\nguesses = \"allowed.txt\"\n{}\n[[strategies]]\nstrategy = \
+                 \"entropy\"\n",
+                hash
+            );
+            Manifest::parse(&text, &dir.join("exp.toml")).unwrap()
+        };
+        let pinned = manifest(&format!("guesses_hash = \"{:016x}\"", hash));
+        assert_eq!(pinned.load_words().unwrap().answers, words);
+        let wrong = manifest("guesses_hash = \"0000000000000000\"");
+        assert!(wrong
+            .load_words()
+            .unwrap_err()
+            .contains("but the manifest pins"));
+        let unpinned = manifest("");
+        assert!(unpinned
+            .load_words()
+            .unwrap_err()
+            .contains(&format!("add guesses_hash = \"{:016x}\"", hash)));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,213 @@
+//! Decision trees written for visualization and other tools
+
+use super::bench::choose_opener;
+use super::{input, make_rng, print_stage};
+use crate::{CommonArgs, ExportTreeArgs};
+use rand::Rng;
+use rudle::optimal::OptimalSolver;
+use rudle::strategy::Strategy;
+use rudle::tree::{Branch, DecisionTree};
+use rudle::{Hint, Word, WordLists};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+/// The version of the JSON tree format, described by `rudle schema tree`
+pub const TREE_VERSION: u64 = 1;
+
+/// Build the decision tree of a strategy and write it to every output file
+/// An interrupt while the tree is built stops it at the branches being built, and the tree
+/// so far is written, with the rest of its branches given up on.
+pub fn export_tree(words: WordLists, common: &CommonArgs, options: &ExportTreeArgs) {
+    if words.answers.is_empty() {
+        println!("The answer list is empty.");
+        return;
+    }
+    let show_progress = !common.deterministic;
+
+    let (strategy, opener, name): (Box<dyn Strategy>, _, _) = if options.optimal {
+        print_stage(1, 3, "Searching for the optimal tree");
+        let Some(mut solver) = OptimalSolver::new(
+            &words.guesses,
+            &words.answers,
+            options.max_attempts,
+            show_progress,
+        ) else {
+            println!("The word lists are too large to search.");
+            return;
+        };
+        let Some(solution) = solver.solve(show_progress) else {
+            println!(
+                "Some answers cannot be solved within {} guesses.",
+                options.max_attempts
+            );
+            return;
+        };
+        (Box::new(solver), solution.opener, "optimal".to_string())
+    } else {
+        print_stage(1, 3, "Choosing the opener");
+        let strategy = options.strategy.build(make_rng(common).gen());
+        let opener = choose_opener(options.strategy, strategy.as_ref(), &words, common)
+            .expect("No guesses to play");
+        (strategy, opener, options.strategy.to_string())
+    };
+
+    print_stage(2, 3, "Building the tree");
+    input::stop_between_steps();
+    let (tree, complete) = DecisionTree::build_until(
+        strategy.as_ref(),
+        &words.guesses,
+        &words.answers,
+        &opener,
+        options.max_attempts,
+        show_progress,
+        &input::is_interrupted,
+    );
+    if !complete {
+        println!("Interrupted, so the branches not built yet are given up on.");
+    }
+    let (total, n_failed) = tree.total_guesses();
+    let n_solved = words.answers.len() - n_failed;
+    println!(
+        "The {} tree opens with {} and needs {:.3} guesses on average, failing {} answers.",
+        name,
+        tree.guess,
+        total as f32 / n_solved.max(1) as f32,
+        n_failed
+    );
+
+    print_stage(3, 3, "Writing the tree");
+    for output in options.outputs.iter() {
+        let path = Path::new(output);
+        let is_dot = path
+            .extension()
+            .is_some_and(|ext| ext == "dot" || ext == "gv");
+        let contents = if is_dot {
+            render_dot(&tree)
+        } else {
+            let mut document = json!({
+                "version": TREE_VERSION,
+                "strategy": name,
+                "max_attempts": options.max_attempts,
+                "tree": node_json(&tree),
+            });
+            if !complete {
+                document["complete"] = json!(false);
+            }
+            format!("{:#}\n", document)
+        };
+        match fs::write(path, contents) {
+            Ok(()) => println!("Wrote {}", output),
+            Err(e) => println!("Warning: failed to write {}: {}", output, e),
+        }
+    }
+}
+
+fn node_json(tree: &DecisionTree) -> Value {
+    let branches: Vec<Value> = tree
+        .branches
+        .iter()
+        .map(|branch| {
+            json!({
+                "hint": branch.hint.to_pattern(&tree.guess),
+                "answers": branch.n_answers,
+                "next": branch.next.as_ref().map(node_json),
+            })
+        })
+        .collect();
+    json!({
+        "guess": tree.guess.to_string(),
+        "answers": tree.n_answers,
+        "solves": tree.solves(),
+        "branches": branches,
+    })
+}
+
+/// Write the tree as a Graphviz digraph, with one node per guess labeled with the number
+/// of answers left and one edge per hint
+fn render_dot(tree: &DecisionTree) -> String {
+    let mut dot = String::from("digraph rudle {\n    node [shape=box, fontname=monospace];\n");
+    let mut next_id = 0;
+    add_dot_node(tree, &mut dot, &mut next_id);
+    dot.push_str("}\n");
+    dot
+}
+
+/// Add a node and everything below it, returning the node's id
+fn add_dot_node(tree: &DecisionTree, dot: &mut String, next_id: &mut usize) -> usize {
+    let id = *next_id;
+    *next_id += 1;
+    let peripheries = if tree.solves() { 2 } else { 1 };
+    dot.push_str(&format!(
+        "    n{} [label=\"{}\\n{}\", peripheries={}];\n",
+        id, tree.guess, tree.n_answers, peripheries
+    ));
+
+    for branch in tree.branches.iter() {
+        let child = match &branch.next {
+            Some(next) => add_dot_node(next, dot, next_id),
+            None => {
+                let child = *next_id;
+                *next_id += 1;
+                dot.push_str(&format!(
+                    "    n{} [label=\"X\\n{}\", color=red];\n",
+                    child, branch.n_answers
+                ));
+                child
+            }
+        };
+        dot.push_str(&format!(
+            "    n{} -> n{} [label=\"{}\"];\n",
+            id,
+            child,
+            branch.hint.to_pattern(&tree.guess)
+        ));
+    }
+    id
+}
+
+/// Read a decision tree written by `export_tree`
+pub fn load_tree(path: &str) -> Result<DecisionTree, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let document: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    match document["version"].as_u64() {
+        Some(TREE_VERSION) => {}
+        Some(version) => return Err(format!("unsupported tree version {}", version)),
+        None => return Err("not a decision tree".to_string()),
+    }
+    parse_node(&document["tree"])
+}
+
+fn parse_node(node: &Value) -> Result<DecisionTree, String> {
+    let guess = node["guess"].as_str().ok_or("a node has no guess")?;
+    let guess = Word::from_string(guess).map_err(|e| e.to_string())?;
+    let n_answers = node["answers"]
+        .as_u64()
+        .ok_or("a node has no answer count")? as usize;
+    let branches = node["branches"]
+        .as_array()
+        .ok_or("a node has no branches")?
+        .iter()
+        .map(|branch| {
+            let hint = branch["hint"].as_str().ok_or("a branch has no hint")?;
+            let hint = Hint::from_string(hint, &guess).map_err(|e| e.to_string())?;
+            let n_answers = branch["answers"]
+                .as_u64()
+                .ok_or("a branch has no answer count")? as usize;
+            let next = match &branch["next"] {
+                Value::Null => None,
+                next => Some(parse_node(next)?),
+            };
+            Ok(Branch {
+                hint,
+                n_answers,
+                next,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(DecisionTree {
+        guess,
+        n_answers,
+        branches,
+    })
+}
@@ -0,0 +1,67 @@
+//! Filters that narrow a word list down by its letters, shared by `fit` and the solver REPL
+
+use regex::RegexBuilder;
+use rudle::Word;
+
+/// Whether a word passes a filter
+pub type WordFilter = dyn Fn(&Word) -> bool;
+
+/// Build a filter from its kind ("regex", "contains" or "excludes") and argument
+/// # Returns
+/// The description of the filter, which builds the same filter again, and its test
+pub fn make_filter(kind: &str, argument: &str) -> Result<(String, Box<WordFilter>), String> {
+    match kind {
+        "regex" => {
+            let regex = RegexBuilder::new(argument)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| format!("invalid pattern: {}", e))?;
+            Ok((
+                format!("regex {}", argument),
+                Box::new(move |w: &Word| regex.is_match(&w.to_string())),
+            ))
+        }
+        "contains" => {
+            let letters = parse_letters(argument);
+            Ok((
+                format!("contains {}", letters.iter().collect::<String>()),
+                Box::new(move |w: &Word| letters.iter().all(|&c| w.iter().any(|l| l == c))),
+            ))
+        }
+        "excludes" => {
+            let letters = parse_letters(argument);
+            Ok((
+                format!("excludes {}", letters.iter().collect::<String>()),
+                Box::new(move |w: &Word| !letters.iter().any(|&c| w.iter().any(|l| l == c))),
+            ))
+        }
+        _ => Err(format!("unknown filter '{}'", kind)),
+    }
+}
+
+/// Turn a crossword pattern such as "c_a__e" into the regular expression of a `regex` filter
+/// Letters stand for themselves and "_", "?" or "." for any letter.
+pub fn pattern_regex(pattern: &str) -> Result<String, String> {
+    let mut regex = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '_' | '?' | '.' => regex.push('.'),
+            c if c.is_ascii_alphabetic() => regex.push(c.to_ascii_uppercase()),
+            c => return Err(format!("invalid character '{}' in pattern", c)),
+        }
+    }
+    regex.push('$');
+    Ok(regex)
+}
+
+/// Read the letters of a filter such as "a,e", in the uppercase that words are stored in
+fn parse_letters(letters: &str) -> Vec<char> {
+    let mut letters: Vec<char> = letters
+        .chars()
+        .filter(|c| c.is_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    letters.sort();
+    letters.dedup();
+    letters
+}
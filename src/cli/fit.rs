@@ -0,0 +1,48 @@
+use super::filter::{make_filter, pattern_regex};
+use crate::FitArgs;
+use rudle::Word;
+
+/// Print every word that fits a crossword pattern and the letter constraints
+///
+/// The word list is loaded with the length of the pattern, so any list of words works,
+/// not only the guesses of a game.
+pub fn fit(words: Vec<Word>, options: &FitArgs) {
+    let regex = match pattern_regex(&options.pattern) {
+        Ok(regex) => regex,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+
+    let mut filters = vec![("regex", regex.as_str())];
+    if let Some(letters) = &options.contains {
+        filters.push(("contains", letters));
+    }
+    if let Some(letters) = &options.excludes {
+        filters.push(("excludes", letters));
+    }
+
+    let mut matches = words;
+    for (kind, argument) in filters {
+        let (_, keep) = match make_filter(kind, argument) {
+            Ok(filter) => filter,
+            Err(e) => {
+                println!("Error: {}", e);
+                return;
+            }
+        };
+        matches.retain(|w| keep(w));
+    }
+    matches.sort();
+    let matches: Vec<String> = matches.iter().map(|w| w.to_string()).collect();
+
+    match matches.len() {
+        0 => println!("No words fit {}.", options.pattern.to_uppercase()),
+        1 => println!("1 word fits {}:", options.pattern.to_uppercase()),
+        n => println!("{} words fit {}:", n, options.pattern.to_uppercase()),
+    }
+    for line in matches.chunks(10) {
+        println!("{}", line.join(" "));
+    }
+}
@@ -0,0 +1,505 @@
+//! Grading games against the solver, one game or many at a time
+
+use super::cache::opening_scores;
+use super::render::print_table;
+use super::stats::{load_games, parse_shared_games, GameEnd, GameRecord};
+use super::{current_day, format_day, parse_month};
+use crate::{CommonArgs, GradeArgs};
+use rudle::solver::{best_guess, get_scores};
+use rudle::strategy::{auto_play, Entropy};
+use rudle::{Hint, LetterHint, Word, WordLists};
+use std::fs;
+use std::path::Path;
+
+/// The days of stored games graded when neither a directory nor a month is given
+const RECENT_DAYS: u64 = 30;
+
+/// How well a guess was played
+pub struct TurnGrade {
+    /// The answers still possible before the guess, and after its hint
+    pub n_before: usize,
+    pub n_after: usize,
+    /// How much of the answers the guess was expected to rule out, as a share of what the
+    /// best guess was expected to, from 0 to 1
+    pub efficiency: f64,
+}
+
+/// How well a game was played
+pub struct GameGrade {
+    /// The day since the Unix epoch the game was played on
+    pub day: u64,
+    pub turns: Vec<TurnGrade>,
+    /// The attempts the game was solved in, or None if it was lost
+    pub result: Option<usize>,
+    /// The guesses the solver needed for the same answer, if the answer is known and in the
+    /// answer list
+    pub solver_guesses: Option<usize>,
+}
+
+impl GameGrade {
+    /// The average efficiency of the guesses
+    pub fn efficiency(&self) -> f64 {
+        match self.turns.is_empty() {
+            true => 0.0,
+            false => {
+                self.turns.iter().map(|turn| turn.efficiency).sum::<f64>() / self.turns.len() as f64
+            }
+        }
+    }
+
+    /// How the result compares to the solver's, from 0 for a lost game to 1 for one solved
+    /// in as few guesses as the solver needs
+    pub fn par(&self) -> Option<f64> {
+        match (self.result, self.solver_guesses) {
+            (None, _) => Some(0.0),
+            (Some(n), Some(solver)) => Some((solver as f64 / n as f64).min(1.0)),
+            (Some(_), None) => None,
+        }
+    }
+
+    /// The grade of the game from 0 to 1: the average of the efficiency and the par, or the
+    /// efficiency alone if the solver's result is not known
+    pub fn score(&self) -> f64 {
+        match self.par() {
+            Some(par) => (self.efficiency() + par) / 2.0,
+            None => self.efficiency(),
+        }
+    }
+}
+
+/// Grades games with the solver's scores and results
+pub struct Grader<'a> {
+    words: &'a WordLists,
+    opening_scores: Vec<(Word, f32, f32)>,
+    opener: Option<Word>,
+    max_attempts: usize,
+}
+
+impl<'a> Grader<'a> {
+    pub fn new(words: &'a WordLists, common: &CommonArgs, max_attempts: usize) -> Self {
+        let opening_scores = opening_scores(words, common);
+        let opener = best_guess(&opening_scores, &words.answers).cloned();
+        Self {
+            words,
+            opening_scores,
+            opener,
+            max_attempts,
+        }
+    }
+
+    /// Grade a game from its guesses and hints
+    /// # Arguments
+    /// * `result` - The attempts the game was solved in, which counts reveals, or None if it
+    ///   was lost
+    /// * `secret` - The secret word of a lost game, if it is known
+    /// # Returns
+    /// The grade, or why the game cannot be graded
+    pub fn grade(
+        &self,
+        day: u64,
+        board: &[(Word, Hint)],
+        result: Option<usize>,
+        secret: Option<&Word>,
+    ) -> Result<GameGrade, String> {
+        let mut answers = self.words.answers.clone();
+        let mut turns = vec![];
+        for (i, (guess, hint)) in board.iter().enumerate() {
+            let n_before = answers.len();
+            let efficiency = match n_before {
+                1 => f64::from(u8::from(answers[0] == *guess)),
+                _ => {
+                    let later_scores;
+                    let scores = match i {
+                        0 => &self.opening_scores,
+                        _ => {
+                            later_scores = get_scores(&self.words.guesses, &answers, false);
+                            &later_scores
+                        }
+                    };
+                    let best = scores.first().map_or(0.0, |(_, score, _)| *score);
+                    let score = match scores.iter().find(|(w, _, _)| w == guess) {
+                        Some((_, score, _)) => *score,
+                        None => get_scores(std::slice::from_ref(guess), &answers, false)[0].1,
+                    };
+                    match best > 0.0 {
+                        true => (score / best).clamp(0.0, 1.0) as f64,
+                        false => 1.0,
+                    }
+                }
+            };
+            answers.retain(|w| Hint::from_guess_and_answer(guess, w).as_ref() == Ok(hint));
+            let solved = hint.iter().all(|&h| h == LetterHint::Correct);
+            if answers.is_empty() && !solved {
+                return Err(format!(
+                    "the hint of {} rules out every answer in the list",
+                    guess
+                ));
+            }
+            turns.push(TurnGrade {
+                n_before,
+                n_after: if solved { 0 } else { answers.len() },
+                efficiency,
+            });
+        }
+        if turns.is_empty() {
+            return Err("no guesses were played".to_string());
+        }
+
+        let secret = match result {
+            Some(_) => board.last().map(|(guess, _)| guess),
+            None => secret,
+        };
+        let solver_guesses = match (secret, &self.opener) {
+            (Some(secret), Some(opener)) if self.words.answers.binary_search(secret).is_ok() => {
+                let history = auto_play(
+                    &Entropy,
+                    &self.words.guesses,
+                    &self.words.answers,
+                    secret,
+                    opener,
+                    self.max_attempts,
+                );
+                let solved = history.last().is_some_and(|(guess, _, _)| guess == secret);
+                solved.then_some(history.len())
+            }
+            _ => None,
+        };
+        Ok(GameGrade {
+            day,
+            turns,
+            result,
+            solver_guesses,
+        })
+    }
+
+    /// Grade a stored game, whose hints come from its secret word
+    pub fn grade_record(&self, game: &GameRecord) -> Result<GameGrade, String> {
+        if game.end == GameEnd::Quit {
+            return Err("the game was quit before it ended".to_string());
+        }
+        let secret = game.secret.as_ref().ok_or("the secret word is not known")?;
+        let board = game
+            .guesses
+            .iter()
+            .map(|guess| {
+                Hint::from_guess_and_answer(guess, secret)
+                    .map(|hint| (guess.clone(), hint))
+                    .map_err(|e| e.to_string())
+            })
+            .collect::<Result<Vec<(Word, Hint)>, String>>()?;
+        self.grade(game.day, &board, game.result, Some(secret))
+    }
+}
+
+/// Grade the games of a directory of transcripts, of a month of stored games or of the last
+/// 30 days of stored games, and report on them together
+pub fn grade(words: WordLists, common: &CommonArgs, options: &GradeArgs) {
+    if words.answers.is_empty() {
+        println!("The answer list is empty.");
+        return;
+    }
+    let (first, next) = match &options.month {
+        Some(month) => match parse_month(month) {
+            Ok(days) => days,
+            Err(e) => {
+                println!("{}", e);
+                return;
+            }
+        },
+        None => (current_day().saturating_sub(RECENT_DAYS - 1), u64::MAX),
+    };
+    let grader = Grader::new(&words, common, options.max_attempts);
+    let (source, graded) = match (&options.batch, &options.month) {
+        (Some(dir), _) => match grade_directory(&grader, Path::new(dir), common.word_size) {
+            Ok(graded) => (dir.clone(), graded),
+            Err(e) => {
+                println!("Warning: could not read {}: {}", dir, e);
+                return;
+            }
+        },
+        (None, month) => {
+            let source = match month {
+                Some(month) => format!("the games played in {}", month),
+                None => format!("the games of the last {} days", RECENT_DAYS),
+            };
+            let graded = load_games()
+                .iter()
+                .filter(|game| (first..next).contains(&game.day))
+                .filter(|game| {
+                    game.guesses
+                        .first()
+                        .is_some_and(|w| w.len() == common.word_size)
+                })
+                .map(|game| {
+                    let name = format!("the game of {}", format_day(game.day));
+                    (name, grader.grade_record(game))
+                })
+                .collect();
+            (source, graded)
+        }
+    };
+
+    let mut grades = vec![];
+    for (name, grade) in graded {
+        match grade {
+            Ok(grade) => grades.push(grade),
+            Err(e) => println!("Warning: Skipped {}: {}", name, e),
+        }
+    }
+    if grades.is_empty() {
+        println!(
+            "No {}-letter games to grade in {}.",
+            common.word_size, source
+        );
+        return;
+    }
+    grades.sort_by_key(|grade| grade.day);
+    println!("Graded {} games from {}.\n", grades.len(), source);
+    print_report(&grades);
+}
+
+/// Grade every game in the files of a directory, which hold stored games as `DAY SECRET
+/// RESULT GUESS...` lines, or share text whose rows name their guesses
+/// # Returns
+/// The name of each game in messages, and its grade or why it cannot be graded
+fn grade_directory(
+    grader: &Grader,
+    dir: &Path,
+    word_size: usize,
+) -> std::io::Result<Vec<(String, Result<GameGrade, String>)>> {
+    let mut paths: Vec<_> = fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    let mut graded = vec![];
+    for path in paths.iter() {
+        let Ok(text) = fs::read_to_string(path) else {
+            continue;
+        };
+        let file = path
+            .file_name()
+            .map_or(String::new(), |name| name.to_string_lossy().into_owned());
+        for (i, line) in text.lines().enumerate() {
+            let Some(game) = GameRecord::from_line(line) else {
+                continue;
+            };
+            if game.guesses.first().is_some_and(|w| w.len() == word_size) {
+                let name = format!("{} line {}", file, i + 1);
+                graded.push((name, grader.grade_record(&game)));
+            }
+        }
+        for game in parse_shared_games(&text, word_size) {
+            let name = format!("'{}' in {}", game.title, file);
+            let board: Option<Vec<(Word, Hint)>> = game
+                .rows
+                .iter()
+                .map(|(guess, hint)| guess.clone().map(|guess| (guess, hint.clone())))
+                .collect();
+            let grade = match board {
+                Some(board) => grader.grade(game.day, &board, game.result, None),
+                None => Err("the grid does not name the guess of every row".to_string()),
+            };
+            graded.push((name, grade));
+        }
+    }
+    Ok(graded)
+}
+
+/// Print the results of the games, the averages of each turn and the trend by week
+fn print_report(grades: &[GameGrade]) {
+    let n_games = grades.len();
+    let won: Vec<&GameGrade> = grades.iter().filter(|g| g.result.is_some()).collect();
+    let average = |values: &mut dyn Iterator<Item = f64>| {
+        let (sum, n) = values.fold((0.0, 0), |(sum, n), value| (sum + value, n + 1));
+        (n > 0).then(|| sum / n as f64)
+    };
+    println!(
+        "Won {} of {} ({:.1}%).",
+        won.len(),
+        n_games,
+        won.len() as f64 * 100.0 / n_games as f64
+    );
+    if let Some(guesses) = average(&mut won.iter().filter_map(|g| g.result.map(|n| n as f64))) {
+        println!("Average: {:.3} guesses over the games won.", guesses);
+    }
+    let behind: Vec<f64> = won
+        .iter()
+        .filter_map(|g| Some(g.result? as f64 - g.solver_guesses? as f64))
+        .collect();
+    if let Some(behind) = average(&mut behind.iter().copied()) {
+        println!(
+            "{:+.3} guesses a game won, compared with the solver on the same answers.",
+            behind
+        );
+    }
+    println!(
+        "Efficiency: {:.1}% of what the best guess rules out, grade {:.1}%.\n",
+        average(&mut grades.iter().map(GameGrade::efficiency)).unwrap_or(0.0) * 100.0,
+        average(&mut grades.iter().map(GameGrade::score)).unwrap_or(0.0) * 100.0
+    );
+
+    let n_turns = grades.iter().map(|g| g.turns.len()).max().unwrap_or(0);
+    let rows: Vec<Vec<String>> = (0..n_turns)
+        .map(|i| {
+            let turns: Vec<&TurnGrade> = grades.iter().filter_map(|g| g.turns.get(i)).collect();
+            let mean = |f: fn(&TurnGrade) -> f64| {
+                turns.iter().map(|&turn| f(turn)).sum::<f64>() / turns.len() as f64
+            };
+            vec![
+                (i + 1).to_string(),
+                turns.len().to_string(),
+                format!("{:.1}", mean(|turn| turn.n_before as f64)),
+                format!("{:.1}", mean(|turn| turn.n_after as f64)),
+                format!("{:.1}%", mean(|turn| turn.efficiency) * 100.0),
+            ]
+        })
+        .collect();
+    println!("By turn:");
+    print_table(
+        &[
+            "Turn",
+            "Games",
+            "Answers before",
+            "Answers after",
+            "Efficiency",
+        ],
+        &rows,
+    );
+
+    // Weeks start on Monday, and the Unix epoch was a Thursday
+    let week_of = |day: u64| day - (day + 3) % 7;
+    let mut weeks: Vec<(u64, Vec<&GameGrade>)> = vec![];
+    for grade in grades.iter() {
+        let week = week_of(grade.day);
+        match weeks.last_mut() {
+            Some((last, games)) if *last == week => games.push(grade),
+            _ => weeks.push((week, vec![grade])),
+        }
+    }
+    let rows: Vec<Vec<String>> = weeks
+        .iter()
+        .map(|(week, games)| {
+            let won: Vec<f64> = games
+                .iter()
+                .filter_map(|g| g.result.map(|n| n as f64))
+                .collect();
+            vec![
+                format_day(*week),
+                games.len().to_string(),
+                won.len().to_string(),
+                average(&mut won.iter().copied()).map_or("-".to_string(), |n| format!("{:.3}", n)),
+                format!(
+                    "{:.1}%",
+                    average(&mut games.iter().map(|g| g.efficiency())).unwrap_or(0.0) * 100.0
+                ),
+                format!(
+                    "{:.1}%",
+                    average(&mut games.iter().map(|g| g.score())).unwrap_or(0.0) * 100.0
+                ),
+            ]
+        })
+        .collect();
+    println!("\nBy week:");
+    print_table(
+        &["Week of", "Games", "Won", "Average", "Efficiency", "Grade"],
+        &rows,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(w: &str) -> Word {
+        Word::from_string(w).unwrap()
+    }
+
+    fn row(guess: &str, answer: &str) -> (Word, Hint) {
+        let hint = Hint::from_guess_and_answer(&word(guess), &word(answer)).unwrap();
+        (word(guess), hint)
+    }
+
+    fn words() -> WordLists {
+        let words = ["BRICK", "CRANE", "GRATE", "PLUMB", "SLATE", "TRACE"].map(word);
+        WordLists::single(words.to_vec())
+    }
+
+    fn grader(words: &WordLists) -> Grader<'_> {
+        let opening_scores = get_scores(&words.guesses, &words.answers, false);
+        let opener = best_guess(&opening_scores, &words.answers).cloned();
+        Grader {
+            words,
+            opening_scores,
+            opener,
+            max_attempts: 6,
+        }
+    }
+
+    #[test]
+    fn the_best_guesses_get_full_marks() {
+        let words = words();
+        let grader = grader(&words);
+        let opener = grader.opener.clone().unwrap();
+        let secret = "PLUMB";
+        let history = auto_play(
+            &Entropy,
+            &words.guesses,
+            &words.answers,
+            &word(secret),
+            &opener,
+            6,
+        );
+        let board: Vec<(Word, Hint)> = history
+            .iter()
+            .map(|(w, _, _)| row(&w.to_string(), secret))
+            .collect();
+
+        let grade = grader.grade(3, &board, Some(board.len()), None).unwrap();
+        assert_eq!(grade.solver_guesses, Some(board.len()));
+        assert_eq!(grade.turns[0].n_before, 6);
+        assert_eq!(grade.turns.last().unwrap().n_after, 0);
+        assert!(grade.turns.iter().all(|turn| turn.efficiency > 0.999));
+        assert!(grade.score() > 0.999);
+    }
+
+    #[test]
+    fn wasted_and_lost_games_score_lower() {
+        let words = words();
+        let grader = grader(&words);
+        // Guessing PLUMB when only TRACE is left wastes the turn
+        let board = [
+            row("CRANE", "TRACE"),
+            row("PLUMB", "TRACE"),
+            row("TRACE", "TRACE"),
+        ];
+        let wasted = grader.grade(0, &board, Some(3), None).unwrap();
+        assert_eq!(wasted.turns[1].n_before, 1);
+        assert_eq!(wasted.turns[1].efficiency, 0.0);
+        assert!(wasted.par().unwrap() < 1.0);
+
+        let lost = grader
+            .grade(0, &board[..2], None, Some(&word("TRACE")))
+            .unwrap();
+        assert_eq!(lost.par(), Some(0.0));
+        assert!(lost.score() < wasted.score());
+    }
+
+    #[test]
+    fn stored_games_are_graded_from_their_secret() {
+        let words = words();
+        let grader = grader(&words);
+        let game = GameRecord::from_line("20000 TRACE 2 CRANE TRACE").unwrap();
+        let grade = grader.grade_record(&game).unwrap();
+        assert_eq!(
+            (grade.day, grade.result, grade.turns.len()),
+            (20000, Some(2), 2)
+        );
+
+        let unknown = GameRecord::from_line("20000 ? 2 CRANE TRACE").unwrap();
+        assert!(grader.grade_record(&unknown).is_err());
+        let quit = GameRecord::from_line("20000 TRACE Q CRANE").unwrap();
+        assert!(grader.grade_record(&quit).is_err());
+    }
+}
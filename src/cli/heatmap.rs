@@ -0,0 +1,123 @@
+//! Grids of how often each letter is at each position among the remaining answers
+
+use super::render::{print_table, truecolor};
+use colored::*;
+use rudle::Word;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+
+/// The color of a cell no answer has, and of a cell every answer has
+const COLD: (u8, u8, u8) = (40, 40, 40);
+const HOT: (u8, u8, u8) = (106, 170, 100);
+
+/// The number of answers with each letter at each position, for every letter in the answers
+fn letter_counts(answers: &[Word], word_size: usize) -> BTreeMap<char, Vec<usize>> {
+    let mut counts: BTreeMap<char, Vec<usize>> = BTreeMap::new();
+    for word in answers.iter() {
+        for (position, c) in word.iter().enumerate().take(word_size) {
+            counts.entry(c).or_insert_with(|| vec![0; word_size])[position] += 1;
+        }
+    }
+    counts
+}
+
+/// The color of a cell for the share of the answers that have its letter at its position
+fn shade(share: f32) -> (u8, u8, u8) {
+    let mix = |cold: u8, hot: u8| (cold as f32 + (hot as f32 - cold as f32) * share).round() as u8;
+    (mix(COLD.0, HOT.0), mix(COLD.1, HOT.1), mix(COLD.2, HOT.2))
+}
+
+/// Write a cell's text on the shade of its share, in 24-bit colors or the basic ones
+fn shade_text(text: &str, share: f32, truecolor: bool) -> ColoredString {
+    let text = text.white();
+    match truecolor {
+        true => {
+            let (r, g, b) = shade(share);
+            text.on_truecolor(r, g, b)
+        }
+        false if share == 0.0 => text,
+        false if share < 0.25 => text.on_bright_black(),
+        false if share < 0.5 => text.on_green(),
+        false => text.black().on_bright_green(),
+    }
+}
+
+/// Print a grid of letters by positions, each cell shaded by the share of the answers that
+/// have the letter there
+/// Without colors, the counts are printed as a table in the selected format instead.
+pub fn print_heatmap(answers: &[Word], word_size: usize) {
+    if answers.is_empty() {
+        println!("No possible answers remain.");
+        return;
+    }
+    let counts = letter_counts(answers, word_size);
+    if !colored::control::SHOULD_COLORIZE.should_colorize() {
+        let positions: Vec<String> = (1..=word_size).map(|p| p.to_string()).collect();
+        let mut header = vec!["Letter"];
+        header.extend(positions.iter().map(String::as_str));
+        let rows: Vec<Vec<String>> = counts
+            .iter()
+            .map(|(c, counts)| {
+                let mut row = vec![c.to_string()];
+                row.extend(counts.iter().map(|n| n.to_string()));
+                row
+            })
+            .collect();
+        print_table(&header, &rows);
+        return;
+    }
+
+    println!(
+        "Letters by position over the {} possible answers:",
+        answers.len()
+    );
+    // Terminals without 24-bit colors get a few steps of the basic colors instead
+    let truecolor = truecolor();
+    let header: String = (1..=word_size).map(|p| format!("{:>4} ", p)).collect();
+    println!("  {}", header);
+    for (c, counts) in counts.iter() {
+        let cells: String = counts
+            .iter()
+            .map(|&n| {
+                let share = n as f32 / answers.len() as f32;
+                let text = match n {
+                    0 => "    ".to_string(),
+                    n => format!("{:>4}", n),
+                };
+                format!("{} ", shade_text(&text, share, truecolor))
+            })
+            .collect();
+        println!("{} {}", c, cells);
+    }
+}
+
+/// Write the heatmap as an HTML page, with the cells shaded like the terminal's
+pub fn write_heatmap_html(path: &str, answers: &[Word], word_size: usize) -> io::Result<()> {
+    let counts = letter_counts(answers, word_size);
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>Rudle heatmap</title></head>\n\
+         <body style=\"font-family:monospace\">\n",
+    );
+    html += &format!(
+        "<p>Letters by position over the {} possible answers</p>\n<table>\n<tr><th></th>",
+        answers.len()
+    );
+    for position in 1..=word_size {
+        html += &format!("<th>{}</th>", position);
+    }
+    html += "</tr>\n";
+    for (c, counts) in counts.iter() {
+        html += &format!("<tr><th>{}</th>", c);
+        for &n in counts.iter() {
+            let (r, g, b) = shade(n as f32 / answers.len().max(1) as f32);
+            html += &format!(
+                "<td style=\"background:rgb({},{},{});color:#fff;text-align:right;padding:2px 8px\">{}</td>",
+                r, g, b, n
+            );
+        }
+        html += "</tr>\n";
+    }
+    html += "</table>\n</body>\n</html>\n";
+    fs::write(path, html)
+}
@@ -0,0 +1,199 @@
+//! Reading the lines the player types, with line editing and recall of earlier lines
+
+use rustyline::config::Config;
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+use std::collections::VecDeque;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, OnceLock};
+use std::thread;
+use std::time::Duration;
+
+/// Set when Ctrl-C or SIGTERM asks the program to stop, so every read ends the input and
+/// the game or session finishes as if the input had run out
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Set on the first interrupt and never cleared, so the program exits with status 130
+static WAS_INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Set once a line reader is made, or a long computation checks for interrupts between its
+/// steps, as from then on the program stops at its next read or step
+static STOPS_GRACEFULLY: AtomicBool = AtomicBool::new(false);
+
+/// Set while the line editor waits for a key, which a signal cannot wake it from
+static EDITING: AtomicBool = AtomicBool::new(false);
+
+/// How often a read of piped input checks whether the program was interrupted
+const INTERRUPT_POLL: Duration = Duration::from_millis(50);
+
+/// Ask the program to stop at its next read
+/// # Returns
+/// Whether it had already been asked to
+pub fn interrupt() -> bool {
+    WAS_INTERRUPTED.store(true, Ordering::SeqCst);
+    INTERRUPTED.swap(true, Ordering::SeqCst)
+}
+
+/// Whether the program was asked to stop
+pub fn is_interrupted() -> bool {
+    INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Whether the program was ever asked to stop, even if it went on to ask a last question
+pub fn was_interrupted() -> bool {
+    WAS_INTERRUPTED.load(Ordering::SeqCst)
+}
+
+/// Read lines again after an interrupt, to ask one last question before exiting
+pub fn clear_interrupt() {
+    INTERRUPTED.store(false, Ordering::SeqCst);
+}
+
+/// Whether stopping can wait for the next read or step, rather than needing the program to
+/// exit where it is
+pub fn can_stop_gracefully() -> bool {
+    STOPS_GRACEFULLY.load(Ordering::SeqCst) && !EDITING.load(Ordering::SeqCst)
+}
+
+/// Let an interrupt wait for the current step of a long computation, such as a game of a
+/// benchmark, which checks `is_interrupted` before each step so that it can stop with the
+/// results so far
+pub fn stop_between_steps() {
+    STOPS_GRACEFULLY.store(true, Ordering::SeqCst);
+}
+
+/// The lines of stdin, read on a thread of their own so that waiting for the next one can
+/// stop when the program is interrupted
+fn stdin_lines() -> &'static Mutex<Receiver<String>> {
+    static LINES: OnceLock<Mutex<Receiver<String>>> = OnceLock::new();
+    LINES.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for line in io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Mutex::new(receiver)
+    })
+}
+
+/// A prompt that reads one line at a time
+///
+/// The history only holds the lines added with `add_history`, so each game can recall its
+/// own guesses with the Up arrow without seeing other sessions' input. When the input is
+/// not a terminal, or no line editor can be created, lines are read from stdin as they are.
+pub struct LineReader {
+    editor: Option<DefaultEditor>,
+    /// The lines left in the script being run, if reading from a script
+    script: Option<VecDeque<String>>,
+    show_prompt: bool,
+}
+
+impl LineReader {
+    pub fn new() -> Self {
+        let config = Config::builder().auto_add_history(false).build();
+        let editor = match io::stdin().is_terminal() {
+            true => DefaultEditor::with_config(config).ok(),
+            false => None,
+        };
+        STOPS_GRACEFULLY.store(true, Ordering::SeqCst);
+        Self {
+            editor,
+            script: None,
+            show_prompt: true,
+        }
+    }
+
+    /// Read commands from a script file, or from stdin if no script is given
+    /// Prompts are only shown when a person is typing at a terminal.
+    pub fn for_commands(script: Option<&str>) -> io::Result<Self> {
+        let mut reader = Self::new();
+        if let Some(path) = script {
+            let text = fs::read_to_string(path)?;
+            reader.editor = None;
+            reader.script = Some(text.lines().map(str::to_string).collect());
+        }
+        reader.show_prompt = reader.is_interactive();
+        Ok(reader)
+    }
+
+    /// Whether a person is typing the lines at a terminal
+    pub fn is_interactive(&self) -> bool {
+        self.editor.is_some()
+    }
+
+    /// Show the prompt and read a line, without its line ending
+    /// # Returns
+    /// None at the end of the input, or once the program is interrupted
+    pub fn read_line(&mut self, prompt: &str) -> Option<String> {
+        if is_interrupted() {
+            return None;
+        }
+        if let Some(script) = &mut self.script {
+            return script.pop_front();
+        }
+        let Some(editor) = &mut self.editor else {
+            if self.show_prompt {
+                print!("{}", prompt);
+                io::stdout().flush().unwrap();
+            }
+            let lines = stdin_lines().lock().unwrap();
+            loop {
+                match lines.recv_timeout(INTERRUPT_POLL) {
+                    Ok(line) => return Some(line.trim_end_matches('\r').to_string()),
+                    Err(RecvTimeoutError::Timeout) if !is_interrupted() => {}
+                    Err(_) => return None,
+                }
+            }
+        };
+
+        EDITING.store(true, Ordering::SeqCst);
+        let line = editor.readline(prompt);
+        EDITING.store(false, Ordering::SeqCst);
+        match line {
+            Ok(line) => Some(line),
+            // The editor reads Ctrl-C as a key rather than a signal
+            Err(ReadlineError::Interrupted) => {
+                interrupt();
+                None
+            }
+            Err(_) => None,
+        }
+    }
+
+    /// Make a line recallable with the Up arrow
+    pub fn add_history(&mut self, line: &str) {
+        if let Some(editor) = &mut self.editor {
+            let _ = editor.add_history_entry(line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_end_once_interrupted() {
+        let path = std::env::temp_dir().join(format!("rudle-script-{}", std::process::id()));
+        fs::write(&path, "hint crane _____\ntop\n").unwrap();
+        let mut reader = LineReader::for_commands(path.to_str()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(reader.read_line("> ").as_deref(), Some("hint crane _____"));
+        assert!(!interrupt());
+        assert!(interrupt());
+        assert_eq!(reader.read_line("> "), None);
+        clear_interrupt();
+        assert_eq!(reader.read_line("> ").as_deref(), Some("top"));
+        assert!(was_interrupted());
+    }
+}
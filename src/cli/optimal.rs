@@ -0,0 +1,67 @@
+use super::bench::print_results;
+use crate::{CommonArgs, OptimalArgs};
+use rudle::optimal::OptimalSolver;
+use rudle::strategy::auto_play;
+use rudle::{Word, WordLists};
+use std::time::Instant;
+
+/// Find the policy that needs the fewest guesses on average, then play it against every answer
+pub fn optimal(words: WordLists, common: &CommonArgs, options: &OptimalArgs) {
+    if words.answers.is_empty() {
+        println!("The answer list is empty.");
+        return;
+    }
+
+    let show_progress = !common.deterministic;
+    let Some(mut solver) = OptimalSolver::new(
+        &words.guesses,
+        &words.answers,
+        options.max_attempts,
+        show_progress,
+    ) else {
+        println!("The word lists are too large to search.");
+        return;
+    };
+
+    println!(
+        "Searching every game of {} guesses against {} answers. This can take minutes.",
+        words.guesses.len(),
+        words.answers.len()
+    );
+    let start = Instant::now();
+    let Some(solution) = solver.solve(show_progress) else {
+        println!(
+            "Some answers cannot be solved within {} guesses.",
+            options.max_attempts
+        );
+        return;
+    };
+    if !common.deterministic {
+        println!("Searched in {:.1?}", start.elapsed());
+    }
+    println!("Optimal opener: {}", solution.opener);
+    println!(
+        "Expected guesses: {:.4} ({} over {} answers)",
+        solution.expected_guesses(),
+        solution.total_guesses,
+        solution.n_answers
+    );
+
+    let results: Vec<(&Word, Option<usize>)> = words
+        .answers
+        .iter()
+        .map(|secret| {
+            let history = auto_play(
+                &solver,
+                &words.guesses,
+                &words.answers,
+                secret,
+                &solution.opener,
+                options.max_attempts,
+            );
+            let solved = history.last().is_some_and(|(guess, _, _)| guess == secret);
+            (secret, solved.then_some(history.len()))
+        })
+        .collect();
+    print_results(&results, options.max_attempts);
+}
@@ -0,0 +1,151 @@
+//! Board state written to a file that streaming software such as OBS can display
+
+use rudle::hint::{keyboard_state, FeedbackSymbols};
+use rudle::{Hint, LetterHint, Word};
+use serde_json::json;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// The version of the JSON overlay format, described by `rudle schema overlay`
+pub const OVERLAY_VERSION: u64 = 1;
+
+/// The letters of a QWERTY keyboard, row by row
+pub const KEYBOARD_ROWS: [&str; 3] = ["QWERTYUIOP", "ASDFGHJKL", "ZXCVBNM"];
+
+/// Where a game stands
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum GameStatus {
+    Playing,
+    Won,
+    Lost,
+}
+
+/// A file that is rewritten with the board and keyboard after every guess
+pub struct Overlay {
+    path: PathBuf,
+    json: bool,
+}
+
+impl Overlay {
+    /// Files ending in `.json` get JSON, anything else gets plain text
+    pub fn new(path: &str) -> Self {
+        let path = PathBuf::from(path);
+        let json = path.extension().is_some_and(|ext| ext == "json");
+        Self { path, json }
+    }
+
+    /// Rewrite the overlay file, printing a warning if it cannot be written
+    /// The answer is only included once the game is over.
+    pub fn update(
+        &self,
+        board: &[(Word, Hint)],
+        max_attempts: usize,
+        word_size: usize,
+        status: GameStatus,
+        secret_word: &Word,
+    ) {
+        let contents = if self.json {
+            render_json(board, max_attempts, status, secret_word)
+        } else {
+            render_text(board, max_attempts, word_size, status, secret_word)
+        };
+
+        if let Err(e) = self.write(&contents) {
+            println!("Warning: failed to write overlay file: {}", e);
+        }
+    }
+
+    /// Write through a temporary file so readers never see a half-written board
+    fn write(&self, contents: &str) -> io::Result<()> {
+        let tmp_path = self.path.with_extension("tmp");
+        fs::write(&tmp_path, contents)?;
+        fs::rename(&tmp_path, &self.path)
+    }
+}
+
+/// Write the board and keyboard as plain text, with the answer once the game is lost
+pub fn render_text(
+    board: &[(Word, Hint)],
+    max_attempts: usize,
+    word_size: usize,
+    status: GameStatus,
+    secret_word: &Word,
+) -> String {
+    let symbols = FeedbackSymbols::default();
+    let mut text = match status {
+        GameStatus::Playing => format!("Rudle {}/{}\n\n", board.len(), max_attempts),
+        GameStatus::Won => format!("Rudle {}/{} - Solved!\n\n", board.len(), max_attempts),
+        GameStatus::Lost => format!(
+            "Rudle X/{} - The word was {}\n\n",
+            max_attempts, secret_word
+        ),
+    };
+
+    for (guess, hint) in board.iter() {
+        let squares: String = hint.iter().map(|&h| symbols.symbol(h)).collect();
+        text.push_str(&format!("{} {}\n", guess, squares));
+    }
+    for _ in board.len()..max_attempts {
+        text.push_str(&"_".repeat(word_size));
+        text.push('\n');
+    }
+
+    // Letters known to be absent are blanked out on the keyboard
+    let letters = keyboard_state(board);
+    text.push('\n');
+    for (indent, row) in KEYBOARD_ROWS.iter().enumerate() {
+        let keys: Vec<String> = row
+            .chars()
+            .map(|c| match letters.get(&c) {
+                Some(LetterHint::Incorrect) => "·".to_string(),
+                _ => c.to_string(),
+            })
+            .collect();
+        text.push_str(&format!("{}{}\n", " ".repeat(indent), keys.join(" ")));
+    }
+    text
+}
+
+fn render_json(
+    board: &[(Word, Hint)],
+    max_attempts: usize,
+    status: GameStatus,
+    secret_word: &Word,
+) -> String {
+    let name = |h: &LetterHint| match h {
+        LetterHint::Correct => "correct",
+        LetterHint::Misplaced => "misplaced",
+        LetterHint::Incorrect => "incorrect",
+    };
+
+    let rows: Vec<_> = board
+        .iter()
+        .map(|(guess, hint)| {
+            json!({
+                "guess": guess.to_string(),
+                "hint": hint.iter().map(name).collect::<Vec<_>>(),
+            })
+        })
+        .collect();
+    let keyboard: serde_json::Map<String, serde_json::Value> = keyboard_state(board)
+        .iter()
+        .map(|(c, h)| (c.to_string(), json!(name(h))))
+        .collect();
+    let (status, answer) = match status {
+        GameStatus::Playing => ("playing", None),
+        GameStatus::Won => ("won", Some(secret_word.to_string())),
+        GameStatus::Lost => ("lost", Some(secret_word.to_string())),
+    };
+
+    let state = json!({
+        "version": OVERLAY_VERSION,
+        "status": status,
+        "attempts": board.len(),
+        "max_attempts": max_attempts,
+        "rows": rows,
+        "keyboard": keyboard,
+        "answer": answer,
+    });
+    format!("{:#}\n", state)
+}
@@ -0,0 +1,145 @@
+//! Versioned files in the Rudle data directory
+//!
+//! Every saved file starts with a `# rudle NAME vVERSION` header. Files from older versions
+//! are upgraded one version at a time when they are loaded and saved again in the current
+//! version, so changing a format never strands the data saved by an earlier release.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A file format and the steps that upgrade older versions of it
+pub struct Format {
+    /// The name in the header, which tells the formats apart
+    pub name: &'static str,
+    /// The step upgrading the contents of each version to the next, oldest first
+    /// Version 0 is the headerless format from before files were versioned, so the current
+    /// version is the number of migrations.
+    pub migrations: &'static [fn(&str) -> String],
+}
+
+/// A migration for a version that only added the header
+pub fn unchanged(contents: &str) -> String {
+    contents.to_string()
+}
+
+impl Format {
+    pub fn version(&self) -> usize {
+        self.migrations.len()
+    }
+
+    /// Load a file's contents without its header, upgrading it if it is from an older version
+    /// # Returns
+    /// None if the file is missing or unreadable, or was saved by a newer version of Rudle
+    pub fn load(&self, path: &Path) -> Option<String> {
+        let text = fs::read_to_string(path).ok()?;
+        let (version, contents) = self.parse_header(&text)?;
+        if version > self.version() {
+            println!(
+                "Warning: {} was saved by a newer version of Rudle and is ignored.",
+                path.display()
+            );
+            return None;
+        }
+        if version == self.version() {
+            return Some(contents.to_string());
+        }
+
+        let contents = self.migrations[version..]
+            .iter()
+            .fold(contents.to_string(), |contents, migrate| migrate(&contents));
+        if let Err(e) = self.save(path, &contents) {
+            println!("Warning: failed to upgrade {}: {}", path.display(), e);
+        }
+        Some(contents)
+    }
+
+    /// Save contents with the current header, through a temporary file so that an
+    /// interrupted save never leaves a half-written file
+    /// Files saved by a newer version of Rudle are left alone.
+    pub fn save(&self, path: &Path, contents: &str) -> io::Result<()> {
+        let existing = fs::read_to_string(path).unwrap_or_default();
+        if self
+            .parse_header(&existing)
+            .is_none_or(|(version, _)| version > self.version())
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "the file was saved by a newer version of Rudle",
+            ));
+        }
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let tmp_path = path.with_extension("tmp");
+        let header = format!("# rudle {} v{}\n", self.name, self.version());
+        fs::write(&tmp_path, header + contents)?;
+        fs::rename(&tmp_path, path)
+    }
+
+    /// Split a file into its version and its contents, treating a file without a header
+    /// as version 0
+    fn parse_header<'a>(&self, text: &'a str) -> Option<(usize, &'a str)> {
+        let prefix = format!("# rudle {} v", self.name);
+        match text.split_once('\n') {
+            Some((header, contents)) if header.starts_with(&prefix) => {
+                Some((header[prefix.len()..].trim().parse().ok()?, contents))
+            }
+            _ => Some((0, text)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Version 1 added the header, and version 2 wrote the words in capitals
+    const WORDS_FORMAT: Format = Format {
+        name: "words",
+        migrations: &[unchanged, |contents| contents.to_uppercase()],
+    };
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rudle-persist-{}-{}.txt", std::process::id(), name))
+    }
+
+    #[test]
+    fn every_older_version_loads_upgraded_and_is_saved_in_the_current_one() {
+        let saved = [
+            ("v0", "crane\nslate\n"),
+            ("v1", "# rudle words v1\ncrane\nslate\n"),
+            ("v2", "# rudle words v2\nCRANE\nSLATE\n"),
+        ];
+        for (version, text) in saved {
+            let path = temp_path(version);
+            fs::write(&path, text).unwrap();
+            assert_eq!(
+                WORDS_FORMAT.load(&path).as_deref(),
+                Some("CRANE\nSLATE\n"),
+                "loading {}",
+                version
+            );
+            assert_eq!(
+                fs::read_to_string(&path).unwrap(),
+                "# rudle words v2\nCRANE\nSLATE\n"
+            );
+            // The upgraded file loads as it is
+            assert_eq!(WORDS_FORMAT.load(&path).as_deref(), Some("CRANE\nSLATE\n"));
+            fs::remove_file(&path).unwrap();
+        }
+    }
+
+    #[test]
+    fn a_newer_version_is_neither_loaded_nor_overwritten() {
+        let path = temp_path("v3");
+        let text = "# rudle words v3\nsomething new\n";
+        fs::write(&path, text).unwrap();
+        assert_eq!(WORDS_FORMAT.load(&path), None);
+        assert!(WORDS_FORMAT.save(&path, "CRANE\n").is_err());
+        assert_eq!(fs::read_to_string(&path).unwrap(), text);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,1072 @@
+use super::attest::{reproducible_seed, write_attestation, AttestedGame};
+use super::boards::play_boards;
+use super::cache::opening_scores;
+use super::grade::Grader;
+use super::input::LineReader;
+use super::overlay::{render_text, GameStatus, Overlay, KEYBOARD_ROWS};
+use super::persist::{self, Format};
+use super::rating::rate_game;
+use super::render::{format, print_table, symbol_cell, theme, OutputFormat};
+use super::solve::Rationale;
+use super::spectate::SpectatorHost;
+use super::stats::{
+    daily_streaks, load_daily, recent_win_rate, record_daily, record_game, GameEnd,
+    WORDLE_EPOCH_DAY,
+};
+use super::tui::play_tui;
+use super::{current_day, data_dir, make_rng, print_guess_meter, print_hint, rng_seed};
+use crate::cli::review::update_review_queue;
+use crate::{CommonArgs, PlayArgs};
+use colored::*;
+use rudle::hint::{keyboard_state, share_grid};
+use rudle::picker::{
+    Adaptive, Adversarial, AnswerPicker, Daily, NeverRepeat, PickerKind, Uniform, Weighted,
+};
+use rudle::rules::Ruleset;
+use rudle::solver::{best_guess, get_scores, solvable_within};
+use rudle::{word, Hint, LetterHint, Word, WordLists};
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::iter::zip;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub fn play(words: WordLists, common: &CommonArgs, options: &PlayArgs) {
+    if options.daily {
+        play_daily(&words, common, options);
+        return;
+    }
+    let mut picker = make_picker(common, options);
+    if options.boards > 1 {
+        for (secret_word, guesses_used) in play_boards(&words, picker.as_mut(), common, options) {
+            update_review_queue(&secret_word, guesses_used, options);
+        }
+        return;
+    }
+    if options.survival {
+        play_survival(words, picker.as_mut(), common, options);
+        return;
+    }
+    let (secret_word, guesses_used) = play_game(&words, picker.as_mut(), common, options);
+    update_review_queue(&secret_word, guesses_used, options);
+}
+
+/// Play today's daily puzzle, once per day, and report the streak of daily puzzles won
+fn play_daily(words: &WordLists, common: &CommonArgs, options: &PlayArgs) {
+    let today = current_day();
+    let puzzle = daily_puzzle(today);
+    let mut daily = load_daily();
+    if let Some(result) = daily.get(&today) {
+        let result = result.map_or("X".to_string(), |n| n.to_string());
+        println!(
+            "You already played Rudle #{} today ({}/{}). Come back tomorrow!",
+            puzzle, result, options.max_attempts
+        );
+        return;
+    }
+
+    println!("Rudle #{}\n", puzzle);
+    let (secret_word, guesses_used) = play_game(words, &mut Daily::new(today), common, options);
+    update_review_queue(&secret_word, guesses_used, options);
+    daily.insert(today, guesses_used);
+    if let Err(e) = record_daily(today, guesses_used) {
+        println!("Warning: failed to record the daily puzzle: {}", e);
+    }
+    let (current, longest) = daily_streaks(&daily, today);
+    println!("Daily streak: {} (longest: {}).", current, longest);
+}
+
+/// The number of the daily puzzle of a day, counted like Wordle's
+fn daily_puzzle(day: u64) -> u64 {
+    day.saturating_sub(WORDLE_EPOCH_DAY)
+}
+
+/// Play games one after another until one is lost, then report the run and the longest run
+/// Words solved during the run are not picked again in it.
+fn play_survival(
+    mut words: WordLists,
+    picker: &mut dyn AnswerPicker,
+    common: &CommonArgs,
+    options: &PlayArgs,
+) {
+    let mut run = 0;
+    while !words.answers.is_empty() {
+        let (secret_word, guesses_used) = play_game(&words, picker, common, options);
+        update_review_queue(&secret_word, guesses_used, options);
+        if guesses_used.is_none() {
+            break;
+        }
+        run += 1;
+        words.answers.retain(|w| w != &secret_word);
+        match words.answers.is_empty() {
+            false => println!("\n{}\n", format!("Run: {} solved. Next word!", run).green()),
+            true => println!("{}", "You solved every answer!".green()),
+        }
+    }
+
+    let best = load_best_run();
+    if run > best {
+        if let Err(e) = save_best_run(run) {
+            println!("Warning: failed to save the longest run: {}", e);
+        }
+    }
+    println!(
+        "Your run ended after {} solved {}. Longest run: {}{}.",
+        run,
+        if run == 1 { "word" } else { "words" },
+        run.max(best),
+        if run > best && best > 0 {
+            " (a new record)"
+        } else {
+            ""
+        }
+    );
+}
+
+/// The format of the file holding the longest survival run
+const SURVIVAL_FORMAT: Format = Format {
+    name: "survival",
+    migrations: &[],
+};
+
+/// The file holding the longest survival run so far
+fn survival_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("survival.txt"))
+}
+
+/// Load the longest survival run so far, treating a missing file as no run
+fn load_best_run() -> usize {
+    survival_path()
+        .and_then(|path| SURVIVAL_FORMAT.load(&path))
+        .and_then(|contents| contents.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Save a new longest survival run
+fn save_best_run(run: usize) -> io::Result<()> {
+    let path = survival_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+    SURVIVAL_FORMAT.save(&path, &format!("{}\n", run))
+}
+
+/// Create the answer picker selected with --pick
+pub fn make_picker(common: &CommonArgs, options: &PlayArgs) -> Box<dyn AnswerPicker> {
+    match options.pick {
+        PickerKind::Uniform => Box::new(Uniform::new(make_rng(common))),
+        PickerKind::Weighted => {
+            let weights = match &options.answer_weights {
+                Some(path) => word::load_weights(path).unwrap_or_else(|e| {
+                    println!("Warning: failed to read the answer weights: {}", e);
+                    HashMap::new()
+                }),
+                None => {
+                    println!("Warning: no --answer-weights given, so every answer is as likely.");
+                    HashMap::new()
+                }
+            };
+            Box::new(Weighted::new(make_rng(common), weights))
+        }
+        PickerKind::Daily => Box::new(Daily::new(current_day())),
+        PickerKind::NeverRepeat => Box::new(NeverRepeat::new(make_rng(common), load_played())),
+        PickerKind::Adversarial => Box::new(Adversarial),
+        PickerKind::Adaptive => {
+            let weights = match &options.answer_weights {
+                Some(path) => word::load_weights(path).unwrap_or_else(|e| {
+                    println!("Warning: failed to read the answer weights: {}", e);
+                    HashMap::new()
+                }),
+                None => HashMap::new(),
+            };
+            let difficulty = match recent_win_rate(common.word_size, ADAPTIVE_GAMES) {
+                Some((win_rate, n_games)) => {
+                    println!(
+                        "Difficulty {:.0}%: you won {} of your last {} games.",
+                        win_rate * 100.0,
+                        (win_rate * n_games as f64).round(),
+                        n_games
+                    );
+                    win_rate
+                }
+                None => ADAPTIVE_START,
+            };
+            let guesses_needed = match &options.tiers {
+                Some(path) => word::load_weights(path).unwrap_or_else(|e| {
+                    println!("Warning: failed to read the difficulty tiers: {}", e);
+                    HashMap::new()
+                }),
+                None => HashMap::new(),
+            };
+            Box::new(
+                Adaptive::new(make_rng(common), difficulty, weights)
+                    .with_guesses_needed(guesses_needed),
+            )
+        }
+    }
+}
+
+/// How many of the latest games --pick adaptive judges the player's form by
+const ADAPTIVE_GAMES: usize = 10;
+
+/// The difficulty --pick adaptive starts at before any game is recorded
+const ADAPTIVE_START: f64 = 0.5;
+
+/// The format of the list of played secret words
+const PLAYED_FORMAT: Format = Format {
+    name: "played",
+    migrations: &[persist::unchanged],
+};
+
+/// The file listing every secret word played so far, one per line
+fn played_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("played.txt"))
+}
+
+/// Load the secret words played so far, treating a missing file as no games played
+fn load_played() -> HashSet<Word> {
+    played_path()
+        .and_then(|path| PLAYED_FORMAT.load(&path))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| Word::from_string(line.trim()).ok())
+        .collect()
+}
+
+/// Add a secret word to the words played so far
+pub fn record_played(secret_word: &Word) -> io::Result<()> {
+    let path = played_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+    let mut contents = PLAYED_FORMAT.load(&path).unwrap_or_default();
+    contents.push_str(&format!("{}\n", secret_word));
+    PLAYED_FORMAT.save(&path, &contents)
+}
+
+/// Play a single game against a secret word chosen by the picker
+/// # Returns
+/// The secret word, and the number of guesses it took to find it or None if the game was lost
+pub fn play_game(
+    words: &WordLists,
+    picker: &mut dyn AnswerPicker,
+    common: &CommonArgs,
+    options: &PlayArgs,
+) -> (Word, Option<usize>) {
+    if options.tui {
+        match console::Term::stdout().is_term() {
+            true => return play_tui(words, picker, common, options),
+            false => {
+                println!("Warning: --tui needs a terminal, so the game is played line by line.")
+            }
+        }
+    }
+    let mut secret_word = picker.pick(&words.answers).expect("Word list is empty");
+
+    println!(
+        "Welcome to Wordle! Guess the {}-letter word. You have {} attempts.\n",
+        common.word_size, options.max_attempts
+    );
+    // Without colors, the rules show the symbols hints are marked with instead
+    let marked = |hint: LetterHint| {
+        let name = theme().color_name(hint);
+        match colored::control::SHOULD_COLORIZE.should_colorize() {
+            true => theme().paint(name, hint).to_string(),
+            false if format() == OutputFormat::Color => format!("as {}", symbol_cell('x', hint)),
+            false => name.to_string(),
+        }
+    };
+    println!(
+        "Letters are marked {} if they don't appear in the word.",
+        marked(LetterHint::Incorrect)
+    );
+    println!(
+        "Letters are marked {} if they are in the wrong position.",
+        marked(LetterHint::Misplaced)
+    );
+    println!(
+        "Letters are marked {} if they correct position.\n",
+        marked(LetterHint::Correct)
+    );
+
+    let started = Instant::now();
+    let mut guess_times: Vec<Duration> = vec![];
+    let mut attempts = 0;
+    let mut solved = false;
+    let mut gave_up = false;
+    let mut remaining_answers = words.answers.clone();
+    // The positions and letters shown by `!reveal`
+    let mut revealed: Vec<(usize, char)> = vec![];
+    // The number of guesses made before each reveal, as every reveal costs an attempt
+    let mut reveal_rows: Vec<usize> = vec![];
+    // The secret word before each guess, for `!undo`
+    let mut before_guesses: Vec<Word> = vec![];
+    let mut meter_history: Vec<(Word, Vec<Word>)> = vec![];
+    let mut board: Vec<(Word, Hint)> = vec![];
+
+    let overlay = options.obs_output.as_deref().map(Overlay::new);
+    let host = options
+        .host
+        .as_deref()
+        .and_then(|address| match SpectatorHost::bind(address) {
+            Ok(host) => {
+                println!("Spectators can watch with: rudle watch {}\n", address);
+                Some(host)
+            }
+            Err(e) => {
+                println!("Warning: failed to host the game on {}: {}\n", address, e);
+                None
+            }
+        });
+    let update_overlay = |board: &[(Word, Hint)], status, secret_word: &Word| {
+        let (max_attempts, word_size) = (options.max_attempts, common.word_size);
+        if let Some(overlay) = &overlay {
+            overlay.update(board, max_attempts, word_size, status, secret_word);
+        }
+        if let Some(host) = &host {
+            host.broadcast(&render_text(
+                board,
+                max_attempts,
+                word_size,
+                status,
+                secret_word,
+            ));
+        }
+    };
+    update_overlay(&board, GameStatus::Playing, &secret_word);
+
+    // With --coop, the engine's proposals for each guess and the guess picked
+    let mut proposals: Option<(usize, Vec<Word>)> = None;
+    let mut coop_picks: Vec<(Vec<Word>, Word)> = vec![];
+
+    // The Up arrow recalls the guesses of this game only
+    let mut input = LineReader::new();
+    while attempts < options.max_attempts {
+        if options.coop && proposals.as_ref().is_none_or(|(n, _)| *n != attempts) {
+            let suggestions = assist_suggestions(
+                &words.guesses,
+                &remaining_answers,
+                options.mode,
+                &board,
+                !common.deterministic,
+            );
+            let words: Vec<Word> = suggestions.into_iter().map(|(w, _)| w).collect();
+            print_proposals(&words, &remaining_answers);
+            proposals = Some((attempts, words));
+        }
+        println!(
+            "You have {} attempts left.",
+            options.max_attempts - attempts
+        );
+        let Some(mut guess) = input.read_line("Enter your guess: ") else {
+            println!();
+            break;
+        };
+        // A proposal is picked by its number
+        if let Some((_, words)) = &proposals {
+            if let Some(word) = guess
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| words.get(i.wrapping_sub(1)))
+            {
+                guess = word.to_string();
+            }
+        }
+
+        if guess.trim() == "!guesses" {
+            if board.is_empty() {
+                println!("No guesses yet.");
+            }
+            for (guess, hint) in board.iter() {
+                print_hint(hint, guess);
+                println!();
+            }
+            println!();
+            continue;
+        }
+        if guess.trim() == "!giveup" {
+            gave_up = true;
+            break;
+        }
+        if guess.trim() == "!undo" {
+            if !options.allow_undo {
+                println!("Undo is off; start the game with --allow-undo to take back guesses.\n");
+                continue;
+            }
+            let Some(secret) = before_guesses.pop() else {
+                println!("No guesses to undo.\n");
+                continue;
+            };
+            let (guess, _) = board.pop().unwrap();
+            println!("Took back {}.\n", guess);
+            // Letters revealed since the guess stay revealed, and keep the attempts they cost
+            (secret_word, remaining_answers) =
+                after_undo(picker, &words.answers, &board, &revealed, secret);
+            for n_guessed in reveal_rows.iter_mut() {
+                *n_guessed = (*n_guessed).min(board.len());
+            }
+            guess_times.pop();
+            meter_history.truncate(board.len());
+            coop_picks.truncate(board.len());
+            attempts -= 1;
+            update_overlay(&board, GameStatus::Playing, &secret_word);
+            continue;
+        }
+        if guess.trim() == "!reveal" {
+            if revealed.len() >= options.powerups {
+                match options.powerups {
+                    0 => println!(
+                        "Reveals are off; start the game with --powerups <N> to use some.\n"
+                    ),
+                    n => println!("You have used all {} reveals of this game.\n", n),
+                }
+                continue;
+            }
+            if attempts + 1 == options.max_attempts {
+                println!("A reveal costs an attempt, and you only have one left.\n");
+                continue;
+            }
+            let known = |i: usize| {
+                revealed.iter().any(|&(j, _)| j == i)
+                    || board
+                        .iter()
+                        .any(|(_, hint)| hint.iter().nth(i) == Some(&LetterHint::Correct))
+            };
+            let Some((i, c)) = secret_word.iter().enumerate().find(|&(i, _)| !known(i)) else {
+                println!("Every letter of the word is already known.\n");
+                continue;
+            };
+            revealed.push((i, c));
+            reveal_rows.push(board.len());
+            remaining_answers.retain(|w| w.iter().nth(i) == Some(c));
+            attempts += 1;
+            println!(
+                "Letter {} is {}. {} reveals left.\n",
+                i + 1,
+                c,
+                options.powerups - revealed.len()
+            );
+            continue;
+        }
+
+        let guess = match parse_guess(&guess, words, common.word_size) {
+            Ok(guess) => guess,
+            Err(e) => {
+                println!("{}\n", e);
+                continue;
+            }
+        };
+
+        if let Err(e) = options.mode.check(&guess, &board) {
+            println!("Not allowed in {} mode: {}.\n", options.mode, e);
+            continue;
+        }
+
+        if options.meter == "live" {
+            print_guess_meter(&guess, &words.guesses, &remaining_answers, common);
+        }
+        if options.meter == "post" || options.post_mortem {
+            meter_history.push((guess.clone(), remaining_answers.clone()));
+        }
+        if let Some((_, words)) = &proposals {
+            coop_picks.push((words.clone(), guess.clone()));
+        }
+
+        guess_times.push(started.elapsed());
+        if options.allow_undo {
+            before_guesses.push(secret_word.clone());
+        }
+        input.add_history(&guess.to_string().to_lowercase());
+        secret_word = picker.respond(&secret_word, &guess, &remaining_answers);
+        let hint = Hint::from_guess_and_answer(&guess, &secret_word).unwrap();
+        board.push((guess.clone(), hint.clone()));
+
+        if guess == secret_word {
+            println!("{}", "Congratulations! You guessed the word!".green());
+            solved = true;
+            break;
+        }
+
+        // Provide feedback for the guess
+        print_hint(&hint, &guess);
+        println!();
+        if !options.no_keyboard {
+            print_keyboard(&board);
+        }
+        remaining_answers.retain(|w| Hint::from_guess_and_answer(&guess, w).as_ref() == Ok(&hint));
+        if options.assist {
+            print_assist(
+                &words.guesses,
+                &remaining_answers,
+                options.mode,
+                &board,
+                common,
+            );
+        }
+        attempts += 1;
+        update_overlay(&board, GameStatus::Playing, &secret_word);
+    }
+
+    let status = if solved {
+        GameStatus::Won
+    } else {
+        GameStatus::Lost
+    };
+    update_overlay(&board, status, &secret_word);
+    let end = match () {
+        _ if solved => GameEnd::Solved,
+        _ if gave_up => GameEnd::GaveUp,
+        _ if attempts == options.max_attempts => GameEnd::OutOfAttempts,
+        _ => GameEnd::Quit,
+    };
+    let guesses_used = finish_game(
+        words,
+        common,
+        options,
+        &secret_word,
+        &board,
+        &guess_times,
+        &meter_history,
+        &reveal_rows,
+        end,
+    );
+    if !coop_picks.is_empty() {
+        print_coop_report(&coop_picks);
+    }
+    (secret_word, guesses_used)
+}
+
+/// The secret word and the answers left once the last guess is taken back
+/// The secret from before the guess is kept unless a letter revealed since contradicts it,
+/// which only happens when the picker changed the secret with the guess. The secret is then
+/// picked again among the answers the board and the revealed letters still allow.
+fn after_undo(
+    picker: &mut dyn AnswerPicker,
+    answers: &[Word],
+    board: &[(Word, Hint)],
+    revealed: &[(usize, char)],
+    secret: Word,
+) -> (Word, Vec<Word>) {
+    let remaining: Vec<Word> = answers
+        .iter()
+        .filter(|w| {
+            board
+                .iter()
+                .all(|(guess, hint)| Hint::from_guess_and_answer(guess, w).as_ref() == Ok(hint))
+                && revealed.iter().all(|&(i, c)| w.iter().nth(i) == Some(c))
+        })
+        .cloned()
+        .collect();
+    let secret = match remaining.contains(&secret) {
+        true => secret,
+        false => picker.pick(&remaining).unwrap_or(secret),
+    };
+    (secret, remaining)
+}
+
+/// Print the engine's proposals for the next guess, each with why it is worth playing
+fn print_proposals(proposals: &[Word], answers: &[Word]) {
+    println!("The engine proposes:");
+    for (i, word) in proposals.iter().enumerate() {
+        let rationale = Rationale::new(word, answers);
+        println!(
+            "  {}. {}: {:.2} answers left on average, at most {}; {}.",
+            i + 1,
+            word,
+            rationale.expected,
+            rationale.largest,
+            rationale.why
+        );
+    }
+    println!(
+        "Pick one by its number (1-{}) or enter a guess of your own.\n",
+        proposals.len()
+    );
+}
+
+/// Print which of the engine's proposals each guess of a co-op game was, and how often the
+/// player went with the engine's first choice
+fn print_coop_report(picks: &[(Vec<Word>, Word)]) {
+    println!("\nHow your picks compared to the engine's proposals:");
+    let rows: Vec<Vec<String>> = picks
+        .iter()
+        .enumerate()
+        .map(|(i, (proposals, guess))| {
+            let choice = match proposals.iter().position(|w| w == guess) {
+                Some(rank) => format!("proposal {}", rank + 1),
+                None => "your own".to_string(),
+            };
+            vec![
+                (i + 1).to_string(),
+                proposals.first().map_or("-".to_string(), |w| w.to_string()),
+                guess.to_string(),
+                choice,
+            ]
+        })
+        .collect();
+    print_table(&["Guess", "Engine's First", "Your Pick", "Choice"], &rows);
+
+    let n_first = picks
+        .iter()
+        .filter(|(proposals, guess)| proposals.first() == Some(guess))
+        .count();
+    let n_own = picks
+        .iter()
+        .filter(|(proposals, guess)| !proposals.contains(guess))
+        .count();
+    println!(
+        "You went with the engine's first choice {} of {} times, another proposal {} times and \
+         your own guess {} times.",
+        n_first,
+        picks.len(),
+        picks.len() - n_first - n_own,
+        n_own
+    );
+}
+
+/// Record a finished game and print what comes after it: the secret word if it was lost or
+/// given up, the share grid and the solver's comparisons asked for
+/// `reveals` holds the number of guesses made before each `!reveal`, whose attempts count
+/// towards the result like guesses do.
+/// # Returns
+/// The number of attempts the game was solved in, or None if it was lost
+#[allow(clippy::too_many_arguments)]
+pub fn finish_game(
+    words: &WordLists,
+    common: &CommonArgs,
+    options: &PlayArgs,
+    secret_word: &Word,
+    board: &[(Word, Hint)],
+    guess_times: &[Duration],
+    meter_history: &[(Word, Vec<Word>)],
+    reveals: &[usize],
+    end: GameEnd,
+) -> Option<usize> {
+    let solved = end == GameEnd::Solved;
+    if let Err(e) = record_played(secret_word) {
+        println!("Warning: failed to record the played word: {}", e);
+    }
+    if let Err(e) = record_game(secret_word, board, reveals.len(), end) {
+        println!("Warning: failed to record the game: {}", e);
+    }
+    if let Some(path) = &options.attest {
+        let game = AttestedGame {
+            board,
+            guess_times,
+            answers: &words.answers,
+            seed: reproducible_seed(options.pick, current_day(), rng_seed(common)),
+            solved,
+        };
+        if let Err(e) = write_attestation(path, &game, options) {
+            println!("Warning: failed to write the attestation: {}", e);
+        }
+    }
+
+    if end == GameEnd::OutOfAttempts || end == GameEnd::GaveUp {
+        let secret_word: String = secret_word.iter().collect();
+        println!(
+            "{}{} The correct word was: {}",
+            "Game Over!".red(),
+            if end == GameEnd::GaveUp {
+                " You gave up."
+            } else {
+                ""
+            },
+            secret_word.green()
+        );
+    }
+
+    if !board.is_empty() {
+        let title = match options.daily {
+            true => format!("Rudle {}", daily_puzzle(current_day())),
+            false => "Rudle".to_string(),
+        };
+        let grid = share_grid(
+            &title,
+            board,
+            reveals,
+            options.max_attempts,
+            &options.share_symbols,
+        );
+        println!("\n{}\n", grid);
+        if options.copy {
+            match copy_to_clipboard(&grid) {
+                Ok(()) => println!("Copied the share grid to the clipboard."),
+                Err(e) => println!("Warning: failed to copy the share grid: {}", e),
+            }
+        }
+    }
+
+    if end != GameEnd::Quit && !board.is_empty() {
+        rate(words, common, options, secret_word, board, reveals, solved);
+    }
+
+    if options.meter == "post" && !meter_history.is_empty() {
+        println!("\nHow your guesses compared to the solver:");
+        for (guess, answers) in meter_history.iter() {
+            print_guess_meter(guess, &words.guesses, answers, common);
+        }
+    }
+    if options.post_mortem && !board.is_empty() {
+        print_post_mortem(
+            board,
+            meter_history,
+            reveals,
+            words,
+            common,
+            options.max_attempts,
+        );
+    }
+
+    solved.then_some(board.len() + reveals.len())
+}
+
+/// Grade a finished game and update the skill rating with it
+fn rate(
+    words: &WordLists,
+    common: &CommonArgs,
+    options: &PlayArgs,
+    secret_word: &Word,
+    board: &[(Word, Hint)],
+    reveals: &[usize],
+    solved: bool,
+) {
+    let result = solved.then_some(board.len() + reveals.len());
+    let grader = Grader::new(words, common, options.max_attempts);
+    let grade = match grader.grade(current_day(), board, result, Some(secret_word)) {
+        Ok(grade) => grade,
+        Err(e) => {
+            println!("Warning: failed to grade the game: {}", e);
+            return;
+        }
+    };
+    match rate_game(&grade, secret_word) {
+        Ok((before, after)) => println!(
+            "Skill rating: {:.0} ({:+.0})\n",
+            after,
+            after.round() - before.round()
+        ),
+        Err(e) => println!("Warning: failed to update the skill rating: {}", e),
+    }
+}
+
+/// The clipboard tools of macOS, Windows, Wayland and X11, tried in order
+const CLIPBOARD_COMMANDS: [&[&str]; 5] = [
+    &["pbcopy"],
+    &["clip"],
+    &["wl-copy"],
+    &["xclip", "-selection", "clipboard"],
+    &["xsel", "--clipboard", "--input"],
+];
+
+/// Copy text to the clipboard with the first clipboard tool that is installed
+fn copy_to_clipboard(text: &str) -> io::Result<()> {
+    for command in CLIPBOARD_COMMANDS {
+        let Ok(mut child) = Command::new(command[0])
+            .args(&command[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::NotFound,
+        "no clipboard tool was found",
+    ))
+}
+
+/// Print a QWERTY keyboard with each letter colored by the best feedback it has had
+fn print_keyboard(board: &[(Word, Hint)]) {
+    for line in keyboard_lines(board) {
+        println!("{}", line);
+    }
+    println!();
+}
+
+/// The lines of a QWERTY keyboard with each letter colored by the best feedback it has had
+/// Letters ruled out are grey. When colors are off they are replaced by "·" instead, and the
+/// correct and misplaced letters are listed below the keyboard.
+pub fn keyboard_lines(board: &[(Word, Hint)]) -> Vec<String> {
+    let letters = keyboard_state(board);
+    let colorize = colored::control::SHOULD_COLORIZE.should_colorize();
+    let mut lines = vec![];
+    for (indent, row) in KEYBOARD_ROWS.iter().enumerate() {
+        let keys: Vec<String> = row
+            .chars()
+            .map(|c| match letters.get(&c) {
+                Some(&h @ (LetterHint::Correct | LetterHint::Misplaced)) => {
+                    theme().paint(&c.to_string(), h).to_string()
+                }
+                Some(LetterHint::Incorrect) if colorize => c.to_string().bright_black().to_string(),
+                Some(LetterHint::Incorrect) => "·".to_string(),
+                None => c.to_string(),
+            })
+            .collect();
+        lines.push(format!("{}{}", " ".repeat(indent), keys.join(" ")));
+    }
+    if !colorize {
+        let with = |feedback: LetterHint| {
+            let mut with: Vec<char> = letters
+                .iter()
+                .filter(|(_, &h)| h == feedback)
+                .map(|(&c, _)| c)
+                .collect();
+            with.sort();
+            match with.is_empty() {
+                true => "none".to_string(),
+                false => with.into_iter().collect(),
+            }
+        };
+        lines.push(format!(
+            "Correct: {}. Misplaced: {}.",
+            with(LetterHint::Correct),
+            with(LetterHint::Misplaced)
+        ));
+    }
+    lines
+}
+
+/// The number of the solver's best guesses `--assist` shows
+pub const ASSIST_SUGGESTIONS: usize = 3;
+
+/// Print how many answers remain and the solver's best guesses, or the answers once there
+/// are no more of them than suggestions
+fn print_assist(
+    guesses: &[Word],
+    answers: &[Word],
+    rules: Ruleset,
+    board: &[(Word, Hint)],
+    common: &CommonArgs,
+) {
+    if answers.len() <= ASSIST_SUGGESTIONS {
+        let answers: Vec<String> = answers.iter().map(|w| w.to_string()).collect();
+        println!("Possible answers: {}\n", answers.join(", "));
+        return;
+    }
+    let suggestions: Vec<String> =
+        assist_suggestions(guesses, answers, rules, board, !common.deterministic)
+            .iter()
+            .map(|(w, score)| format!("{} ({:.2}%)", w, score))
+            .collect();
+    println!(
+        "{} possible answers remain. The solver suggests: {}\n",
+        answers.len(),
+        suggestions.join(", ")
+    );
+}
+
+/// The solver's best guesses with their expected scores, the number `--assist` shows, out of
+/// the guesses the rules allow after the board
+pub fn assist_suggestions(
+    guesses: &[Word],
+    answers: &[Word],
+    rules: Ruleset,
+    board: &[(Word, Hint)],
+    show_progress: bool,
+) -> Vec<(Word, f32)> {
+    let allowed: Vec<Word> = guesses
+        .iter()
+        .filter(|w| rules.check(w, board).is_ok())
+        .cloned()
+        .collect();
+    // Among guesses that score the same, one that could be the answer is the better play
+    let mut scores = get_scores(&allowed, answers, show_progress);
+    let possible = |w: &Word| answers.binary_search(w).is_ok();
+    scores.sort_by(|a, b| {
+        b.1.total_cmp(&a.1)
+            .then(possible(&b.0).cmp(&possible(&a.0)))
+    });
+    scores
+        .into_iter()
+        .take(ASSIST_SUGGESTIONS)
+        .map(|(w, score, _)| (w, score))
+        .collect()
+}
+
+/// Read a guess entered by the player, checking that it is an allowed guess of the game
+/// # Returns
+/// The guess, or the message explaining why it was rejected
+pub fn parse_guess(guess: &str, words: &WordLists, word_size: usize) -> Result<Word, String> {
+    // Normalize so that composed and decomposed accents compare equal,
+    // and measure the guess in user-perceived characters rather than bytes
+    let guess: String = guess.trim().nfc().collect();
+
+    if let Some(letter) = guess
+        .graphemes(true)
+        .find(|g| !g.chars().all(|c| c.is_ascii_alphabetic()))
+    {
+        return Err(format!("'{}' is not in the puzzle alphabet.", letter));
+    }
+
+    if guess.graphemes(true).count() != word_size {
+        return Err(format!("Please enter a {}-letter word.", word_size));
+    }
+
+    let guess = Word::from_string(&guess).map_err(|e| format!("Error: {}.", e))?;
+    if words.guesses.binary_search(&guess).is_err() {
+        return Err("Invalid word. Please try again.".to_string());
+    }
+    Ok(guess)
+}
+
+/// The attempts left after a turn of the game, less those spent on reveals up to then
+/// `reveals` holds the number of guesses made before each reveal.
+fn guesses_left(max_attempts: usize, turn: usize, reveals: &[usize]) -> usize {
+    let n_revealed = reveals
+        .iter()
+        .filter(|&&n_guessed| n_guessed <= turn)
+        .count();
+    max_attempts.saturating_sub(turn + 1 + n_revealed)
+}
+
+/// Print what the solver would have played before each guess, and the guess that won or lost
+/// the game
+///
+/// A won game is decided by the guess that left a single answer. A lost game is lost at the
+/// first guess that left more answers than `solvable_within` expects the guesses left to
+/// solve.
+fn print_post_mortem(
+    board: &[(Word, Hint)],
+    positions: &[(Word, Vec<Word>)],
+    reveals: &[usize],
+    words: &WordLists,
+    common: &CommonArgs,
+    max_attempts: usize,
+) {
+    let solved = board
+        .last()
+        .is_some_and(|(_, hint)| hint.iter().all(|&h| h == LetterHint::Correct));
+    let mut decided = None;
+    let mut rows = vec![];
+    for (i, ((guess, hint), (_, answers))) in zip(board, positions).enumerate() {
+        let scores = match i {
+            0 => opening_scores(words, common),
+            _ => get_scores(&words.guesses, answers, false),
+        };
+        let solver_guess = best_guess(&scores, answers).map_or("-".to_string(), Word::to_string);
+        let n_left = match hint.iter().all(|&h| h == LetterHint::Correct) {
+            true => 0,
+            false => answers
+                .iter()
+                .filter(|w| Hint::from_guess_and_answer(guess, w).as_ref() == Ok(hint))
+                .count(),
+        };
+
+        let guesses_left = guesses_left(max_attempts, i, reveals);
+        let decisive = match solved {
+            true => n_left <= 1,
+            false => !solvable_within(n_left, guesses_left),
+        };
+        let note = match decided {
+            None if decisive => {
+                decided = Some(i);
+                match (solved, n_left) {
+                    (true, 0) => "solved",
+                    (true, _) => "won here: one answer left",
+                    (false, _) => "lost here: too many answers for the guesses left",
+                }
+            }
+            _ if solved && n_left == 0 => "solved",
+            _ => "",
+        };
+        rows.push(vec![
+            (i + 1).to_string(),
+            guess.to_string(),
+            answers.len().to_string(),
+            n_left.to_string(),
+            solver_guess,
+            note.to_string(),
+        ]);
+    }
+
+    println!("\nPost-mortem:");
+    print_table(
+        &[
+            "Turn",
+            "Guess",
+            "Answers before",
+            "Answers after",
+            "Solver",
+            "Note",
+        ],
+        &rows,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<Word> {
+        let mut words: Vec<Word> = words
+            .iter()
+            .map(|w| Word::from_string(w).unwrap())
+            .collect();
+        words.sort();
+        words
+    }
+
+    #[test]
+    fn suggestions_keep_to_the_rules_of_the_game() {
+        let answers = words(&[
+            "BAKES", "CAKES", "FAKES", "LAKES", "MAKES", "RAKES", "WAKES",
+        ]);
+        let guesses = words(&[
+            "BAKES", "CAKES", "FAKES", "LAKES", "MAKES", "RAKES", "WAKES", "FLORA", "CRAMB",
+        ]);
+        let guess = Word::from_string("TAKES").unwrap();
+        let hint = Hint::from_guess_and_answer(&guess, &answers[0]).unwrap();
+        let board = vec![(guess, hint)];
+
+        let easy = assist_suggestions(&guesses, &answers, Ruleset::Easy, &board, false);
+        assert!(easy
+            .iter()
+            .any(|(w, _)| Ruleset::Hard.check(w, &board).is_err()));
+        let hard = assist_suggestions(&guesses, &answers, Ruleset::Hard, &board, false);
+        assert!(!hard.is_empty());
+        assert!(hard
+            .iter()
+            .all(|(w, _)| Ruleset::Hard.check(w, &board).is_ok()));
+    }
+
+    #[test]
+    fn reveals_count_against_the_guesses_left() {
+        assert_eq!(guesses_left(6, 0, &[]), 5);
+        // One reveal before the first guess and one after the second
+        let reveals = [0, 2];
+        assert_eq!(guesses_left(6, 0, &reveals), 4);
+        assert_eq!(guesses_left(6, 1, &reveals), 3);
+        assert_eq!(guesses_left(6, 2, &reveals), 1);
+        assert_eq!(guesses_left(6, 3, &reveals), 0);
+    }
+
+    #[test]
+    fn undoing_against_the_adversary_keeps_the_secret_to_the_revealed_letters() {
+        let answers = words(&["BATCH", "CATCH", "HATCH", "LATCH", "MATCH"]);
+        let mut picker = Adversarial;
+        let secret = picker.pick(&answers).unwrap();
+        let guess = secret.clone();
+        let dodged = picker.respond(&secret, &guess, &answers);
+        assert_ne!(dodged, secret);
+
+        // Taking back the guess with nothing revealed restores the secret from before it
+        let (kept, remaining) = after_undo(&mut picker, &answers, &[], &[], secret.clone());
+        assert_eq!((kept, remaining), (secret.clone(), answers.clone()));
+
+        // A letter of the dodged secret revealed after the guess rules the old secret out
+        let revealed = [(0, dodged.iter().next().unwrap())];
+        let (rederived, remaining) =
+            after_undo(&mut picker, &answers, &[], &revealed, secret.clone());
+        assert_eq!(remaining, vec![dodged.clone()]);
+        assert_eq!(rederived, dodged);
+    }
+}
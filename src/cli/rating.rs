@@ -0,0 +1,135 @@
+//! An Elo-style skill rating, updated with the grade of every game played
+//!
+//! Each game is scored as a match against the solver, which holds a fixed rating. The grade
+//! of the game from `grade` is the player's score in the match, so a player whose games grade
+//! better than their rating predicts climbs, and one whose games grade worse falls.
+
+use super::data_dir;
+use super::grade::GameGrade;
+use super::persist::Format;
+use rudle::Word;
+use std::io;
+use std::path::PathBuf;
+
+/// The format of the list of rated games
+const RATING_FORMAT: Format = Format {
+    name: "rating",
+    migrations: &[],
+};
+
+/// The rating of a player before their first graded game
+const INITIAL_RATING: f64 = 1500.0;
+
+/// The rating of the solver, which every game is scored against
+const SOLVER_RATING: f64 = 2000.0;
+
+/// How far a single game can move the rating
+const K_FACTOR: f64 = 32.0;
+
+/// The file listing every rated game, as one `DAY SECRET GRADE RATING` line each
+fn rating_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("rating.txt"))
+}
+
+/// The grade a player of a rating is expected to get against the solver
+fn expected_score(rating: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf((SOLVER_RATING - rating) / 400.0))
+}
+
+/// The rating after a game with a grade from 0 to 1
+pub fn updated_rating(rating: f64, score: f64) -> f64 {
+    rating + K_FACTOR * (score - expected_score(rating))
+}
+
+/// Load the rating after each rated game in the order they were played, treating a missing
+/// file as none
+pub fn load_ratings() -> Vec<f64> {
+    rating_path()
+        .and_then(|path| RATING_FORMAT.load(&path))
+        .map(|contents| ratings(&contents))
+        .unwrap_or_default()
+}
+
+/// The ratings of the lines of a rating file, skipping any that are malformed
+fn ratings(contents: &str) -> Vec<f64> {
+    contents
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(3)?.parse().ok())
+        .collect()
+}
+
+/// Update the rating with a graded game and save it
+/// # Returns
+/// The rating before the game and after it
+pub fn rate_game(grade: &GameGrade, secret: &Word) -> io::Result<(f64, f64)> {
+    let path = rating_path()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+    let mut contents = RATING_FORMAT.load(&path).unwrap_or_default();
+    let before = ratings(&contents).last().copied().unwrap_or(INITIAL_RATING);
+    let score = grade.score();
+    let after = updated_rating(before, score);
+    contents.push_str(&format!(
+        "{} {} {:.4} {:.1}\n",
+        grade.day, secret, score, after
+    ));
+    RATING_FORMAT.save(&path, &contents)?;
+    Ok((before, after))
+}
+
+/// Print the current rating, with its change over the last ten games and the best reached
+pub fn print_rating(ratings: &[f64]) {
+    let Some(current) = ratings.last() else {
+        return;
+    };
+    let best = ratings.iter().copied().fold(f64::MIN, f64::max);
+    let recent = ratings.len().min(10);
+    let before_recent = match ratings.len() > recent {
+        true => ratings[ratings.len() - recent - 1],
+        false => INITIAL_RATING,
+    };
+    println!(
+        "Skill rating: {:.0} from {} graded games ({:+.0} over the last {}, best {:.0}).\n",
+        current,
+        ratings.len(),
+        current - before_recent,
+        recent,
+        best
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playing_like_the_solver_raises_the_rating_and_losing_lowers_it() {
+        assert!(updated_rating(INITIAL_RATING, 1.0) > INITIAL_RATING);
+        assert!(updated_rating(INITIAL_RATING, 0.0) < INITIAL_RATING);
+        // A player rated like the solver is expected to grade 50%
+        assert_eq!(updated_rating(SOLVER_RATING, 0.5), SOLVER_RATING);
+    }
+
+    #[test]
+    fn one_game_moves_the_rating_by_at_most_the_k_factor() {
+        for rating in [0.0, INITIAL_RATING, SOLVER_RATING, 3000.0] {
+            for score in [0.0, 0.5, 1.0] {
+                assert!((updated_rating(rating, score) - rating).abs() <= K_FACTOR);
+            }
+        }
+    }
+
+    #[test]
+    fn ratings_settle_where_the_grades_meet_their_expectation() {
+        let mut rating = INITIAL_RATING;
+        for _ in 0..2000 {
+            rating = updated_rating(rating, 0.8);
+        }
+        assert!((expected_score(rating) - 0.8).abs() < 0.01);
+    }
+
+    #[test]
+    fn rating_lines_are_read_back_skipping_malformed_ones() {
+        let contents = "20000 CRANE 0.7500 1512.3\nnot a line\n20001 SLATE 0.2000 1498.1\n";
+        assert_eq!(ratings(contents), vec![1512.3, 1498.1]);
+    }
+}
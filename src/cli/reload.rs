@@ -0,0 +1,168 @@
+//! Reloading the word lists when their files change, for the modes that run long enough for
+//! someone to edit a list meanwhile
+//!
+//! The files are polled by their modification time and size whenever the mode is about to
+//! use the lists, such as before each command of the REPL or each key of the TUI.
+
+use super::cache::ScoreCache;
+use crate::CommonArgs;
+use rudle::variant::Variant;
+use rudle::{word, WordLists};
+use std::fs;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// What a file looked like when it was last read
+type Stamp = Option<(SystemTime, u64)>;
+
+/// The word list files of the command line, with what they looked like when they were loaded
+pub struct ListWatcher {
+    guesses: Option<(PathBuf, Stamp)>,
+    answers: Option<(PathBuf, Stamp)>,
+    word_size: usize,
+}
+
+/// The outcome of checking the word lists for changes
+pub enum Reload {
+    Unchanged,
+    /// The new lists, with a summary of how the answers changed
+    Reloaded(WordLists, String),
+    /// Why the changed files could not be used, in which case the old lists stay in use
+    Failed(String),
+}
+
+/// The modification time and size of a file, or None if it cannot be read
+fn stamp(path: &PathBuf) -> Stamp {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+impl ListWatcher {
+    /// Watch the files the lists were loaded from: --file, or words.txt without it, and
+    /// --answers-file. The embedded list of a variant never changes.
+    pub fn new(common: &CommonArgs) -> Self {
+        let embedded = common.variant.and_then(Variant::embedded_words).is_some();
+        let guesses = match (&common.file, embedded) {
+            (Some(path), _) => Some(PathBuf::from(path)),
+            (None, true) => None,
+            (None, false) => Some(PathBuf::from("words.txt")),
+        };
+        let watch = |path: PathBuf| {
+            let stamp = stamp(&path);
+            (path, stamp)
+        };
+        Self {
+            guesses: guesses.map(watch),
+            answers: common.answers_file.as_ref().map(PathBuf::from).map(watch),
+            word_size: common.word_size,
+        }
+    }
+
+    /// Load the lists again if one of their files changed since they were last loaded
+    pub fn poll(&mut self, old: &WordLists) -> Reload {
+        let mut changed = false;
+        for (path, last) in self.guesses.iter_mut().chain(self.answers.iter_mut()) {
+            let now = stamp(path);
+            changed |= now != *last;
+            *last = now;
+        }
+        if !changed {
+            return Reload::Unchanged;
+        }
+
+        let load = |file: &Option<(PathBuf, Stamp)>| {
+            file.as_ref().map(|(path, _)| {
+                word::load_words(path, self.word_size)
+                    .map_err(|e| format!("failed to load {}: {}", path.display(), e))
+            })
+        };
+        let guesses = match load(&self.guesses) {
+            Some(Ok(guesses)) => guesses,
+            Some(Err(e)) => return Reload::Failed(e),
+            None => old.guesses.clone(),
+        };
+        let words = match load(&self.answers) {
+            Some(Ok(answers)) => WordLists::new(guesses, answers),
+            Some(Err(e)) => return Reload::Failed(e),
+            None if self.guesses.is_some() => WordLists::single(guesses),
+            None => WordLists::new(guesses, old.answers.clone()),
+        };
+        if words.answers.is_empty() {
+            return Reload::Failed(format!(
+                "the new lists have no {}-letter answers",
+                self.word_size
+            ));
+        }
+        if words.guesses == old.guesses && words.answers == old.answers {
+            return Reload::Unchanged;
+        }
+
+        let n_added = words
+            .answers
+            .iter()
+            .filter(|w| old.answers.binary_search(w).is_err())
+            .count();
+        let n_removed = old
+            .answers
+            .iter()
+            .filter(|w| words.answers.binary_search(w).is_err())
+            .count();
+        let summary = format!(
+            "Reloaded the word lists: {} guesses and {} answers ({} added, {} removed).",
+            words.guesses.len(),
+            words.answers.len(),
+            n_added,
+            n_removed
+        );
+        Reload::Reloaded(words, summary)
+    }
+}
+
+/// Delete the cached scores of lists that were replaced by reloaded ones, as nothing reads
+/// them once the files hold other words
+pub fn forget_scores(old: &WordLists) {
+    if let Err(e) = ScoreCache::new(&old.guesses, &old.answers).remove() {
+        println!(
+            "Warning: failed to remove the score cache of the old lists: {}",
+            e
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+    use rudle::Word;
+
+    fn words(words: &[&str]) -> Vec<Word> {
+        words
+            .iter()
+            .map(|w| Word::from_string(w).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn a_changed_list_is_loaded_again_and_an_unchanged_one_is_not() {
+        let path = std::env::temp_dir().join(format!("rudle-reload-{}.txt", std::process::id()));
+        fs::write(&path, "crane\nslate\n").unwrap();
+        let args = crate::Cli::parse_from(["rudle", "--file", path.to_str().unwrap(), "stats"]);
+        let mut watcher = ListWatcher::new(&args.common);
+        let old = WordLists::single(words(&["CRANE", "SLATE"]));
+        assert!(matches!(watcher.poll(&old), Reload::Unchanged));
+
+        fs::write(&path, "crane\nslate\ntrace\n").unwrap();
+        match watcher.poll(&old) {
+            Reload::Reloaded(new, _) => {
+                assert_eq!(new.answers, words(&["CRANE", "SLATE", "TRACE"]))
+            }
+            _ => panic!("the changed list was not reloaded"),
+        }
+        assert!(matches!(watcher.poll(&old), Reload::Unchanged));
+
+        // A list that cannot be used leaves the old one in place
+        fs::write(&path, "cat\n").unwrap();
+        assert!(matches!(watcher.poll(&old), Reload::Failed(_)));
+        fs::remove_file(&path).unwrap();
+    }
+}
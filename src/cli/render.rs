@@ -0,0 +1,488 @@
+//! Renderers for the hints and tables the commands print, selected with `--format`
+
+use colored::*;
+use rudle::hint::FeedbackSymbols;
+use rudle::{Hint, LetterHint, Word};
+use std::env;
+use std::fmt;
+use std::iter::zip;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// A way of writing the output of the commands
+pub trait Renderer {
+    /// Write a guess marked with its hint, with its letters as question marks if it is the
+    /// word kept out of the output
+    fn hint(&self, hint: &Hint, guess: &Word) -> String;
+
+    /// Write a table with a header row, ending in a newline
+    /// Columns whose cells are all numbers are aligned to the right.
+    fn table(&self, header: &[&str], rows: &[Vec<String>]) -> String;
+}
+
+/// ANSI colors for the terminal, with tables padded into columns
+pub struct Color;
+
+impl Renderer for Color {
+    /// Without colors, as with NO_COLOR or output to a file, the hints are written in
+    /// symbols instead
+    fn hint(&self, hint: &Hint, guess: &Word) -> String {
+        let colorize = colored::control::SHOULD_COLORIZE.should_colorize();
+        zip(shown(guess).chars(), hint.iter())
+            .map(|(c, &h)| match colorize {
+                true => theme().paint(&c.to_string(), h).to_string(),
+                false => symbol_cell(c, h),
+            })
+            .collect()
+    }
+
+    fn table(&self, header: &[&str], rows: &[Vec<String>]) -> String {
+        text_table(header, rows)
+    }
+}
+
+/// Text without escape codes, with hints in the letter format the `hint` command reads
+pub struct Plain;
+
+impl Renderer for Plain {
+    fn hint(&self, hint: &Hint, guess: &Word) -> String {
+        about(guess, format!("{} {}", guess, hint.to_pattern(guess)))
+    }
+
+    fn table(&self, header: &[&str], rows: &[Vec<String>]) -> String {
+        text_table(header, rows)
+    }
+}
+
+/// Markdown, with hints as share-grid squares and GitHub-style tables
+pub struct Markdown;
+
+impl Renderer for Markdown {
+    fn hint(&self, hint: &Hint, guess: &Word) -> String {
+        let symbols = FeedbackSymbols::default();
+        let squares: String = hint.iter().map(|&h| symbols.symbol(h)).collect();
+        format!("{} `{}`", squares, shown(guess))
+    }
+
+    fn table(&self, header: &[&str], rows: &[Vec<String>]) -> String {
+        let right = right_aligned(header, rows);
+        let row = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+        let mut table = row(header.iter().map(|h| h.replace('|', "\\|")).collect());
+        table += &row(right
+            .iter()
+            .map(|&r| if r { "---:" } else { "---" }.to_string())
+            .collect());
+        for cells in rows {
+            table += &row(cells.iter().map(|c| c.trim().replace('|', "\\|")).collect());
+        }
+        table
+    }
+}
+
+/// HTML fragments with inline styles, so they keep their look wherever they are pasted
+pub struct Html;
+
+impl Renderer for Html {
+    fn hint(&self, hint: &Hint, guess: &Word) -> String {
+        zip(shown(guess).chars(), hint.iter())
+            .map(|(c, h)| {
+                format!(
+                    "<span style=\"background:{};color:#fff;font-family:monospace\">{}</span>",
+                    theme().css(*h),
+                    c
+                )
+            })
+            .collect()
+    }
+
+    fn table(&self, header: &[&str], rows: &[Vec<String>]) -> String {
+        let right = right_aligned(header, rows);
+        let row = |tag: &str, cells: Vec<&str>| {
+            let cells: Vec<String> = zip(cells, right.iter())
+                .map(|(cell, &r)| {
+                    let style = if r { " style=\"text-align:right\"" } else { "" };
+                    format!("<{}{}>{}</{}>", tag, style, escape_html(cell.trim()), tag)
+                })
+                .collect();
+            format!("<tr>{}</tr>\n", cells.join(""))
+        };
+        let mut table = String::from("<table>\n");
+        table += &row("th", header.to_vec());
+        for cells in rows {
+            table += &row("td", cells.iter().map(String::as_str).collect());
+        }
+        table + "</table>\n"
+    }
+}
+
+/// The output formats that can be selected on the command line
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum OutputFormat {
+    Color,
+    Plain,
+    Markdown,
+    Html,
+}
+
+impl OutputFormat {
+    pub const ALL: [OutputFormat; 4] = [
+        OutputFormat::Color,
+        OutputFormat::Plain,
+        OutputFormat::Markdown,
+        OutputFormat::Html,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            OutputFormat::Color => "color",
+            OutputFormat::Plain => "plain",
+            OutputFormat::Markdown => "markdown",
+            OutputFormat::Html => "html",
+        }
+    }
+
+    fn renderer(self) -> &'static dyn Renderer {
+        match self {
+            OutputFormat::Color => &Color,
+            OutputFormat::Plain => &Plain,
+            OutputFormat::Markdown => &Markdown,
+            OutputFormat::Html => &Html,
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        OutputFormat::ALL
+            .into_iter()
+            .find(|format| format.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = OutputFormat::ALL.iter().map(|f| f.name()).collect();
+                format!(
+                    "unknown output format '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// The index in `OutputFormat::ALL` of the format selected with `--format`
+static FORMAT: AtomicUsize = AtomicUsize::new(0);
+
+/// Select the format every command prints in
+/// Every format but `color` also turns off the colors of the rest of the output.
+pub fn set_format(format: OutputFormat) {
+    let index = OutputFormat::ALL.iter().position(|&f| f == format);
+    FORMAT.store(index.unwrap_or(0), Ordering::Relaxed);
+    if format != OutputFormat::Color {
+        colored::control::set_override(false);
+    }
+}
+
+/// The colors hints are drawn in, selected with `--color-theme`
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Theme {
+    /// Wordle's green and yellow
+    Default,
+    /// The high-contrast orange and blue, which colorblind players can tell apart
+    Colorblind,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 2] = [Theme::Default, Theme::Colorblind];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Theme::Default => "default",
+            Theme::Colorblind => "colorblind",
+        }
+    }
+
+    /// The name of the color of a hint, for the rules printed before a game
+    pub fn color_name(self, hint: LetterHint) -> &'static str {
+        match (self, hint) {
+            (Theme::Default, LetterHint::Correct) => "green",
+            (Theme::Default, LetterHint::Misplaced) => "yellow",
+            (Theme::Colorblind, LetterHint::Correct) => "orange",
+            (Theme::Colorblind, LetterHint::Misplaced) => "blue",
+            (_, LetterHint::Incorrect) => "grey",
+        }
+    }
+
+    /// Write text in the color of a hint
+    /// Incorrect letters keep the terminal's own color, which is readable on light and dark
+    /// backgrounds alike.
+    pub fn paint(self, text: &str, hint: LetterHint) -> ColoredString {
+        match (self, hint) {
+            (_, LetterHint::Incorrect) => text.normal(),
+            (Theme::Default, LetterHint::Correct) => text.green(),
+            (Theme::Default, LetterHint::Misplaced) => text.yellow(),
+            (Theme::Colorblind, LetterHint::Correct) if truecolor() => text.truecolor(245, 121, 58),
+            (Theme::Colorblind, LetterHint::Misplaced) if truecolor() => {
+                text.truecolor(133, 192, 249)
+            }
+            (Theme::Colorblind, LetterHint::Correct) => text.bright_red(),
+            (Theme::Colorblind, LetterHint::Misplaced) => text.bright_blue(),
+        }
+    }
+
+    /// Write text on the color of a hint, as the cells of a board are
+    pub fn fill(self, text: &str, hint: LetterHint) -> ColoredString {
+        let text = text.black();
+        match (self, hint) {
+            (_, LetterHint::Incorrect) => text.white().on_bright_black(),
+            (Theme::Default, LetterHint::Correct) => text.on_green(),
+            (Theme::Default, LetterHint::Misplaced) => text.on_yellow(),
+            (Theme::Colorblind, LetterHint::Correct) if truecolor() => {
+                text.on_truecolor(245, 121, 58)
+            }
+            (Theme::Colorblind, LetterHint::Misplaced) if truecolor() => {
+                text.on_truecolor(133, 192, 249)
+            }
+            (Theme::Colorblind, LetterHint::Correct) => text.on_bright_red(),
+            (Theme::Colorblind, LetterHint::Misplaced) => text.on_bright_blue(),
+        }
+    }
+
+    /// The CSS color of a hint, for the HTML format
+    pub fn css(self, hint: LetterHint) -> &'static str {
+        match (self, hint) {
+            (_, LetterHint::Incorrect) => "#787c7e",
+            (Theme::Default, LetterHint::Correct) => "#6aaa64",
+            (Theme::Default, LetterHint::Misplaced) => "#c9b458",
+            (Theme::Colorblind, LetterHint::Correct) => "#f5793a",
+            (Theme::Colorblind, LetterHint::Misplaced) => "#85c0f9",
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Theme::ALL
+            .into_iter()
+            .find(|theme| theme.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Theme::ALL.iter().map(|t| t.name()).collect();
+                format!(
+                    "unknown color theme '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// The index in `Theme::ALL` of the theme selected with `--color-theme`
+static THEME: AtomicUsize = AtomicUsize::new(0);
+
+/// Select the colors every command draws hints in
+pub fn set_theme(theme: Theme) {
+    let index = Theme::ALL.iter().position(|&t| t == theme);
+    THEME.store(index.unwrap_or(0), Ordering::Relaxed);
+}
+
+/// The theme selected with `--color-theme`
+pub fn theme() -> Theme {
+    Theme::ALL[THEME.load(Ordering::Relaxed)]
+}
+
+/// The word kept out of the output with `--spoiler-guard`, if any
+static HIDDEN_WORD: Mutex<Option<Word>> = Mutex::new(None);
+
+/// Keep a word, such as today's daily answer, out of the words the commands print
+pub fn set_hidden_word(word: Option<Word>) {
+    *HIDDEN_WORD.lock().unwrap() = word;
+}
+
+/// Write a word for output, as question marks if it is the word kept out of the output
+pub fn shown(word: &Word) -> String {
+    about(word, word.to_string())
+}
+
+/// Text written about a word, with every letter as a question mark if it is the word kept
+/// out of the output
+fn about(word: &Word, text: String) -> String {
+    match HIDDEN_WORD.lock().unwrap().as_ref() == Some(word) {
+        true => hide_letters(&text),
+        false => text,
+    }
+}
+
+/// Replace every letter of a text with a question mark
+fn hide_letters(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_alphabetic() { '?' } else { c })
+        .collect()
+}
+
+/// Whether the terminal says it draws 24-bit colors
+pub fn truecolor() -> bool {
+    env::var("COLORTERM").is_ok_and(|v| v == "truecolor" || v == "24bit")
+}
+
+/// A letter marked with its hint in symbols, for when colors are off: "[C]" if it is
+/// correct, "(m)" if it is misplaced and ".x." if it is not in the word
+pub fn symbol_cell(letter: char, hint: LetterHint) -> String {
+    let lower = letter.to_lowercase();
+    match hint {
+        LetterHint::Correct => format!("[{}]", letter.to_uppercase()),
+        LetterHint::Misplaced => format!("({})", lower),
+        LetterHint::Incorrect => format!(".{}.", lower),
+    }
+}
+
+/// The format selected with `--format`
+pub fn format() -> OutputFormat {
+    OutputFormat::ALL[FORMAT.load(Ordering::Relaxed)]
+}
+
+/// The renderer of the format selected with `--format`
+pub fn renderer() -> &'static dyn Renderer {
+    format().renderer()
+}
+
+/// Print a table with the selected renderer
+pub fn print_table(header: &[&str], rows: &[Vec<String>]) {
+    print!("{}", renderer().table(header, rows));
+}
+
+/// The number of characters text takes up on the terminal, leaving out its color codes
+pub fn visible_width(text: &str) -> usize {
+    let mut in_escape = false;
+    text.chars()
+        .filter(|&c| {
+            match c {
+                '\x1b' => in_escape = true,
+                'm' if in_escape => {
+                    in_escape = false;
+                    return false;
+                }
+                _ => {}
+            }
+            !in_escape
+        })
+        .count()
+}
+
+/// Pad the cells into columns separated by "|", under a header and a line of dashes
+fn text_table(header: &[&str], rows: &[Vec<String>]) -> String {
+    let right = right_aligned(header, rows);
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .filter_map(|cells| cells.get(i))
+                .map(|cell| cell.trim().chars().count())
+                .chain([header[i].chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = (0..widths.len())
+            .map(|i| {
+                let cell = cells.get(i).map_or("", |c| c.trim());
+                let padding = " ".repeat(widths[i] - cell.chars().count());
+                match right[i] {
+                    true => padding + cell,
+                    false => cell.to_string() + &padding,
+                }
+            })
+            .collect();
+        padded.join(" | ").trim_end().to_string() + "\n"
+    };
+
+    let dashes: Vec<String> = widths.iter().map(|&w| "-".repeat(w)).collect();
+    let mut table = line(header.to_vec());
+    table += &(dashes.join("-|-") + "\n");
+    for cells in rows {
+        table += &line(cells.iter().map(String::as_str).collect());
+    }
+    table
+}
+
+/// Whether each column holds only numbers, such as "12", "3.5" or "98.267%", or "-" for none
+fn right_aligned(header: &[&str], rows: &[Vec<String>]) -> Vec<bool> {
+    (0..header.len())
+        .map(|i| {
+            let mut cells = rows
+                .iter()
+                .filter_map(|cells| cells.get(i))
+                .map(|cell| cell.trim())
+                .filter(|cell| !cell.is_empty() && *cell != "-")
+                .peekable();
+            cells.peek().is_some()
+                && cells.all(|cell| cell.trim_end_matches('%').parse::<f64>().is_ok())
+        })
+        .collect()
+}
+
+/// Escape the characters that HTML gives a meaning to
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Keeps a word out of the output until it is dropped, so that a failed test does not
+    /// leave it hidden for the tests after it
+    struct HiddenWord;
+
+    impl HiddenWord {
+        fn new(word: &Word) -> Self {
+            set_hidden_word(Some(word.clone()));
+            Self
+        }
+    }
+
+    impl Drop for HiddenWord {
+        fn drop(&mut self) {
+            set_hidden_word(None);
+        }
+    }
+
+    #[test]
+    fn only_the_hidden_word_is_masked() {
+        let (daily, other) = (
+            Word::from_string("qajaq").unwrap(),
+            Word::from_string("slate").unwrap(),
+        );
+        let hint = Hint::from_guess_and_answer(&daily, &other).unwrap();
+        {
+            let _hidden = HiddenWord::new(&daily);
+            assert_eq!(shown(&daily), "?????");
+            assert_eq!(shown(&other), "SLATE");
+            assert_eq!(Plain.hint(&hint, &daily), "????? _*___");
+            assert_eq!(Markdown.hint(&hint, &daily), "⬛🟨⬛⬛⬛ `?????`");
+            assert_eq!(Plain.hint(&hint, &other), "SLATE _*___");
+        }
+        assert_eq!(shown(&daily), "QAJAQ");
+    }
+
+    #[test]
+    fn hiding_letters_keeps_the_marks_around_them() {
+        assert_eq!(hide_letters("CRANE c*a__"), "????? ?*?__");
+    }
+}
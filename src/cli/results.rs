@@ -0,0 +1,490 @@
+//! Results files of benchmarks and tournaments, for analysis outside Rudle
+//!
+//! A results file is JSON lines, described by `rudle schema results`. The first line
+//! describes the run, and the lines after it are one of:
+//! * `{"type": "entrant", "name": "entropy", "kind": "strategy", "opener": "SALET"}` for each
+//!   strategy or bot that played
+//! * `{"type": "game", "entrant": "entropy", "answer": "CRANE", "guesses": 3, "elapsed_ms": 41}`
+//!   for each game played, with `guesses` null if the game was not solved
+//! * `{"type": "environment", "os": "linux", ...}` after the run line of an experiment,
+//!   describing the build and the machine it ran on
+//! * `{"type": "end", "complete": true}` as the last line, once the run is over
+//!
+//! Lines of types a reader does not know are skipped, so new ones can be added without
+//! breaking older readers.
+
+use rudle::hash::word_list_hash;
+use rudle::{Word, WordLists};
+use serde_json::{json, Value};
+use std::env;
+use std::fs::File;
+use std::io::{self, Write};
+use std::sync::Mutex;
+
+/// The version of the results format, described by `rudle schema results`
+pub const RESULTS_VERSION: u64 = 1;
+
+/// What a run was: the command, the word lists and the parameters the games were played with
+pub struct RunInfo {
+    /// The command that wrote the file, such as "bench" or "tournament"
+    pub command: String,
+    pub rudle_version: String,
+    /// The `word_list_hash` of the guesses and of the answers
+    pub guesses_hash: u64,
+    pub answers_hash: u64,
+    pub n_guesses: usize,
+    pub n_answers: usize,
+    pub word_size: usize,
+    pub max_attempts: usize,
+    /// The seed of the random choices, if they were seeded
+    pub seed: Option<u64>,
+    /// The options of the command that the results depend on, such as the noise of `bench`
+    pub parameters: Value,
+}
+
+impl RunInfo {
+    pub fn new(
+        command: &str,
+        words: &WordLists,
+        max_attempts: usize,
+        seed: Option<u64>,
+        parameters: Value,
+    ) -> Self {
+        Self {
+            command: command.to_string(),
+            rudle_version: env!("CARGO_PKG_VERSION").to_string(),
+            guesses_hash: word_list_hash(&words.guesses),
+            answers_hash: word_list_hash(&words.answers),
+            n_guesses: words.guesses.len(),
+            n_answers: words.answers.len(),
+            word_size: words.guesses.first().map_or(0, Word::len),
+            max_attempts,
+            seed,
+            parameters,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({
+            "type": "run",
+            "version": RESULTS_VERSION,
+            "command": self.command,
+            "rudle_version": self.rudle_version,
+            "word_lists": {
+                "guesses": format!("{:016x}", self.guesses_hash),
+                "answers": format!("{:016x}", self.answers_hash),
+                "n_guesses": self.n_guesses,
+                "n_answers": self.n_answers,
+            },
+            "word_size": self.word_size,
+            "max_attempts": self.max_attempts,
+            "seed": self.seed,
+            "parameters": self.parameters,
+        })
+    }
+
+    fn from_json(line: &Value) -> Result<Self, String> {
+        match (line["type"].as_str(), line["version"].as_u64()) {
+            (Some("run"), Some(RESULTS_VERSION)) => {}
+            (Some("run"), Some(version)) => {
+                return Err(format!("unsupported results version {}", version))
+            }
+            _ => return Err("not a results file".to_string()),
+        }
+        let string = |value: &Value, field: &str| {
+            value[field]
+                .as_str()
+                .map(str::to_string)
+                .ok_or_else(|| format!("the run has no {}", field))
+        };
+        let number = |value: &Value, field: &str| {
+            value[field]
+                .as_u64()
+                .ok_or_else(|| format!("the run has no {}", field))
+        };
+        let hash = |field: &str| {
+            let hash = string(&line["word_lists"], field)?;
+            u64::from_str_radix(&hash, 16).map_err(|e| format!("{} hash '{}': {}", field, hash, e))
+        };
+        Ok(Self {
+            command: string(line, "command")?,
+            rudle_version: string(line, "rudle_version")?,
+            guesses_hash: hash("guesses")?,
+            answers_hash: hash("answers")?,
+            n_guesses: number(&line["word_lists"], "n_guesses")? as usize,
+            n_answers: number(&line["word_lists"], "n_answers")? as usize,
+            word_size: number(line, "word_size")? as usize,
+            max_attempts: number(line, "max_attempts")? as usize,
+            seed: line["seed"].as_u64(),
+            parameters: line["parameters"].clone(),
+        })
+    }
+}
+
+/// A strategy or bot that played in a run
+pub struct Entrant {
+    pub name: String,
+    /// "strategy" for a built-in strategy, "bot" for a bot run as a child process
+    pub kind: String,
+    /// The first guess of every game, if it is always the same
+    pub opener: Option<Word>,
+}
+
+impl Entrant {
+    fn to_json(&self) -> Value {
+        json!({
+            "type": "entrant",
+            "name": self.name,
+            "kind": self.kind,
+            "opener": self.opener.as_ref().map(Word::to_string),
+        })
+    }
+
+    fn from_json(line: &Value) -> Result<Self, String> {
+        Ok(Self {
+            name: line["name"]
+                .as_str()
+                .ok_or("an entrant has no name")?
+                .to_string(),
+            kind: line["kind"].as_str().unwrap_or("strategy").to_string(),
+            opener: match line["opener"].as_str() {
+                Some(opener) => Some(Word::from_string(opener).map_err(|e| e.to_string())?),
+                None => None,
+            },
+        })
+    }
+}
+
+/// A game one entrant played
+pub struct GameResult {
+    pub entrant: String,
+    pub answer: Word,
+    /// The number of guesses it took, or None if it was not solved
+    pub guesses: Option<usize>,
+    /// How long the game took, or None if timings were left out for reproducible output
+    pub elapsed_ms: Option<u64>,
+}
+
+impl GameResult {
+    fn to_json(&self) -> Value {
+        json!({
+            "type": "game",
+            "entrant": self.entrant,
+            "answer": self.answer.to_string(),
+            "guesses": self.guesses,
+            "elapsed_ms": self.elapsed_ms,
+        })
+    }
+
+    fn from_json(line: &Value) -> Result<Self, String> {
+        let answer = line["answer"].as_str().ok_or("a game has no answer")?;
+        Ok(Self {
+            entrant: line["entrant"]
+                .as_str()
+                .ok_or("a game has no entrant")?
+                .to_string(),
+            answer: Word::from_string(answer).map_err(|e| e.to_string())?,
+            guesses: line["guesses"].as_u64().map(|n| n as usize),
+            elapsed_ms: line["elapsed_ms"].as_u64(),
+        })
+    }
+}
+
+/// Where a run was made: the build of Rudle, the machine and the command line, which
+/// experiments record so that their results can be reproduced
+pub fn environment() -> Value {
+    json!({
+        "type": "environment",
+        "rudle_version": env!("CARGO_PKG_VERSION"),
+        "build": if cfg!(debug_assertions) { "debug" } else { "release" },
+        "os": env::consts::OS,
+        "arch": env::consts::ARCH,
+        "threads": rayon::current_num_threads(),
+        "arguments": env::args().collect::<Vec<String>>(),
+    })
+}
+
+/// The contents of a results file
+pub struct Results {
+    pub run: RunInfo,
+    /// The environment line, if the file has one
+    pub environment: Option<Value>,
+    pub entrants: Vec<Entrant>,
+    pub games: Vec<GameResult>,
+    /// Whether the run finished, rather than being interrupted
+    pub complete: bool,
+}
+
+impl Results {
+    /// The games an entrant played, as `bench::print_results` takes them
+    pub fn games_of(&self, entrant: &str) -> Vec<(&Word, Option<usize>)> {
+        self.games
+            .iter()
+            .filter(|game| game.entrant == entrant)
+            .map(|game| (&game.answer, game.guesses))
+            .collect()
+    }
+}
+
+/// Read a results file, including one whose run was interrupted
+/// A last line cut short by the interruption is skipped.
+pub fn read_results(path: &str) -> Result<Results, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty());
+    let (_, first) = lines.next().ok_or("the file is empty")?;
+    let first: Value = serde_json::from_str(first).map_err(|_| "not a results file")?;
+    let mut results = Results {
+        run: RunInfo::from_json(&first)?,
+        environment: None,
+        entrants: vec![],
+        games: vec![],
+        complete: false,
+    };
+
+    let n_lines = text.lines().count();
+    for (i, line) in lines {
+        let line: Value = match serde_json::from_str(line) {
+            Ok(line) => line,
+            Err(_) if i + 1 == n_lines && !text.ends_with('\n') => break,
+            Err(e) => return Err(format!("line {}: {}", i + 1, e)),
+        };
+        let at_line = |e: String| format!("line {}: {}", i + 1, e);
+        match line["type"].as_str() {
+            Some("entrant") => results
+                .entrants
+                .push(Entrant::from_json(&line).map_err(at_line)?),
+            Some("game") => results
+                .games
+                .push(GameResult::from_json(&line).map_err(at_line)?),
+            Some("environment") => results.environment = Some(line),
+            Some("end") => results.complete = line["complete"].as_bool().unwrap_or(true),
+            _ => {}
+        }
+    }
+    Ok(results)
+}
+
+/// Writes a results file one line at a time, flushing every line so that the file holds
+/// everything written so far if the program stops
+///
+/// The writer can be shared between threads that play games in parallel. The first failed
+/// write prints a warning, and stops any more from being tried.
+pub struct ResultsWriter {
+    path: String,
+    file: Mutex<Option<File>>,
+}
+
+impl ResultsWriter {
+    /// Create the file and write the description of the run
+    pub fn create(path: &str, run: &RunInfo) -> io::Result<Self> {
+        let writer = Self {
+            path: path.to_string(),
+            file: Mutex::new(Some(File::create(path)?)),
+        };
+        writer.write(&run.to_json());
+        Ok(writer)
+    }
+
+    /// Write the environment line, made by `environment`
+    pub fn environment(&self, environment: &Value) {
+        self.write(environment);
+    }
+
+    pub fn entrant(&self, entrant: &Entrant) {
+        self.write(&entrant.to_json());
+    }
+
+    pub fn game(&self, game: &GameResult) {
+        self.write(&game.to_json());
+    }
+
+    /// Write the last line, which tells whether every game of the run was played
+    pub fn finish(&self, complete: bool) {
+        self.write(&json!({ "type": "end", "complete": complete }));
+    }
+
+    fn write(&self, line: &Value) {
+        let mut file = self.file.lock().unwrap();
+        let Some(f) = file.as_mut() else {
+            return;
+        };
+        if let Err(e) = writeln!(f, "{}", line).and_then(|()| f.flush()) {
+            println!("Warning: failed to write to {}: {}", self.path, e);
+            *file = None;
+        }
+    }
+}
+
+/// Create a results file if a path was given
+/// # Returns
+/// The writer, or None if no path was given or the file could not be created, which is
+/// reported
+pub fn create_results(path: Option<&str>, run: &RunInfo) -> Option<ResultsWriter> {
+    let path = path?;
+    match ResultsWriter::create(path, run) {
+        Ok(writer) => Some(writer),
+        Err(e) => {
+            println!("Warning: failed to create {}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Print the results of every entrant in a results file
+pub fn print_results_file(path: &str) {
+    let results = match read_results(path) {
+        Ok(results) => results,
+        Err(e) => {
+            println!("Warning: could not read {}: {}", path, e);
+            return;
+        }
+    };
+    let run = &results.run;
+    println!(
+        "{} run of Rudle {} over {} answers and {} guesses (lists {:016x} and {:016x}), at most \
+         {} guesses a game{}.",
+        run.command,
+        run.rudle_version,
+        run.n_answers,
+        run.n_guesses,
+        run.answers_hash,
+        run.guesses_hash,
+        run.max_attempts,
+        run.seed
+            .map_or(String::new(), |seed| format!(", seed {}", seed))
+    );
+    if let Some(environment) = &results.environment {
+        println!(
+            "Made with a {} build on {} {}, threads: {}, command: {}",
+            environment["build"].as_str().unwrap_or("?"),
+            environment["os"].as_str().unwrap_or("?"),
+            environment["arch"].as_str().unwrap_or("?"),
+            environment["threads"],
+            environment["arguments"]
+                .as_array()
+                .map_or(String::new(), |args| args
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .collect::<Vec<&str>>()
+                    .join(" "))
+        );
+    }
+    if !results.complete {
+        println!("The run was interrupted, so these are the games played before it stopped.");
+    }
+    for entrant in results.entrants.iter() {
+        let games = results.games_of(&entrant.name);
+        println!(
+            "\n{} ({}{}): {} games",
+            entrant.name,
+            entrant.kind,
+            entrant
+                .opener
+                .as_ref()
+                .map_or(String::new(), |opener| format!(", opening with {}", opener)),
+            games.len()
+        );
+        if !games.is_empty() {
+            super::bench::print_results(&games, run.max_attempts);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(w: &str) -> Word {
+        Word::from_string(w).unwrap()
+    }
+
+    fn temp_path(name: &str) -> String {
+        let file = format!("rudle-{}-{}.jsonl", name, std::process::id());
+        std::env::temp_dir()
+            .join(file)
+            .to_str()
+            .unwrap()
+            .to_string()
+    }
+
+    fn run() -> RunInfo {
+        let words = WordLists::single(vec![word("crane"), word("slate")]);
+        RunInfo::new("bench", &words, 6, Some(7), json!({ "noise": 0.0 }))
+    }
+
+    #[test]
+    fn results_are_read_back_as_written() {
+        let path = temp_path("results");
+        let writer = ResultsWriter::create(&path, &run()).unwrap();
+        writer.entrant(&Entrant {
+            name: "entropy".to_string(),
+            kind: "strategy".to_string(),
+            opener: Some(word("crane")),
+        });
+        for (answer, guesses) in [("crane", Some(1)), ("slate", None)] {
+            writer.game(&GameResult {
+                entrant: "entropy".to_string(),
+                answer: word(answer),
+                guesses,
+                elapsed_ms: None,
+            });
+        }
+        writer.finish(true);
+
+        let results = read_results(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(results.complete);
+        assert_eq!(results.run.command, "bench");
+        assert_eq!(results.run.seed, Some(7));
+        assert_eq!(results.run.n_answers, 2);
+        assert_eq!(results.run.word_size, 5);
+        assert_eq!(results.run.parameters["noise"], 0.0);
+        assert_eq!(
+            results.run.answers_hash,
+            word_list_hash(&[word("crane"), word("slate")])
+        );
+        assert_eq!(results.entrants[0].opener, Some(word("crane")));
+        assert_eq!(
+            results.games_of("entropy"),
+            [(&word("crane"), Some(1)), (&word("slate"), None)]
+        );
+    }
+
+    #[test]
+    fn interrupted_runs_are_read_up_to_the_last_whole_line() {
+        let path = temp_path("partial");
+        let writer = ResultsWriter::create(&path, &run()).unwrap();
+        writer.game(&GameResult {
+            entrant: "entropy".to_string(),
+            answer: word("crane"),
+            guesses: Some(1),
+            elapsed_ms: Some(3),
+        });
+        drop(writer);
+        let mut text = std::fs::read_to_string(&path).unwrap();
+        text.push_str(r#"{"type": "game", "entrant": "entr"#);
+        std::fs::write(&path, text).unwrap();
+
+        let results = read_results(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!results.complete);
+        assert_eq!(results.games.len(), 1);
+        assert_eq!(results.games[0].elapsed_ms, Some(3));
+    }
+
+    #[test]
+    fn other_files_are_not_read_as_results() {
+        let path = temp_path("other");
+        std::fs::write(&path, "{\"version\": 1, \"tree\": {}}\n").unwrap();
+        assert!(read_results(&path).is_err());
+        std::fs::write(&path, "{\"type\": \"run\", \"version\": 99}\n").unwrap();
+        assert!(read_results(&path)
+            .err()
+            .unwrap()
+            .contains("unsupported results version"));
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,155 @@
+use super::persist::{self, Format};
+use super::{current_day, data_dir};
+use crate::cli::play::{make_picker, play_game};
+use crate::{CommonArgs, PlayArgs};
+use rudle::picker::AnswerPicker;
+use rudle::{Word, WordLists};
+use std::io;
+use std::path::PathBuf;
+
+/// Play a game that preferentially picks words the player struggled with before
+pub fn review(words: WordLists, common: &CommonArgs, options: &PlayArgs) {
+    let mut picker = ReviewPicker {
+        queue: ReviewQueue::load(),
+        fallback: make_picker(common, options),
+    };
+    let (secret_word, guesses_used) = play_game(&words, &mut picker, common, options);
+    update_review_queue(&secret_word, guesses_used, options);
+}
+
+/// Pick the most overdue review word, or let another picker choose when none is due
+struct ReviewPicker {
+    queue: ReviewQueue,
+    fallback: Box<dyn AnswerPicker>,
+}
+
+impl AnswerPicker for ReviewPicker {
+    fn pick(&mut self, answers: &[Word]) -> Option<Word> {
+        match self.queue.next_due(answers, current_day()) {
+            Some(word) => {
+                println!("Reviewing a word you struggled with before.\n");
+                Some(word.clone())
+            }
+            None => {
+                println!("No words are due for review. Playing a random word instead.\n");
+                self.fallback.pick(answers)
+            }
+        }
+    }
+
+    fn respond(&mut self, secret: &Word, guess: &Word, remaining: &[Word]) -> Word {
+        self.fallback.respond(secret, guess, remaining)
+    }
+}
+
+/// Schedule the secret word for review if the game was lost or won on the last attempt
+pub fn update_review_queue(secret_word: &Word, guesses_used: Option<usize>, options: &PlayArgs) {
+    let struggled = guesses_used.is_none_or(|n| n >= options.max_attempts);
+    let mut queue = ReviewQueue::load();
+    queue.record(secret_word, struggled, current_day());
+    if let Err(e) = queue.save() {
+        println!("Warning: failed to save the review queue: {}", e);
+    }
+}
+
+/// Days to wait before serving a word again, indexed by how many times in a row it has been
+/// solved comfortably since it was last missed
+const REVIEW_INTERVALS: [u64; 5] = [1, 2, 4, 8, 16];
+
+/// The review queue file format
+const REVIEW_FORMAT: Format = Format {
+    name: "review",
+    migrations: &[persist::unchanged],
+};
+
+/// A word scheduled for review
+struct ReviewEntry {
+    word: Word,
+    level: usize,
+    due_day: u64,
+}
+
+/// Words the player failed or only just solved, re-served at growing intervals
+///
+/// The queue is stored as one `WORD LEVEL DUE_DAY` line per word in the Rudle data directory.
+struct ReviewQueue {
+    entries: Vec<ReviewEntry>,
+}
+
+impl ReviewQueue {
+    fn path() -> Option<PathBuf> {
+        data_dir().map(|dir| dir.join("review.txt"))
+    }
+
+    /// Load the queue, treating a missing or unreadable file as an empty queue
+    fn load() -> Self {
+        let contents = Self::path()
+            .and_then(|path| REVIEW_FORMAT.load(&path))
+            .unwrap_or_default();
+
+        let entries = contents
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let word = Word::from_string(fields.next()?).ok()?;
+                let level = fields.next()?.parse().ok()?;
+                let due_day = fields.next()?.parse().ok()?;
+                Some(ReviewEntry {
+                    word,
+                    level,
+                    due_day,
+                })
+            })
+            .collect();
+
+        Self { entries }
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let path = Self::path()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+        let contents: String = self
+            .entries
+            .iter()
+            .map(|e| format!("{} {} {}\n", e.word, e.level, e.due_day))
+            .collect();
+        REVIEW_FORMAT.save(&path, &contents)
+    }
+
+    /// The most overdue word that is in the word list, if any word is due
+    fn next_due(&self, word_list: &[Word], today: u64) -> Option<&Word> {
+        self.entries
+            .iter()
+            .filter(|e| e.due_day <= today && word_list.contains(&e.word))
+            .min_by_key(|e| e.due_day)
+            .map(|e| &e.word)
+    }
+
+    /// Record the outcome of a game
+    /// A struggle (re)starts the word's schedule; a comfortable solve moves it to the
+    /// next interval, and the word leaves the queue once it passes the last interval.
+    fn record(&mut self, word: &Word, struggled: bool, today: u64) {
+        let position = self.entries.iter().position(|e| &e.word == word);
+        match (position, struggled) {
+            (Some(i), true) => {
+                self.entries[i].level = 0;
+                self.entries[i].due_day = today + REVIEW_INTERVALS[0];
+            }
+            (None, true) => self.entries.push(ReviewEntry {
+                word: word.clone(),
+                level: 0,
+                due_day: today + REVIEW_INTERVALS[0],
+            }),
+            (Some(i), false) => {
+                let level = self.entries[i].level + 1;
+                if level >= REVIEW_INTERVALS.len() {
+                    self.entries.remove(i);
+                } else {
+                    self.entries[i].level = level;
+                    self.entries[i].due_day = today + REVIEW_INTERVALS[level];
+                }
+            }
+            (None, false) => {}
+        }
+    }
+}
@@ -0,0 +1,490 @@
+//! JSON Schemas describing Rudle's machine-readable outputs
+
+use super::attest::ATTESTATION_VERSION;
+use super::export::TREE_VERSION;
+use super::overlay::OVERLAY_VERSION;
+use super::results::RESULTS_VERSION;
+use super::solve::SESSION_VERSION;
+use super::tournament::BOT_PROTOCOL_VERSION;
+use serde_json::{json, Value};
+
+/// The outputs that have a schema, by the name `rudle schema` takes
+pub const SCHEMA_NAMES: [&str; 6] = [
+    "attestation",
+    "bot",
+    "overlay",
+    "results",
+    "session",
+    "tree",
+];
+
+/// Print the JSON Schema of an output, or list the available ones
+pub fn schema(name: &str) {
+    match schema_for(name) {
+        Some(schema) => println!("{:#}", schema),
+        None => println!(
+            "Unknown schema '{}'. Available schemas: {}",
+            name,
+            SCHEMA_NAMES.join(", ")
+        ),
+    }
+}
+
+/// The schema of an output
+/// Outputs carry a `version` field matching the version in the schema's `$id`, which is
+/// bumped whenever a change would break existing consumers.
+pub fn schema_for(name: &str) -> Option<Value> {
+    match name {
+        "attestation" => Some(attestation_schema()),
+        "bot" => Some(bot_schema()),
+        "overlay" => Some(overlay_schema()),
+        "results" => Some(results_schema()),
+        "session" => Some(session_schema()),
+        "tree" => Some(tree_schema()),
+        _ => None,
+    }
+}
+
+fn attestation_schema() -> Value {
+    let id = format!(
+        "https://github.com/ianpotpie/Rudle/schemas/attestation/v{}.json",
+        ATTESTATION_VERSION
+    );
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": id,
+        "title": "Rudle game attestation",
+        "description": "The record of a game written by `rudle play --attest <FILE>`",
+        "type": "object",
+        "required": [
+            "version", "rudle_version", "puzzle", "mode", "max_attempts", "guesses", "result",
+            "assists", "digest"
+        ],
+        "properties": {
+            "version": { "const": ATTESTATION_VERSION },
+            "rudle_version": { "type": "string" },
+            "puzzle": {
+                "type": "object",
+                "required": ["pick", "seed_hash"],
+                "properties": {
+                    "pick": {
+                        "enum": ["uniform", "weighted", "daily", "never-repeat", "adversarial", "adaptive"],
+                    },
+                    "seed_hash": {
+                        "description": "FNV-1a of the answer list and the seed, the same for \
+                                        every game of a daily puzzle or --seed; null if the secret word \
+                                        cannot be picked again",
+                        "type": ["string", "null"],
+                        "pattern": "^[0-9a-f]{16}$",
+                    },
+                },
+            },
+            "mode": { "enum": ["easy", "hard", "ultra-hard"] },
+            "max_attempts": { "type": "integer", "minimum": 1 },
+            "guesses": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["guess", "hint", "elapsed_ms"],
+                    "properties": {
+                        "guess": { "type": "string", "pattern": "^[A-Z]+$" },
+                        "hint": { "type": "string", "pattern": "^[a-z*_]+$" },
+                        "elapsed_ms": {
+                            "description": "The time from the start of the game to the guess",
+                            "type": "integer",
+                            "minimum": 0,
+                        },
+                    },
+                },
+            },
+            "result": {
+                "description": "The number of guesses it took, null if the game was lost",
+                "type": ["integer", "null"],
+                "minimum": 1,
+            },
+            "assists": {
+                "description": "The solver help that was on during the game",
+                "type": "object",
+                "properties": {
+                    "meter": { "enum": ["off", "live", "post"] },
+                    "post_mortem": { "type": "boolean" },
+                    "assist": { "type": "boolean" },
+                },
+            },
+            "digest": {
+                "description": "FNV-1a of the compact JSON of the other fields, keys sorted",
+                "type": "string",
+                "pattern": "^[0-9a-f]{16}$",
+            },
+        },
+    })
+}
+
+fn bot_schema() -> Value {
+    let id = format!(
+        "https://github.com/ianpotpie/Rudle/schemas/bot/v{}.json",
+        BOT_PROTOCOL_VERSION
+    );
+    let word = json!({ "type": "string", "pattern": "^[A-Z]+$" });
+    let words = json!({ "type": "array", "items": word });
+    let game = json!({ "type": "integer", "minimum": 1 });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": id,
+        "title": "Rudle bot message",
+        "description": "A line `rudle tournament` writes to a --bot, which answers each `guess` \
+                        message with a line {\"guess\": \"WORD\"}",
+        "oneOf": [
+            {
+                "description": "Sent once, before the first game",
+                "type": "object",
+                "required": ["type", "version", "word_size", "max_attempts", "guesses", "answers"],
+                "properties": {
+                    "type": { "const": "start" },
+                    "version": { "const": BOT_PROTOCOL_VERSION },
+                    "word_size": { "type": "integer", "minimum": 1 },
+                    "max_attempts": { "type": "integer", "minimum": 1 },
+                    "guesses": words,
+                    "answers": words,
+                },
+            },
+            {
+                "description": "Sent before each guess, with the guesses of the game so far",
+                "type": "object",
+                "required": ["type", "game", "history"],
+                "properties": {
+                    "type": { "const": "guess" },
+                    "game": game,
+                    "history": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["guess", "hint"],
+                            "properties": {
+                                "guess": word,
+                                "hint": {
+                                    "description": "g for a correct letter, y for a misplaced \
+                                                    one and b for one not in the word",
+                                    "type": "string",
+                                    "pattern": "^[gyb]+$",
+                                },
+                            },
+                        },
+                    },
+                },
+            },
+            {
+                "description": "Sent after each game",
+                "type": "object",
+                "required": ["type", "game", "answer", "solved", "guesses"],
+                "properties": {
+                    "type": { "const": "result" },
+                    "game": game,
+                    "answer": word,
+                    "solved": { "type": "boolean" },
+                    "guesses": { "type": "integer", "minimum": 0 },
+                },
+            },
+        ],
+    })
+}
+
+fn overlay_schema() -> Value {
+    let id = format!(
+        "https://github.com/ianpotpie/Rudle/schemas/overlay/v{}.json",
+        OVERLAY_VERSION
+    );
+    let letter_hint = json!({ "enum": ["correct", "misplaced", "incorrect"] });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": id,
+        "title": "Rudle streaming overlay",
+        "description": "The board written by `rudle play --obs-output <FILE>.json`",
+        "type": "object",
+        "required": [
+            "version", "status", "attempts", "max_attempts", "rows", "keyboard", "answer"
+        ],
+        "properties": {
+            "version": { "const": OVERLAY_VERSION },
+            "status": { "enum": ["playing", "won", "lost"] },
+            "attempts": { "type": "integer", "minimum": 0 },
+            "max_attempts": { "type": "integer", "minimum": 1 },
+            "rows": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "required": ["guess", "hint"],
+                    "properties": {
+                        "guess": { "type": "string", "pattern": "^[A-Z]+$" },
+                        "hint": { "type": "array", "items": letter_hint },
+                    },
+                },
+            },
+            "keyboard": {
+                "description": "The best feedback seen for every guessed letter",
+                "type": "object",
+                "propertyNames": { "pattern": "^[A-Z]$" },
+                "additionalProperties": letter_hint,
+            },
+            "answer": {
+                "description": "The secret word, only given once the game is over",
+                "type": ["string", "null"],
+            },
+        },
+    })
+}
+
+fn session_schema() -> Value {
+    let id = format!(
+        "https://github.com/ianpotpie/Rudle/schemas/session/v{}.json",
+        SESSION_VERSION
+    );
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": id,
+        "title": "Rudle solver session",
+        "description": "The session written by the `save <FILE>` command of `rudle solve`",
+        "type": "object",
+        "required": ["version", "word_size", "steps", "remaining", "settings"],
+        "properties": {
+            "version": { "const": SESSION_VERSION },
+            "word_size": { "type": "integer", "minimum": 1 },
+            "steps": {
+                "description": "The hints and filters in the order they were applied",
+                "type": "array",
+                "items": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "required": ["guess", "hint"],
+                            "properties": {
+                                "guess": { "type": "string", "pattern": "^[A-Z]+$" },
+                                "hint": { "type": "string", "pattern": "^[a-z*_]+$" },
+                            },
+                        },
+                        {
+                            "type": "object",
+                            "required": ["filter"],
+                            "properties": {
+                                "filter": {
+                                    "description": "The arguments of `filter`",
+                                    "type": "string",
+                                    "pattern": "^(regex|contains|excludes) ",
+                                },
+                            },
+                        },
+                    ],
+                },
+            },
+            "remaining": {
+                "description": "The answers still possible when the session was saved",
+                "type": "array",
+                "items": { "type": "string", "pattern": "^[A-Z]+$" },
+            },
+            "settings": {
+                "type": "object",
+                "properties": {
+                    "rules": { "enum": ["easy", "hard", "ultra-hard"] },
+                    "vocab": { "enum": ["mine", "all"] },
+                    "budget_ms": {
+                        "description": "The scoring budget of `set budget`, null if off",
+                        "type": ["integer", "null"],
+                        "minimum": 0,
+                    },
+                },
+            },
+        },
+    })
+}
+
+fn results_schema() -> Value {
+    let id = format!(
+        "https://github.com/ianpotpie/Rudle/schemas/results/v{}.json",
+        RESULTS_VERSION
+    );
+    let hash = json!({ "type": "string", "pattern": "^[0-9a-f]{16}$" });
+    let count = json!({ "type": "integer", "minimum": 0 });
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": id,
+        "title": "Rudle results line",
+        "description": "A line of the results file written by `rudle bench --output <FILE>`, \
+                        `rudle tournament --output <FILE>` or `rudle experiment run`; the \
+                        first line is the run, and a file without an end line is from a run \
+                        that was interrupted",
+        "oneOf": [
+            {
+                "description": "The first line, describing the run",
+                "type": "object",
+                "required": [
+                    "type", "version", "command", "rudle_version", "word_lists", "word_size",
+                    "max_attempts", "seed", "parameters"
+                ],
+                "properties": {
+                    "type": { "const": "run" },
+                    "version": { "const": RESULTS_VERSION },
+                    "command": { "enum": ["bench", "experiment", "tournament"] },
+                    "rudle_version": { "type": "string" },
+                    "word_lists": {
+                        "description": "FNV-1a of each list written one word per line, and \
+                                        the number of words in it",
+                        "type": "object",
+                        "required": ["guesses", "answers", "n_guesses", "n_answers"],
+                        "properties": {
+                            "guesses": hash,
+                            "answers": hash,
+                            "n_guesses": count,
+                            "n_answers": count,
+                        },
+                    },
+                    "word_size": { "type": "integer", "minimum": 1 },
+                    "max_attempts": { "type": "integer", "minimum": 1 },
+                    "seed": {
+                        "description": "The seed of the random choices, null if not seeded",
+                        "type": ["integer", "null"],
+                        "minimum": 0,
+                    },
+                    "parameters": {
+                        "description": "The options the results depend on, such as the noise \
+                                        of `bench` or the strategies and seeds of an experiment",
+                        "type": "object",
+                    },
+                },
+            },
+            {
+                "description": "The build and machine an experiment ran on, after the run line",
+                "type": "object",
+                "required": [
+                    "type", "rudle_version", "build", "os", "arch", "threads", "arguments"
+                ],
+                "properties": {
+                    "type": { "const": "environment" },
+                    "rudle_version": { "type": "string" },
+                    "build": { "enum": ["debug", "release"] },
+                    "os": { "type": "string" },
+                    "arch": { "type": "string" },
+                    "threads": {
+                        "description": "The threads the games were played on",
+                        "type": "integer",
+                        "minimum": 1,
+                    },
+                    "arguments": {
+                        "description": "The command line, starting with the program",
+                        "type": "array",
+                        "items": { "type": "string" },
+                    },
+                },
+            },
+            {
+                "description": "A strategy or bot that played",
+                "type": "object",
+                "required": ["type", "name", "kind", "opener"],
+                "properties": {
+                    "type": { "const": "entrant" },
+                    "name": { "type": "string" },
+                    "kind": { "enum": ["strategy", "bot"] },
+                    "opener": {
+                        "description": "The first guess of every game, null for a bot",
+                        "type": ["string", "null"],
+                        "pattern": "^[A-Z]+$",
+                    },
+                },
+            },
+            {
+                "description": "A game an entrant played",
+                "type": "object",
+                "required": ["type", "entrant", "answer", "guesses", "elapsed_ms"],
+                "properties": {
+                    "type": { "const": "game" },
+                    "entrant": { "type": "string" },
+                    "answer": { "type": "string", "pattern": "^[A-Z]+$" },
+                    "guesses": {
+                        "description": "The guesses it took, null if it was not solved",
+                        "type": ["integer", "null"],
+                        "minimum": 1,
+                    },
+                    "elapsed_ms": {
+                        "description": "The time the game took, null with --deterministic",
+                        "type": ["integer", "null"],
+                        "minimum": 0,
+                    },
+                },
+            },
+            {
+                "description": "The last line, once the run is over",
+                "type": "object",
+                "required": ["type", "complete"],
+                "properties": {
+                    "type": { "const": "end" },
+                    "complete": {
+                        "description": "Whether every game of the run was played",
+                        "type": "boolean",
+                    },
+                },
+            },
+        ],
+    })
+}
+
+fn tree_schema() -> Value {
+    let id = format!(
+        "https://github.com/ianpotpie/Rudle/schemas/tree/v{}.json",
+        TREE_VERSION
+    );
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": id,
+        "title": "Rudle decision tree",
+        "description": "The decision tree written by `rudle export-tree <FILE>.json`",
+        "type": "object",
+        "required": ["version", "strategy", "max_attempts", "tree"],
+        "properties": {
+            "version": { "const": TREE_VERSION },
+            "strategy": {
+                "description": "The strategy that was exported, or \"optimal\"",
+                "type": "string",
+            },
+            "max_attempts": { "type": "integer", "minimum": 1 },
+            "complete": {
+                "description": "False if the export was interrupted, so that the branches \
+                                not built yet are given up on",
+                "type": "boolean",
+            },
+            "tree": { "$ref": "#/$defs/node" },
+        },
+        "$defs": {
+            "node": {
+                "type": "object",
+                "required": ["guess", "answers", "solves", "branches"],
+                "properties": {
+                    "guess": { "type": "string", "pattern": "^[A-Z]+$" },
+                    "answers": {
+                        "description": "The number of answers still possible",
+                        "type": "integer",
+                        "minimum": 1,
+                    },
+                    "solves": {
+                        "description": "Whether the guess can be the answer",
+                        "type": "boolean",
+                    },
+                    "branches": {
+                        "description": "Every hint but the solving one, in the solver's format",
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "required": ["hint", "answers", "next"],
+                            "properties": {
+                                "hint": { "type": "string", "pattern": "^[a-z*_]+$" },
+                                "answers": { "type": "integer", "minimum": 1 },
+                                "next": {
+                                    "description": "Null if the strategy gave up",
+                                    "oneOf": [{ "$ref": "#/$defs/node" }, { "type": "null" }],
+                                },
+                            },
+                        },
+                    },
+                },
+            },
+        },
+    })
+}
@@ -0,0 +1,190 @@
+//! Checks of a build against the transcripts of known solver sessions and games
+//!
+//! Each case runs this binary with a bundled word list and scripted input, and compares
+//! what it prints with the transcript embedded when it was built. The transcripts live in
+//! the `selftest` directory and are rewritten with `rudle selftest --bless` when a change to
+//! the output is intended.
+
+use rudle::{Hint, Word};
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::iter::zip;
+use std::path::{Path, PathBuf};
+use std::process::{self, Command, Stdio};
+
+/// The word list every case plays with, in place of `{words}` in its arguments
+const WORDS: &str = include_str!("../../selftest/words.txt");
+
+/// Pairs of guesses and answers with the hints they must get, mostly repeated letters
+const HINTS: &str = include_str!("../../selftest/hints.txt");
+
+/// A run of the binary and the output it must print
+struct Case {
+    name: &'static str,
+    args: &'static [&'static str],
+    input: &'static str,
+    transcript: &'static str,
+}
+
+const CASES: [Case; 4] = [
+    Case {
+        name: "solve",
+        args: &[
+            "--deterministic",
+            "--format",
+            "plain",
+            "--file",
+            "{words}",
+            "solve",
+        ],
+        input: "hint slate _____\nremaining\ntop 3\nhint audio bbbby\nhistory\nundo\n\
+                clusters radar\nexplain geese\nlegal sheep\nset rules hard\nsuggest\n",
+        transcript: include_str!("../../selftest/solve.txt"),
+    },
+    Case {
+        name: "solve-json",
+        args: &["--deterministic", "--file", "{words}", "solve", "--json"],
+        input: "hint speed s*ee_\nscore sheep\ntop 2\nhistory\nhistory --share\n",
+        transcript: include_str!("../../selftest/solve-json.txt"),
+    },
+    Case {
+        name: "auto",
+        args: &[
+            "--deterministic",
+            "--file",
+            "{words}",
+            "solve",
+            "--auto",
+            "eerie",
+        ],
+        input: "",
+        transcript: include_str!("../../selftest/auto.txt"),
+    },
+    Case {
+        name: "play",
+        args: &[
+            "--seed", "7", "--format", "plain", "--file", "{words}", "play",
+        ],
+        input: "geese\nxyzzy\nlevel\nsweet\nspeed\ntaste\nsheep\n",
+        transcript: include_str!("../../selftest/play.txt"),
+    },
+];
+
+/// Run every case and report the ones whose output differs from their transcript, exiting
+/// with status 1 if there are any
+/// With `bless`, the transcripts in the source tree are replaced by the output instead.
+pub fn selftest(bless: bool) -> io::Result<()> {
+    let dir = env::temp_dir().join(format!("rudle-selftest-{}", process::id()));
+    fs::create_dir_all(&dir)?;
+    let words_path = dir.join("words.txt");
+    fs::write(&words_path, WORDS)?;
+
+    let mut outputs = vec![("hints", HINTS, check_hints())];
+    for case in CASES.iter() {
+        outputs.push((
+            case.name,
+            case.transcript,
+            run_case(case, &dir, &words_path)?,
+        ));
+    }
+    fs::remove_dir_all(&dir)?;
+
+    let mut n_failed = 0;
+    for (name, transcript, output) in outputs.iter() {
+        if bless {
+            let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+                .join("selftest")
+                .join(format!("{}.txt", name));
+            fs::write(&path, output)?;
+            println!("{:<12} blessed {}", name, path.display());
+            continue;
+        }
+        match first_difference(transcript, output) {
+            None => println!("{:<12} ok", name),
+            Some((line, expected, actual)) => {
+                n_failed += 1;
+                println!("{:<12} FAILED at line {}", name, line);
+                println!("  expected: {}", expected);
+                println!("  actual:   {}", actual);
+            }
+        }
+    }
+
+    if bless {
+        println!("Build again to embed the new transcripts.");
+    } else if n_failed > 0 {
+        println!("{} of {} cases failed.", n_failed, outputs.len());
+        process::exit(1);
+    } else {
+        println!("All {} cases passed.", outputs.len());
+    }
+    Ok(())
+}
+
+/// Score every guess of the hint cases against its answer, in the format of their transcript
+fn check_hints() -> String {
+    let mut output = String::new();
+    for line in HINTS.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let parsed = match fields[..] {
+            [guess, answer, ..] if !line.starts_with('#') => {
+                Word::from_string(guess).and_then(|g| Ok((g, Word::from_string(answer)?)))
+            }
+            _ => {
+                output += line;
+                output += "\n";
+                continue;
+            }
+        };
+        let hint = parsed.and_then(|(guess, answer)| {
+            let hint = Hint::from_guess_and_answer(&guess, &answer)?;
+            Ok(hint.to_pattern(&guess))
+        });
+        let hint = hint.unwrap_or_else(|e| format!("error: {}", e));
+        output += &format!("{} {} {}\n", fields[0], fields[1], hint);
+    }
+    output
+}
+
+/// Run this binary with the arguments and input of a case
+/// # Returns
+/// What it printed, with the word list's path as `{words}` again
+fn run_case(case: &Case, dir: &Path, words_path: &Path) -> io::Result<String> {
+    let words_path = words_path.to_string_lossy();
+    let args: Vec<String> = case
+        .args
+        .iter()
+        .map(|arg| arg.replace("{words}", &words_path))
+        .collect();
+    // Every case starts without saved games, settings or cached scores
+    let data_dir: PathBuf = dir.join(case.name);
+    let mut child = Command::new(env::current_exe()?)
+        .args(&args)
+        .env("XDG_DATA_HOME", &data_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(case.input.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).replace(words_path.as_ref(), "{words}"))
+}
+
+/// The first line where the output differs from the transcript, counting from 1, with the
+/// line expected and the line printed, or None if they are the same
+fn first_difference(transcript: &str, output: &str) -> Option<(usize, String, String)> {
+    let expected: Vec<&str> = transcript.lines().collect();
+    let actual: Vec<&str> = output.lines().collect();
+    let line = zip(expected.iter(), actual.iter())
+        .position(|(e, a)| e != a)
+        .or_else(|| (expected.len() != actual.len()).then(|| expected.len().min(actual.len())))?;
+    let at = |lines: &[&str]| {
+        lines
+            .get(line)
+            .map_or("(end of output)".to_string(), |l| l.to_string())
+    };
+    Some((line + 1, at(&expected), at(&actual)))
+}
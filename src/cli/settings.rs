@@ -0,0 +1,384 @@
+//! The settings of the solver REPL, changed with `set` and kept between sessions on request
+
+use super::data_dir;
+use super::persist::Format;
+use rudle::hint::FeedbackSymbols;
+use rudle::rules::Ruleset;
+use rudle::LetterHint;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The format of the saved settings, one `NAME VALUE` line each
+const SETTINGS_FORMAT: Format = Format {
+    name: "settings",
+    migrations: &[],
+};
+
+/// The names of the settings, in the order `show settings` lists them
+pub const SETTING_NAMES: [&str; 10] = [
+    "units",
+    "auto-top",
+    "theme",
+    "strictness",
+    "depth",
+    "tolerance",
+    "rules",
+    "vocab",
+    "budget",
+    "spoiler-guard",
+];
+
+/// How `top` and `score` write the share of answers a guess removes
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Units {
+    /// The percentage of the answers removed
+    Percent,
+    /// The bits of information gained, where each bit halves the answers
+    Bits,
+}
+
+impl Units {
+    pub const ALL: [Units; 2] = [Units::Percent, Units::Bits];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Units::Percent => "percent",
+            Units::Bits => "bits",
+        }
+    }
+
+    /// Write a score, the percentage of the answers removed, in these units
+    pub fn format(self, score: f32) -> String {
+        match self {
+            Units::Percent => format!("{:.3}%", score),
+            Units::Bits => format!("{:.3}", -f32::log2(1.0 - score / 100.0)),
+        }
+    }
+}
+
+impl fmt::Display for Units {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Units {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Units::ALL
+            .into_iter()
+            .find(|units| units.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Units::ALL.iter().map(|u| u.name()).collect();
+                format!(
+                    "unknown units '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Which guesses `top` ranks, and whether possible answers come first
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Strictness {
+    /// Every allowed guess, by score alone
+    Off,
+    /// Every allowed guess, with possible answers before the probe words that score the same
+    AnswersFirst,
+    /// Only the possible answers
+    Strict,
+}
+
+impl Strictness {
+    pub const ALL: [Strictness; 3] = [
+        Strictness::Off,
+        Strictness::AnswersFirst,
+        Strictness::Strict,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Strictness::Off => "off",
+            Strictness::AnswersFirst => "answers-first",
+            Strictness::Strict => "strict",
+        }
+    }
+}
+
+impl fmt::Display for Strictness {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Strictness {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Strictness::ALL
+            .into_iter()
+            .find(|strictness| strictness.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Strictness::ALL.iter().map(|s| s.name()).collect();
+                format!(
+                    "unknown strictness '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// The settings of the solver REPL
+pub struct Settings {
+    pub units: Units,
+    /// How many of the best guesses to print after each hint, or 0 to print none
+    pub auto_top: usize,
+    /// The symbols of the share grids printed by `history --share`
+    pub theme: FeedbackSymbols,
+    pub strictness: Strictness,
+    /// How many guesses ahead to look when ranking, 1 or 2
+    pub depth: u8,
+    /// How many percentage points a possible answer may score below a probe word and still
+    /// come first with `answers-first`
+    pub tolerance: f32,
+    /// The rules `suggest` follows
+    pub rules: Ruleset,
+    /// Whether `top` only recommends words from the vocabulary given with --vocab
+    pub vocab: bool,
+    /// How long scoring after each hint may take, or None to score every guess
+    pub budget: Option<Duration>,
+    /// Whether today's daily answer is masked wherever a command would print it
+    pub spoiler_guard: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            units: Units::Percent,
+            auto_top: 0,
+            theme: FeedbackSymbols::default(),
+            strictness: Strictness::Off,
+            depth: 1,
+            tolerance: 0.0,
+            rules: Ruleset::Easy,
+            vocab: false,
+            budget: None,
+            spoiler_guard: false,
+        }
+    }
+}
+
+fn settings_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("settings.txt"))
+}
+
+impl Settings {
+    /// The default settings with those saved by `set --save` on top, warning about any
+    /// saved value that is no longer valid
+    pub fn load() -> Self {
+        let mut settings = Self::default();
+        let contents = settings_path()
+            .and_then(|path| SETTINGS_FORMAT.load(&path))
+            .unwrap_or_default();
+        for line in contents.lines() {
+            let Some((name, value)) = line.split_once(' ') else {
+                continue;
+            };
+            if let Err(e) = settings.set(name, value.trim(), true) {
+                println!("Warning: ignoring the saved setting {}: {}", name, e);
+            }
+        }
+        settings
+    }
+
+    /// Save the settings, so that the next REPL starts with them
+    /// `vocab` is left out, as it follows whether the solver is started with --vocab.
+    pub fn save(&self) -> io::Result<()> {
+        let path = settings_path().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "no data directory was found")
+        })?;
+        let lines: Vec<String> = SETTING_NAMES
+            .iter()
+            .filter(|&&name| name != "vocab")
+            .map(|&name| format!("{} {}\n", name, self.value(name)))
+            .collect();
+        SETTINGS_FORMAT.save(&path, &lines.concat())
+    }
+
+    /// Change a setting, given whether a vocabulary was loaded for `vocab`
+    /// # Returns
+    /// A message describing the new setting, or why the value was rejected
+    pub fn set(&mut self, name: &str, value: &str, vocab_loaded: bool) -> Result<String, String> {
+        match name {
+            "units" => {
+                self.units = value.parse()?;
+                Ok(match self.units {
+                    Units::Percent => "Scores are the percentage of the answers a guess removes.",
+                    Units::Bits => "Scores are the bits of information a guess gives.",
+                }
+                .to_string())
+            }
+            "auto-top" => {
+                self.auto_top = match value {
+                    "off" => 0,
+                    n => n
+                        .parse()
+                        .map_err(|_| "auto-top must be a number of guesses or 'off'")?,
+                };
+                Ok(match self.auto_top {
+                    0 => "The best guesses are only printed by 'top'.".to_string(),
+                    n => format!("The best {} guesses are printed after each hint.", n),
+                })
+            }
+            "theme" => {
+                self.theme = value.parse()?;
+                Ok(format!("Share grids use {}.", self.value("theme")))
+            }
+            "strictness" => {
+                self.strictness = value.parse()?;
+                Ok(match self.strictness {
+                    Strictness::Off => "'top' ranks every allowed guess by its score.",
+                    Strictness::AnswersFirst => {
+                        "'top' ranks possible answers before probe words that score the same."
+                    }
+                    Strictness::Strict => "'top' only ranks the possible answers.",
+                }
+                .to_string())
+            }
+            "depth" => {
+                self.depth = match value {
+                    "1" => 1,
+                    "2" => 2,
+                    _ => return Err("depth must be 1 or 2".to_string()),
+                };
+                Ok(format!(
+                    "Guesses are ranked looking {} guess{} ahead.",
+                    self.depth,
+                    if self.depth == 1 { "" } else { "es" }
+                ))
+            }
+            "tolerance" => {
+                self.tolerance = value
+                    .trim_end_matches('%')
+                    .parse()
+                    .ok()
+                    .filter(|&t: &f32| t >= 0.0)
+                    .ok_or("tolerance must be a number of percentage points, e.g. '0.5'")?;
+                Ok(format!(
+                    "With answers-first, possible answers come before probe words scoring up to \
+                     {} points more.",
+                    self.tolerance
+                ))
+            }
+            "rules" => {
+                self.rules = value.parse()?;
+                Ok(format!(
+                    "Suggesting guesses allowed under the {} rules.",
+                    self.rules
+                ))
+            }
+            "vocab" => match value {
+                "mine" if !vocab_loaded => {
+                    Err("no vocabulary loaded, start the solver with --vocab <FILE>".to_string())
+                }
+                "mine" => {
+                    self.vocab = true;
+                    Ok("Only recommending words from your vocabulary.".to_string())
+                }
+                "all" => {
+                    self.vocab = false;
+                    Ok("Recommending every allowed guess.".to_string())
+                }
+                _ => Err("vocab must be 'mine' or 'all'".to_string()),
+            },
+            "budget" => {
+                self.budget = match value {
+                    "off" => None,
+                    value => Some(
+                        parse_duration(value)
+                            .ok_or("budget must look like '5s', '500ms' or 'off'")?,
+                    ),
+                };
+                Ok(match self.budget {
+                    None => "Every guess will be scored after each hint.".to_string(),
+                    Some(duration) => format!(
+                        "Scoring after each hint will stop after {:?}, best candidates first.",
+                        duration
+                    ),
+                })
+            }
+            "spoiler-guard" => {
+                self.spoiler_guard = match value {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err("spoiler-guard must be 'on' or 'off'".to_string()),
+                };
+                Ok(match self.spoiler_guard {
+                    true => "Today's daily answer is masked as ????? in the output.",
+                    false => "Today's daily answer is printed like any other word.",
+                }
+                .to_string())
+            }
+            _ => Err(format!(
+                "unknown setting '{}', expected one of: {}",
+                name,
+                SETTING_NAMES.join(", ")
+            )),
+        }
+    }
+
+    /// The value of a setting, written the way `set` reads it
+    pub fn value(&self, name: &str) -> String {
+        match name {
+            "units" => self.units.to_string(),
+            "auto-top" => match self.auto_top {
+                0 => "off".to_string(),
+                n => n.to_string(),
+            },
+            "theme" => [
+                LetterHint::Correct,
+                LetterHint::Misplaced,
+                LetterHint::Incorrect,
+            ]
+            .map(|h| self.theme.symbol(h))
+            .concat(),
+            "strictness" => self.strictness.to_string(),
+            "depth" => self.depth.to_string(),
+            "tolerance" => self.tolerance.to_string(),
+            "rules" => self.rules.to_string(),
+            "vocab" => match self.vocab {
+                true => "mine".to_string(),
+                false => "all".to_string(),
+            },
+            "budget" => match self.budget {
+                None => "off".to_string(),
+                Some(budget) => format!("{}ms", budget.as_millis()),
+            },
+            "spoiler-guard" => match self.spoiler_guard {
+                true => "on".to_string(),
+                false => "off".to_string(),
+            },
+            _ => panic!("Unknown setting {}", name),
+        }
+    }
+}
+
+/// Parse a duration such as "5s", "500ms" or "2m"
+fn parse_duration(s: &str) -> Option<Duration> {
+    let split = s.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+    let (amount, unit) = s.split_at(split);
+    let amount: f64 = amount.parse().ok()?;
+    let seconds = match unit {
+        "ms" => amount / 1000.0,
+        "s" => amount,
+        "m" => amount * 60.0,
+        _ => return None,
+    };
+    Duration::try_from_secs_f64(seconds).ok()
+}
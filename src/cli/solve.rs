@@ -0,0 +1,2467 @@
+use super::cache::ScoreCache;
+use super::export::load_tree;
+use super::filter::{make_filter, WordFilter};
+use super::heatmap::{print_heatmap, write_heatmap_html};
+use super::input::{self, LineReader};
+use super::reload::{forget_scores, ListWatcher, Reload};
+use super::render::{print_table, set_hidden_word, shown};
+use super::settings::{Settings, Strictness, SETTING_NAMES};
+use super::{current_day, make_rng, print_hint};
+use crate::{CommonArgs, SolveArgs};
+use clap::{Parser, Subcommand};
+use indicatif::ProgressBar;
+use rand::Rng;
+use rudle::hint::{parse_share_row, share_grid, FeedbackSymbols};
+use rudle::picker::{AnswerPicker, Daily};
+use rudle::rules::Ruleset;
+use rudle::solver::{
+    best_guess, collapse_equivalent, expected_guesses, get_scores_within,
+    get_weighted_scores_within, lookahead_scores, partition_key, prefilter, solvable_within,
+    suggest_quick, AnswerSet, HintMatrix, LOOKAHEAD_CANDIDATES, PREFILTER_SIZE,
+};
+use rudle::strategy::{auto_play, StrategyKind};
+use rudle::tree::DecisionTree;
+use rudle::{word, Hint, LetterHint, ParseError, Word, WordLists};
+use serde_json::{json, Value};
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::iter::zip;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Command-line arguments for the REPL
+#[derive(Parser)]
+#[command(author, version, about)]
+struct SolverArgs {
+    #[command(subcommand)]
+    command: SolverCommand,
+}
+
+/// REPL commands
+#[derive(Subcommand)]
+enum SolverCommand {
+    /// Print the top n best guesses with their scores
+    Top {
+        /// Number of guesses to print
+        n: usize,
+        /// The strictness to rank with this time, the `strictness` setting if not given
+        strictness: Option<Strictness>,
+    },
+    /// Print the score of a word
+    Score {
+        /// The word to score
+        word: String,
+    },
+    /// Add a word and its hint to narrow the list
+    Hint {
+        /// The guessed word
+        guess: String,
+        /// Feedback for the guessed word (e.g., "g*y**")
+        hint: String,
+    },
+    /// Add the hints of a pasted emoji share grid for the given guesses
+    ImportShare {
+        /// The guesses in order, optionally followed by the rows of the grid
+        tokens: Vec<String>,
+    },
+    /// Print the history of guesses and feedback
+    History {
+        /// Print the history as an emoji share grid instead
+        #[arg(long)]
+        share: bool,
+    },
+    /// Print the next guess to play, under the rules set with `set rules`, and why
+    Suggest,
+    /// Print a decent next guess right away, without scoring every guess
+    Quick,
+    /// Change a setting of the REPL
+    Set {
+        /// The setting to change
+        name: String,
+        /// The new value
+        value: String,
+        /// Also save every setting to the config file, for the next REPLs to start with
+        #[arg(long)]
+        save: bool,
+    },
+    /// Print the current settings
+    Show {
+        /// What to print, which can only be "settings"
+        what: String,
+    },
+    /// Print whether a word may be guessed under each ruleset, given the history
+    Legal {
+        /// The word to check
+        word: String,
+    },
+    /// Group the remaining answers by the hint they would give for a guess
+    #[command(visible_alias = "partition")]
+    Clusters {
+        /// The guess to split the answers with, the best guess if not given
+        word: Option<String>,
+    },
+    /// Print how much information each letter of a guess gives over the remaining answers
+    Explain {
+        /// The guess to explain
+        word: String,
+    },
+    /// Print the letters still possible at a position and how many answers each would leave
+    Explore {
+        /// The position to explore, starting from 1
+        position: usize,
+    },
+    /// Print how many remaining answers repeat a letter, and which repeats are most common
+    Doubles,
+    /// Print a grid of letters by positions, shaded by how many remaining answers have each
+    Heatmap {
+        /// Write the grid to an HTML page instead
+        #[arg(long, value_name = "FILE")]
+        export: Option<String>,
+    },
+    /// Remove answers that do not match a pattern or letters, as known from outside the game
+    Filter {
+        #[command(subcommand)]
+        command: FilterCommand,
+    },
+    /// Print the remaining possible answers
+    Remaining {
+        /// The most answers to print
+        #[arg(default_value_t = REMAINING_LIMIT)]
+        n: usize,
+    },
+    /// Print how likely each remaining answer is to be the secret word
+    Probs {
+        /// The most answers to print
+        #[arg(default_value_t = REMAINING_LIMIT)]
+        n: usize,
+        /// Write every remaining answer with its rank and probability to a CSV file instead
+        #[arg(long, value_name = "FILE")]
+        export: Option<String>,
+    },
+    /// Manage independent games solved side by side
+    Session {
+        #[command(subcommand)]
+        command: SessionCommand,
+    },
+    /// Follow a decision tree written by `rudle export-tree`
+    Tree {
+        #[command(subcommand)]
+        command: TreeCommand,
+    },
+    /// Save the session's hints, filters and settings to a JSON file
+    Save {
+        /// The file to write
+        file: String,
+    },
+    /// Replace the session's hints, filters and settings with those saved in a file
+    Load {
+        /// The file written by `save`
+        file: String,
+    },
+    /// Undo the last guess and restore the word list
+    Undo,
+    /// Exit the REPL
+    Exit,
+}
+
+/// Session commands
+#[derive(Subcommand)]
+enum SessionCommand {
+    /// Start a new session and switch to it
+    New {
+        /// The name to switch to the session by
+        name: String,
+        /// The word list of the session, the word lists of the first session if not given
+        #[arg(long)]
+        file: Option<String>,
+        /// A separate list of possible answers for the session
+        #[arg(long, requires = "file")]
+        answers_file: Option<String>,
+        /// The number of letters per word in the session's word lists
+        #[arg(long, requires = "file")]
+        word_size: Option<usize>,
+    },
+    /// Switch to another session
+    Switch {
+        /// The name of the session
+        name: String,
+    },
+    /// List the sessions
+    List,
+}
+
+/// Filter commands
+#[derive(Subcommand)]
+enum FilterCommand {
+    /// Keep the answers matching a regular expression, regardless of case
+    Regex {
+        /// The pattern, e.g. "^S..RE$"
+        pattern: String,
+    },
+    /// Keep the answers containing every letter
+    Contains {
+        /// The letters, e.g. "a,e"
+        letters: String,
+    },
+    /// Keep the answers containing none of the letters
+    Excludes {
+        /// The letters, e.g. "q,z"
+        letters: String,
+    },
+}
+
+/// Decision tree commands
+#[derive(Subcommand)]
+enum TreeCommand {
+    /// Load a JSON decision tree
+    Load {
+        /// The tree file
+        file: String,
+    },
+    /// Print the guess the tree plays next
+    Next,
+}
+
+/// The version of the files written by `save`, described by `rudle schema session`
+pub const SESSION_VERSION: u64 = 1;
+
+/// How many remaining answers `remaining` prints by default
+const REMAINING_LIMIT: usize = 50;
+
+const HELP_MESSAGE: &str = "top <n> [off|answers-first|strict]
+                     Print the top n best guesses with their scores, given the
+                     remaining possible answers. Scores are the percentage by 
+                     which a guessed word reduces the list of possible remaining 
+                     answers, or the bits of information it gives with 'set
+                     units bits'. A strictness given after n replaces the
+                     'strictness' setting for this ranking. The Tier column
+                     tells answers and probe words apart. Guesses that split
+                     the answers into exactly the same groups are listed once,
+                     with the number of equivalent guesses.
+
+score <word>         Print the scores of a word, given the remaining possible 
+                     answers. Scores are the percentage by which a guessed word 
+                     reduces the list of possible remaining answers. 
+
+hint <word> <hint>   Add a word and its hint to reduce the possible answers.
+                     For <word> retype the guessed word.
+                     Here is how to type <hint>:
+                     - If a letter is green/guessed correctly, retype the letter
+                     - If a letter is yellow/misplaced, type '*' in its position
+                     - If a letter is grey/incorrect, type '_' in its position
+                     Example: 'hint hello h*ll_'
+                     Colors work too: 'g' for green, 'y' for yellow and 'b' for
+                     grey, as in 'hint hello gyggb'.
+
+import-share <guesses...>
+                     Add the hints of every guess at once from the emoji grid of
+                     a share post. Give the guesses, then paste the grid (the
+                     'Wordle 1,234 4/6' line may be included) and end it with a
+                     blank line. Rows may also be given after the guesses, and
+                     rows that name their guess (e.g. '⬛🟨⬛⬛🟩 crane') need no
+                     guesses at all, as in 'import-share' followed by the rows.
+
+history [--share]    Print the history of guesses and feedback. With '--share',
+                     print it as the emoji grid used to share Wordle results.
+
+set <name> <value> [--save]
+                     Change a setting, listed below. With '--save', every
+                     setting but vocab is also saved to the config file, which
+                     the REPL starts with from then on. --depth and
+                     --share-symbols still override the saved settings.
+
+show settings        Print the value of every setting.
+
+set units <percent|bits>
+                     Print the scores of 'top' and 'score' as the percentage of
+                     the answers removed (the default), or as bits of
+                     information, where each bit halves the answers.
+
+set auto-top <n|off> Print the best n guesses after each hint, as 'top n' would.
+                     This waits for the scores after every hint.
+
+set theme <symbols>  Choose the symbols of 'history --share': default,
+                     colorblind, or three symbols such as '🟦🟧⬛'.
+
+set strictness <off|answers-first|strict>
+                     Choose which guesses 'top' ranks: every guess by score
+                     (off, the default), possible answers before probe words
+                     that score the same (answers-first), or only the possible
+                     answers (strict).
+
+set depth <1|2>      Rank by looking one or two guesses ahead, as with --depth.
+                     The guesses are scored again when a command needs them.
+
+set tolerance <points>
+                     With answers-first, put possible answers ahead of probe
+                     words that score up to this many percentage points more
+                     (default: 0).
+
+set vocab <mine|all> Choose whether 'top' only recommends words from the list
+                     given with --vocab, or from every allowed guess. Answers
+                     are narrowed down over the full answer list either way.
+
+set rules <rules>    Choose the rules 'suggest' follows: easy (the default), hard
+                     or ultra-hard, as checked by 'legal'.
+
+set budget <time>    Limit how long scoring after each hint may take, e.g. '5s'
+                     or '500ms', or 'off' to always score every guess. The most
+                     promising guesses are scored first and 'top' says when the
+                     ranking is approximate.
+
+set spoiler-guard <on|off>
+                     Mask today's daily answer as ????? wherever a command would
+                     print it, as with --spoiler-guard, so the daily can be
+                     analyzed mid-game from the counts alone.
+
+suggest              Print the guess to play next with the --strategy the solver
+                     was started with, following 'set rules', with how many
+                     answers it is expected to leave and why it was picked.
+
+quick                Print a decent next guess within milliseconds. It uses the
+                     scores already calculated if there are any, and otherwise
+                     only scores the guesses that cover the most letters of the
+                     remaining answers, so it may miss the best guess.
+
+legal <word>         Print whether <word> may be guessed next under the easy,
+                     hard and ultra-hard rules, given the hints so far.
+
+clusters [word]      Group the remaining answers by the hint they would give for
+                     [word], or for the current best guess if no word is given,
+                     largest group first, to show how the guess splits them.
+                     Each group shows its share of the answers and the bits of
+                     information it adds to the guess's entropy, which explains
+                     its score. 'partition' is another name for this command.
+
+explain <word>       Print how many bits of information <word> gives over the
+                     remaining answers, and how much each of its letters gives
+                     on its own: how often it would be green, yellow and grey.
+                     Letters whose hint is already certain give nothing and are
+                     marked, such as a letter no answer contains.
+
+explore <position>   Print the letters that are still possible at a position
+                     (starting from 1) and how many of the remaining answers
+                     would be left if that letter turned out to be green there.
+
+doubles              Print how many of the remaining answers contain a repeated
+                     letter and which repeated letters are most common. Hints
+                     for repeated letters are easy to misread, so check this
+                     before ruling a letter out.
+
+heatmap [--export <file>]
+                     Print a grid of every letter by position, each cell shaded
+                     by how many of the remaining answers have the letter there.
+                     The counts are printed as a table when colors are off, and
+                     --export writes the grid to an HTML page instead.
+
+filter regex <pattern>
+                     Keep only the answers matching a regular expression (e.g.
+                     ^S..RE$), ignoring case. Use it for knowledge that is not a
+                     hint, such as the theme of the puzzle. 'undo' removes the
+                     latest filter or guess.
+
+filter contains <letters>
+                     Keep only the answers containing every letter (e.g. a,e).
+
+filter excludes <letters>
+                     Keep only the answers containing none of the letters.
+
+remaining [n]        Print the remaining possible answers in alphabetical order,
+                     or only the first n (default: 50) and how many are left.
+
+probs [n] [--export <file>]
+                     Print how likely each remaining answer is to be the secret
+                     word, the first n (default: 50) from the likeliest. Answers
+                     are weighed by their frequency if one is loaded, or else
+                     equally. --export writes every remaining answer with its
+                     rank and probability to a CSV file instead, which studies
+                     of how the answers narrow down over a game can collect
+                     after each hint.
+
+session new <name> [--file <file>] [--answers-file <file>] [--word-size <n>]
+                     Start another game, such as a second Quordle board, and
+                     switch to it. It uses the word lists loaded at startup
+                     unless a --file is given. Each session keeps its own hints.
+
+session switch <name>
+                     Switch to another session.
+
+session list         List the sessions, marking the current one with '*'.
+
+tree load <file>     Load a JSON decision tree written by 'rudle export-tree'.
+                     While the hints follow the tree, each hint prints the guess
+                     it plays next, and guesses are scored in the background
+                     while it is read, so slow machines stay responsive.
+
+tree next            Print the guess the loaded tree plays next.
+
+save <file>          Save the hints, filters and settings of the session to a
+                     JSON file, to resume the analysis later or share it.
+
+load <file>          Replace the hints, filters and settings of the session with
+                     those saved by 'save'. The guesses are scored again when a
+                     command needs their scores.
+
+undo                 Undo the last guess or filter and restore the word list
+
+help                 Print the help message, listing the available commands.
+
+exit                 Exit the REPL";
+
+/// One game being solved, with its own word lists, hints and scores
+struct Session {
+    name: String,
+    word_size: usize,
+    /// Every allowed guess, sorted
+    word_list: Vec<Word>,
+    remaining_guesses: Vec<Word>,
+    remaining_answers: AnswerSet,
+    guess_history: Vec<(Word, Hint)>,
+    /// Each filter with the number of guesses made before it
+    filters: Vec<(usize, String)>,
+    /// The scores of every guess before any hint, as they were before being ranked at a depth
+    opening_scores: Vec<(Word, f32, f32)>,
+    /// The scores after each hint or filter, or None until a command needs them
+    word_scores: Vec<Option<Vec<(Word, f32, f32)>>>,
+    /// Whether every guess was scored in time after each hint or filter
+    scores_complete: Vec<bool>,
+    /// The decision tree loaded with `tree load`
+    tree: Option<DecisionTree>,
+    /// The scores being calculated in the background since the latest hint
+    rescore: Option<Rescore>,
+    /// Counts every change to the remaining answers, so that a background rescore can tell
+    /// whether the answers it scored against are still the current ones
+    state: u64,
+}
+
+impl Session {
+    fn new(
+        name: &str,
+        word_size: usize,
+        words: WordLists,
+        opening_scores: Vec<(Word, f32, f32)>,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            word_size,
+            word_list: words.guesses.clone(),
+            remaining_guesses: words.guesses,
+            remaining_answers: AnswerSet::new(words.answers),
+            guess_history: vec![],
+            filters: vec![],
+            word_scores: vec![Some(opening_scores.clone())],
+            opening_scores,
+            scores_complete: vec![true],
+            tree: None,
+            rescore: None,
+            state: 0,
+        }
+    }
+
+    /// Note that the remaining answers changed, cancelling the background rescore of the
+    /// answers before
+    fn answers_changed(&mut self) {
+        self.state += 1;
+        if let Some(rescore) = self.rescore.take() {
+            rescore.cancel();
+        }
+    }
+
+    /// The guesses worth scoring against the remaining answers, or every guess if exhaustive
+    fn candidates(&self, exhaustive: bool) -> Vec<Word> {
+        match exhaustive {
+            true => self.remaining_guesses.clone(),
+            false => prefilter(
+                &self.remaining_guesses,
+                &self.remaining_answers.to_vec(),
+                PREFILTER_SIZE,
+            ),
+        }
+    }
+
+    /// The number of hints and filters applied, which indexes the scores after the latest
+    fn generation(&self) -> usize {
+        self.guess_history.len() + self.filters.len()
+    }
+
+    /// The hints and filters in the order they were applied
+    fn steps(&self) -> Vec<Step<'_>> {
+        let mut guesses = self.guess_history.iter();
+        let mut filters = self.filters.iter().peekable();
+        (0..self.generation())
+            .map(|_| {
+                let n_guessed = self.guess_history.len() - guesses.len();
+                match filters.next_if(|(n, _)| *n == n_guessed) {
+                    Some((_, description)) => Step::Filter(description),
+                    None => {
+                        let (guess, hint) = guesses.next().expect("More steps than generations");
+                        Step::Guess(guess, hint)
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Narrow the answers down by a guess and its hint, without scoring the guesses again
+    /// # Returns
+    /// The number of answers that were removed
+    fn apply_hint(&mut self, guess: &Word, hint: &Hint) -> usize {
+        self.answers_changed();
+        self.remaining_guesses.retain(|w| w != guess);
+        let n_removed = self.remaining_answers.retain(|w| {
+            let h = Hint::from_guess_and_answer(guess, w).expect("Invalid hint");
+            h == *hint
+        });
+        self.guess_history.push((guess.clone(), hint.clone()));
+        n_removed
+    }
+
+    /// Narrow the answers down by a filter, without scoring the guesses again
+    /// # Returns
+    /// The number of answers that were removed
+    fn apply_filter(&mut self, description: String, keep: &WordFilter) -> usize {
+        self.answers_changed();
+        let word_size = self.word_size;
+        let n_removed = self
+            .remaining_answers
+            .retain(|w| w.len() == word_size && keep(w));
+        self.filters.push((self.guess_history.len(), description));
+        n_removed
+    }
+
+    /// Apply the hints and filters of another session in order, such as the session this one
+    /// replaces after the word lists were reloaded
+    fn replay(&mut self, steps: &[Step]) {
+        for step in steps {
+            match *step {
+                Step::Guess(guess, hint) => {
+                    self.apply_hint(guess, hint);
+                }
+                Step::Filter(description) => {
+                    let (kind, argument) = description.split_once(' ').unwrap_or((description, ""));
+                    let (description, keep) = make_filter(kind, argument)
+                        .expect("Filters are described as they are made");
+                    self.apply_filter(description, &keep);
+                }
+            }
+            self.word_scores.push(None);
+            self.scores_complete.push(true);
+        }
+    }
+
+    /// Undo every hint and filter, keeping the opening scores
+    fn reset(&mut self) {
+        self.answers_changed();
+        while self.remaining_answers.undo() {}
+        self.remaining_guesses = self.word_list.clone();
+        self.guess_history.clear();
+        self.filters.clear();
+        self.word_scores.truncate(1);
+        self.scores_complete.truncate(1);
+    }
+}
+
+/// How the guesses of the REPL are scored, which the background rescores share
+#[derive(Clone)]
+struct Scorer {
+    weights: Option<Arc<HashMap<Word, f64>>>,
+    matrix: Option<Arc<HintMatrix>>,
+    show_progress: bool,
+}
+
+/// The scores of the guesses, and whether every guess was scored within the budget
+type Scores = (Vec<(Word, f32, f32)>, bool);
+
+impl Scorer {
+    fn score(&self, guesses: &[Word], answers: &[Word], budget: Option<Duration>) -> Scores {
+        let show_progress = self.show_progress;
+        match (&self.weights, &self.matrix) {
+            (Some(weights), _) => {
+                get_weighted_scores_within(guesses, answers, weights, show_progress, budget)
+            }
+            (None, Some(matrix)) => {
+                matrix.get_scores_within(guesses, answers, show_progress, budget)
+            }
+            (None, None) => get_scores_within(guesses, answers, show_progress, budget),
+        }
+    }
+
+    /// Score the guesses on another thread, without a progress bar to interrupt the output
+    /// Without a budget the guesses are scored in batches, stopping between them once
+    /// `cancelled` is set; with one, the budget bounds the scoring instead.
+    fn spawn(
+        &self,
+        guesses: Vec<Word>,
+        answers: Vec<Word>,
+        budget: Option<Duration>,
+        cancelled: Arc<AtomicBool>,
+    ) -> JoinHandle<Scores> {
+        let scorer = Scorer {
+            show_progress: false,
+            ..self.clone()
+        };
+        thread::spawn(move || {
+            if budget.is_some() {
+                return scorer.score(&guesses, &answers, budget);
+            }
+            let mut scores = Vec::with_capacity(guesses.len());
+            for batch in guesses.chunks(RESCORE_BATCH) {
+                if cancelled.load(Ordering::Relaxed) {
+                    return (scores, false);
+                }
+                scores.extend(scorer.score(batch, &answers, None).0);
+            }
+            // Sorted like the scores of a single call
+            scores.sort_by(|a, b| {
+                b.1.partial_cmp(&a.1)
+                    .unwrap()
+                    .then_with(|| Ord::cmp(&a.0, &b.0))
+            });
+            (scores, true)
+        })
+    }
+}
+
+/// The number of guesses a background rescore scores between checks for cancellation
+const RESCORE_BATCH: usize = 1000;
+
+/// Scores being calculated in the background after a hint
+struct Rescore {
+    /// The state of the session's answers the scores are for
+    state: u64,
+    n_candidates: usize,
+    handle: JoinHandle<Scores>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Rescore {
+    /// Stop the scoring at its next batch, leaving the thread to end on its own
+    fn cancel(self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Wait for the scores, with a spinner while they are still being calculated
+    fn finish(self, show_progress: bool) -> Scores {
+        if show_progress && !self.handle.is_finished() {
+            let spinner = ProgressBar::new_spinner();
+            spinner.set_message("Finishing the scores calculated since the last hint...");
+            while !self.handle.is_finished() {
+                spinner.tick();
+                thread::sleep(Duration::from_millis(50));
+            }
+            spinner.finish_and_clear();
+        }
+        self.handle.join().expect("The background scoring panicked")
+    }
+}
+
+/// A hint or filter that narrowed the answers of a session
+enum Step<'a> {
+    Guess(&'a Word, &'a Hint),
+    /// The description of a filter, e.g. "contains AE"
+    Filter(&'a str),
+}
+
+pub fn solve(words: WordLists, common: &CommonArgs, options: &SolveArgs) {
+    // How common each answer is, which weighs the answers when scoring
+    let weights = load_answer_weights(common, options);
+
+    // The opening scores only depend on the word lists, so they are kept between sessions,
+    // unless the answers are weighted
+    let show_progress = !common.deterministic;
+    let use_cache = !options.no_cache && weights.is_none();
+    let cache = ScoreCache::new(&words.guesses, &words.answers);
+    let opening_scores = if use_cache { cache.load() } else { None };
+
+    // Computing every hint once up front makes scoring after each hint much cheaper, as long
+    // as the lists are small enough for the matrix to fit in memory. With the opening scores
+    // cached, later rounds only score the few remaining answers, so building it would not pay.
+    let matrix = match (opening_scores.as_ref(), weights.as_ref()) {
+        (None, None) => HintMatrix::new(&words.guesses, &words.answers, show_progress),
+        _ => None,
+    };
+    // The matrix is built again when the word lists are reloaded
+    let scorer = RefCell::new(Scorer {
+        weights: weights.clone().map(Arc::new),
+        matrix: matrix.map(Arc::new),
+        show_progress,
+    });
+    let score = |guesses: &[Word], answers: &[Word], budget| {
+        scorer.borrow().score(guesses, answers, budget)
+    };
+
+    // The settings saved with `set --save`, unless the command line says otherwise
+    let mut settings = Settings::load();
+    if let Some(depth) = options.depth {
+        settings.depth = depth;
+    }
+    if let Some(symbols) = &options.share_symbols {
+        settings.theme = symbols.clone();
+    }
+    settings.spoiler_guard = (settings.spoiler_guard || options.spoiler_guard) && !options.reveal;
+
+    // The answer of today's daily puzzle, which `spoiler-guard` keeps out of the output
+    let guard_spoilers = |settings: &Settings, answers: &[Word]| {
+        let daily_answer = Daily::new(current_day()).pick(answers);
+        set_hidden_word(daily_answer.filter(|_| settings.spoiler_guard))
+    };
+    guard_spoilers(&settings, &words.answers);
+
+    // Guesses the player knows, which `top` can be limited to with `set vocab mine`
+    let vocab =
+        options
+            .vocab
+            .as_ref()
+            .and_then(|path| match word::load_words(path, common.word_size) {
+                Ok(vocab) => Some(vocab),
+                Err(e) => {
+                    println!("Warning: failed to load the vocabulary {}: {}", path, e);
+                    None
+                }
+            });
+    settings.vocab = vocab.is_some();
+
+    // The strategy `suggest` plays
+    let strategy = options.strategy.build(make_rng(common).gen());
+
+    // With depth 2 the best guesses are re-ranked by what they leave for the next guess
+    let depth = Cell::new(settings.depth);
+    let lookahead = |scores, guesses: &[Word], answers: &[Word]| match depth.get() {
+        2 => lookahead_scores(scores, guesses, answers),
+        _ => scores,
+    };
+    // Start a session from the cached opening scores, or calculate and cache them
+    let open_session = |name: &str, word_size, words: WordLists, cached: Option<Vec<_>>| {
+        let opening_scores = match cached {
+            Some(scores) => scores,
+            None => {
+                let (scores, _) = score(&words.guesses, &words.answers, None);
+                if use_cache {
+                    let cache = ScoreCache::new(&words.guesses, &words.answers);
+                    if let Err(e) = cache.save(&scores) {
+                        println!("Warning: failed to save the score cache: {}", e);
+                    }
+                }
+                scores
+            }
+        };
+        let ranked = lookahead(opening_scores.clone(), &words.guesses, &words.answers);
+        let mut session = Session::new(name, word_size, words, opening_scores);
+        session.word_scores[0] = Some(ranked);
+        session
+    };
+
+    // The lists loaded at startup, which are loaded again when their files change
+    let mut watcher = ListWatcher::new(common);
+    let mut loaded = words.clone();
+    let mut sessions = vec![open_session(
+        "main",
+        common.word_size,
+        words,
+        opening_scores,
+    )];
+    let mut current = 0;
+
+    if let Some(answer) = &options.auto {
+        let session = &sessions[0];
+        let answers = session.remaining_answers.to_vec();
+        auto_solve(
+            answer,
+            &session.remaining_guesses,
+            &answers,
+            session.word_scores[0].as_ref().expect("Opening scores"),
+            common,
+            options,
+        );
+        return;
+    }
+
+    // Commands come from the --script, piped input or a person at the terminal, and only
+    // the person sees the banner and the prompt
+    let mut reader = match LineReader::for_commands(options.script.as_deref()) {
+        Ok(reader) => reader,
+        Err(e) => {
+            println!("Error: failed to read the script: {}", e);
+            return;
+        }
+    };
+    // With --json, only the output of commands is printed, so every line can be parsed
+    let prompt = if options.json { "" } else { "> " };
+    if reader.is_interactive() && !options.json {
+        println!("Starting Wordle Solver REPL. Type 'help' for commands.");
+    }
+
+    // A command to run before reading the next one, such as the `top` of `set auto-top`
+    let mut queued: Option<String> = None;
+
+    // Stop once the script or the piped input runs out
+    loop {
+        let (input, was_queued) = match queued.take() {
+            Some(command) => (command, true),
+            None => match reader.read_line(prompt) {
+                Some(input) => (input, false),
+                None => break,
+            },
+        };
+        let input = input.trim();
+        // Blank lines and comments let scripts be laid out for reading
+        if input.is_empty() || input.starts_with('#') {
+            continue;
+        }
+
+        // Sessions on lists that were edited since the last command start over on the new
+        // lists, with their hints and filters applied again
+        match watcher.poll(&loaded) {
+            Reload::Unchanged => {}
+            Reload::Reloaded(new, summary) => {
+                println!("{}", summary);
+                let cached = match use_cache {
+                    true => ScoreCache::new(&new.guesses, &new.answers).load(),
+                    false => None,
+                };
+                scorer.borrow_mut().matrix = match (cached.as_ref(), weights.as_ref()) {
+                    (None, None) => {
+                        HintMatrix::new(&new.guesses, &new.answers, show_progress).map(Arc::new)
+                    }
+                    _ => None,
+                };
+                let mut cached = cached;
+                for session in sessions.iter_mut() {
+                    if session.word_list != loaded.guesses
+                        || session.remaining_answers.all() != loaded.answers
+                    {
+                        continue;
+                    }
+                    let name = session.name.clone();
+                    let mut fresh =
+                        open_session(&name, session.word_size, new.clone(), cached.take());
+                    fresh.replay(&session.steps());
+                    if session.tree.is_some() {
+                        println!(
+                            "Dropped the tree of session {}, which was built for the old lists.",
+                            name
+                        );
+                    }
+                    if let Some(rescore) = std::mem::replace(session, fresh).rescore {
+                        rescore.cancel();
+                    }
+                    // Sessions after the first read the scores the first one cached
+                    if use_cache {
+                        cached = ScoreCache::new(&new.guesses, &new.answers).load();
+                    }
+                }
+                guard_spoilers(&settings, &new.answers);
+                forget_scores(&loaded);
+                loaded = new;
+            }
+            Reload::Failed(e) => println!("Warning: kept the old word lists: {}", e),
+        }
+        if !was_queued {
+            reader.add_history(input);
+        }
+        let mut args: Vec<&str> = vec!["repl"];
+        args.extend(input.split_whitespace());
+
+        if args.len() == 2 && args[1] == "help" {
+            println!("{}", HELP_MESSAGE);
+            continue;
+        }
+
+        // Parse the input into commands
+        let command_name = args.get(1).copied().unwrap_or_default().to_string();
+        let args = match SolverArgs::try_parse_from(args) {
+            Ok(parsed) => parsed,
+            Err(_) => {
+                let message = "Bad command. Type 'help' for commands.";
+                print_error(options.json, &command_name, message);
+                continue;
+            }
+        };
+
+        let session = &mut sessions[current];
+
+        // Score the guesses after the latest hint or filter the first time a command needs
+        // them, keeping the scores until the hint or filter is undone
+        let generation = session.generation();
+        let needs_scores = matches!(
+            args.command,
+            SolverCommand::Top { .. }
+                | SolverCommand::Score { .. }
+                | SolverCommand::Clusters { word: None }
+        ) || (matches!(args.command, SolverCommand::Suggest)
+            && options.strategy == StrategyKind::Entropy);
+        if needs_scores && session.word_scores[generation].is_none() {
+            let answers = session.remaining_answers.to_vec();
+            let (n_candidates, (scores, complete)) = match session.rescore.take() {
+                Some(rescore) if rescore.state == session.state => {
+                    (rescore.n_candidates, rescore.finish(show_progress))
+                }
+                other => {
+                    if let Some(rescore) = other {
+                        rescore.cancel();
+                    }
+                    let candidates = session.candidates(options.exhaustive);
+                    (
+                        candidates.len(),
+                        score(&candidates, &answers, settings.budget),
+                    )
+                }
+            };
+            session.word_scores[generation] =
+                Some(lookahead(scores, &session.remaining_guesses, &answers));
+            session.scores_complete[generation] =
+                complete && n_candidates == session.remaining_guesses.len();
+        }
+        let current_scores = session.word_scores[generation].as_deref().unwrap_or(&[]);
+
+        // Process the parsed command
+        match args.command {
+            SolverCommand::Session { command } => match command {
+                SessionCommand::New {
+                    name,
+                    file,
+                    answers_file,
+                    word_size,
+                } => {
+                    if sessions.iter().any(|s| s.name == name) {
+                        println!("A session named {} already exists.", name);
+                        continue;
+                    }
+                    // Without a file, the session reuses the word lists loaded at startup
+                    let (word_size, words) = match file {
+                        None => {
+                            let main = &sessions[0];
+                            let answers = main.remaining_answers.all().to_vec();
+                            let words = WordLists::new(main.word_list.clone(), answers);
+                            (main.word_size, words)
+                        }
+                        Some(file) => {
+                            let word_size = word_size.unwrap_or(common.word_size);
+                            let load = |path: &str| word::load_words(path, word_size);
+                            let words = match (load(&file), answers_file.as_deref().map(load)) {
+                                (Ok(guesses), None) => WordLists::single(guesses),
+                                (Ok(guesses), Some(Ok(answers))) => {
+                                    WordLists::new(guesses, answers)
+                                }
+                                (Err(e), _) | (_, Some(Err(e))) => {
+                                    println!("Error: failed to load the word lists: {}", e);
+                                    continue;
+                                }
+                            };
+                            (word_size, words)
+                        }
+                    };
+                    let cached = match use_cache {
+                        true => ScoreCache::new(&words.guesses, &words.answers).load(),
+                        false => None,
+                    };
+                    let session = open_session(&name, word_size, words, cached);
+                    println!(
+                        "Started session {} with {} possible answers.",
+                        name,
+                        session.remaining_answers.len()
+                    );
+                    sessions.push(session);
+                    current = sessions.len() - 1;
+                }
+                SessionCommand::Switch { name } => {
+                    match sessions.iter().position(|s| s.name == name) {
+                        Some(i) => {
+                            current = i;
+                            println!(
+                                "Switched to session {} ({} possible answers remaining).",
+                                name,
+                                sessions[i].remaining_answers.len()
+                            );
+                        }
+                        None => println!("No session named {}.", name),
+                    }
+                }
+                SessionCommand::List => {
+                    for (i, s) in sessions.iter().enumerate() {
+                        let marker = if i == current { "*" } else { " " };
+                        println!(
+                            "{} {:<12} {} letters, {} guesses, {} possible answers",
+                            marker,
+                            s.name,
+                            s.word_size,
+                            s.guess_history.len(),
+                            s.remaining_answers.len()
+                        );
+                    }
+                }
+            },
+            SolverCommand::Top { n, strictness } => {
+                let strictness = strictness.unwrap_or(settings.strictness);
+                let filtered_scores;
+                let vocab = vocab.as_ref().filter(|_| settings.vocab);
+                let scores = match (strictness, vocab) {
+                    (Strictness::Off, None) => current_scores,
+                    (strictness, vocab) => {
+                        let strict = strictness == Strictness::Strict;
+                        let is_answer = |w: &Word| session.remaining_answers.contains(w);
+                        let mut scores = current_scores
+                            .iter()
+                            .filter(|(w, _, _)| !strict || is_answer(w))
+                            .filter(|(w, _, _)| vocab.is_none_or(|v| v.binary_search(w).is_ok()))
+                            .cloned()
+                            .collect::<Vec<(Word, f32, f32)>>();
+                        // Scores are sorted already, so a stable sort only reorders ties, or
+                        // the answers within the tolerance of a probe word
+                        if strictness == Strictness::AnswersFirst {
+                            let ranked = |(w, score, _): &(Word, f32, f32)| match is_answer(w) {
+                                true => score + settings.tolerance,
+                                false => *score,
+                            };
+                            scores.sort_by(|a, b| {
+                                ranked(b)
+                                    .total_cmp(&ranked(a))
+                                    .then(is_answer(&b.0).cmp(&is_answer(&a.0)))
+                            });
+                        }
+                        filtered_scores = scores;
+                        &filtered_scores
+                    }
+                };
+
+                let complete = session.scores_complete[session.generation()];
+                let collapsed = collapse_equivalent(scores, &session.remaining_answers.to_vec(), n);
+                let guesses_left = options
+                    .max_attempts
+                    .saturating_sub(session.guess_history.len());
+                let answers = session.remaining_answers.to_vec();
+                let risk = |word: &Word| classify_risk(word, &answers, guesses_left);
+                if options.json {
+                    let guesses: Vec<Value> = collapsed
+                        .iter()
+                        .enumerate()
+                        .map(|(i, ((word, avg_score, min_score), n_equivalent))| {
+                            json!({
+                                "rank": i + 1,
+                                "word": shown(word),
+                                "expected": json_percent(*avg_score),
+                                "worst_case": json_percent(*min_score),
+                                "guesses": json_guesses(expected_guesses(word, &answers)),
+                                "equivalent": n_equivalent,
+                                "possible_answer": session.remaining_answers.contains(word),
+                                "risk": risk(word),
+                            })
+                        })
+                        .collect();
+                    let output = json!({
+                        "command": "top",
+                        "approximate": !complete,
+                        "guesses": guesses,
+                    });
+                    println!("{}", output);
+                    continue;
+                }
+
+                if !complete {
+                    println!(
+                        "Approximate ranking: only {} of {} guesses were scored.",
+                        current_scores.len(),
+                        session.remaining_guesses.len()
+                    );
+                }
+                if depth.get() == 2 {
+                    println!(
+                        "The best {} guesses are ranked by looking two guesses ahead.",
+                        LOOKAHEAD_CANDIDATES
+                    );
+                }
+                let rows: Vec<Vec<String>> = collapsed
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, ((word, avg_score, min_score), n_equivalent))| {
+                        let equivalent = if n_equivalent > 0 {
+                            format!("+{}", n_equivalent)
+                        } else {
+                            String::new()
+                        };
+                        vec![
+                            (i + 1).to_string(),
+                            shown(word),
+                            settings.units.format(*avg_score),
+                            settings.units.format(*min_score),
+                            format!("{:.2}", expected_guesses(word, &answers)),
+                            match session.remaining_answers.contains(word) {
+                                true => "answer".to_string(),
+                                false => "probe".to_string(),
+                            },
+                            equivalent,
+                            risk(word).to_string(),
+                        ]
+                    })
+                    .collect();
+                print_table(
+                    &[
+                        "Rank",
+                        "Word",
+                        "Expected",
+                        "Worst-Case",
+                        "Guesses",
+                        "Tier",
+                        "Equivalent",
+                        "Risk",
+                    ],
+                    &rows,
+                );
+            }
+            SolverCommand::Score { word } => {
+                let word = match Word::from_string(&word) {
+                    Ok(w) => w,
+                    Err(e) => {
+                        print_error(options.json, "score", &format!("Error: {}", e));
+                        continue;
+                    }
+                };
+                let scores = current_scores;
+
+                if let Some((i, (_, avg_score, min_score))) =
+                    scores.iter().enumerate().find(|(_, (w, _, _))| w == &word)
+                {
+                    let guesses = expected_guesses(&word, &session.remaining_answers.to_vec());
+                    if options.json {
+                        let output = json!({
+                            "command": "score",
+                            "word": word.to_string(),
+                            "rank": i + 1,
+                            "expected": json_percent(*avg_score),
+                            "worst_case": json_percent(*min_score),
+                            "guesses": json_guesses(guesses),
+                        });
+                        println!("{}", output);
+                    } else {
+                        println!("Rank: {}", i + 1);
+                        println!("Expected: {}", settings.units.format(*avg_score));
+                        println!("Worst-Case: {}", settings.units.format(*min_score));
+                        println!("Expected guesses to solve: {:.2}", guesses);
+                    }
+                } else if !session.scores_complete[session.generation()]
+                    && session.word_list.binary_search(&word).is_ok()
+                {
+                    let message = "Word was not among the guesses scored after the last hint.";
+                    print_error(options.json, "score", message);
+                } else {
+                    print_error(options.json, "score", "Word not found in word list.");
+                }
+            }
+            SolverCommand::Hint { guess, hint } => {
+                let guess = match Word::from_string(&guess) {
+                    Ok(w) => w,
+                    Err(e) => {
+                        print_error(options.json, "hint", &format!("Error: {}", e));
+                        continue;
+                    }
+                };
+                let hint = match Hint::from_string(&hint, &guess) {
+                    Ok(h) => h,
+                    Err(e) => {
+                        print_error(options.json, "hint", &format!("Error: {}", e));
+                        continue;
+                    }
+                };
+                if guess.len() != session.word_size || hint.len() != session.word_size {
+                    let message = format!(
+                        "Guess and hint must both have a size of {}",
+                        session.word_size
+                    );
+                    print_error(options.json, "hint", &message);
+                    continue;
+                }
+                if !guess.iter().all(|c| c.is_alphabetic()) {
+                    let message = "Guess must only contain alphabetic characters.";
+                    print_error(options.json, "hint", message);
+                    continue;
+                }
+                let n_removed = session.apply_hint(&guess, &hint);
+                let tree_guess = session.tree.as_ref().map(|tree| {
+                    tree.follow(&session.guess_history)
+                        .map(|node| node.guess.clone())
+                });
+                if options.json {
+                    let mut output = json!({
+                        "command": "hint",
+                        "guess": guess.to_string(),
+                        "hint": hint.to_pattern(&guess),
+                        "removed": n_removed,
+                        "remaining": session.remaining_answers.len(),
+                    });
+                    if let Some(tree_guess) = &tree_guess {
+                        output["tree_next"] = json!(tree_guess.as_ref().map(shown));
+                    }
+                    println!("{}", output);
+                } else {
+                    print_hint(&hint, &guess);
+                    println!();
+                    println!("Removed {} words.", n_removed);
+                    println!(
+                        "{} possible answers remaining.",
+                        session.remaining_answers.len()
+                    );
+                    match &tree_guess {
+                        Some(Some(tree_guess)) => {
+                            println!("The tree plays {} next.", shown(tree_guess))
+                        }
+                        Some(None) => println!("The game has left the tree."),
+                        None => {}
+                    }
+                }
+
+                // The guesses are scored in the background while the hint is read, and a
+                // command that needs the scores waits for them, so entering the hints of a
+                // whole game does not wait on scoring after each of them
+                session.word_scores.push(None);
+                session.scores_complete.push(true);
+                let candidates = session.candidates(options.exhaustive);
+                let cancelled = Arc::new(AtomicBool::new(false));
+                session.rescore = Some(Rescore {
+                    state: session.state,
+                    n_candidates: candidates.len(),
+                    handle: scorer.borrow().spawn(
+                        candidates,
+                        session.remaining_answers.to_vec(),
+                        settings.budget,
+                        cancelled.clone(),
+                    ),
+                    cancelled,
+                });
+                if settings.auto_top > 0 {
+                    queued = Some(format!("top {}", settings.auto_top));
+                }
+            }
+            SolverCommand::ImportShare { tokens } => {
+                let rows = match read_share_rows(&tokens, session.word_size, &mut reader) {
+                    Ok(rows) => rows,
+                    Err(e) => {
+                        print_error(options.json, "import-share", &format!("Error: {}", e));
+                        continue;
+                    }
+                };
+                let mut applied: Vec<Value> = vec![];
+                for (guess, hint) in rows.iter() {
+                    let n_removed = session.apply_hint(guess, hint);
+                    session.word_scores.push(None);
+                    session.scores_complete.push(true);
+                    if options.json {
+                        applied.push(json!({
+                            "guess": guess.to_string(),
+                            "hint": hint.to_pattern(guess),
+                            "removed": n_removed,
+                            "remaining": session.remaining_answers.len(),
+                        }));
+                    } else {
+                        print_hint(hint, guess);
+                        println!(" - Removed {} words.", n_removed);
+                    }
+                }
+                if options.json {
+                    println!(
+                        "{}",
+                        json!({ "command": "import-share", "guesses": applied })
+                    );
+                } else {
+                    println!(
+                        "Added {} hints. {} possible answers remaining.",
+                        rows.len(),
+                        session.remaining_answers.len()
+                    );
+                }
+                if settings.auto_top > 0 && !rows.is_empty() {
+                    queued = Some(format!("top {}", settings.auto_top));
+                }
+            }
+            SolverCommand::History { share: true } => {
+                let grid = share_grid(
+                    "Rudle",
+                    &session.guess_history,
+                    &[],
+                    options.max_attempts,
+                    &settings.theme,
+                );
+                if options.json {
+                    println!("{}", json!({ "command": "history", "share": grid }));
+                } else {
+                    println!("{}", grid);
+                }
+            }
+            SolverCommand::History { share: false } => {
+                let removed_answers = session.remaining_answers.removed_per_generation();
+                let mut n_words =
+                    session.remaining_answers.len() + removed_answers.iter().sum::<usize>();
+
+                let steps = zip(session.steps(), removed_answers.iter().copied());
+
+                if options.json {
+                    let mut json_guesses: Vec<Value> = vec![];
+                    let mut json_filters: Vec<Value> = vec![];
+                    for (step, n_removed) in steps {
+                        n_words -= n_removed;
+                        match step {
+                            Step::Guess(guess, hint) => json_guesses.push(json!({
+                                "guess": guess.to_string(),
+                                "hint": hint.to_pattern(guess),
+                                "removed": n_removed,
+                                "remaining": n_words,
+                            })),
+                            Step::Filter(description) => json_filters.push(json!({
+                                "filter": description,
+                                "after_guesses": json_guesses.len(),
+                                "removed": n_removed,
+                                "remaining": n_words,
+                            })),
+                        }
+                    }
+                    let n_start = session.remaining_answers.all().len();
+                    let mut output = json!({
+                        "command": "history",
+                        "start": n_start,
+                        "guesses": json_guesses,
+                    });
+                    if !json_filters.is_empty() {
+                        output["filters"] = json!(json_filters);
+                    }
+                    println!("{}", output);
+                    continue;
+                }
+                println!("Starting with {} words", n_words);
+
+                let mut n_guessed = 0;
+                for (step, n_removed) in steps {
+                    let percent_removed = n_removed as f32 * 100.0 / n_words as f32;
+                    match step {
+                        Step::Guess(guess, hint) => {
+                            n_guessed += 1;
+                            print!("{}: ", n_guessed);
+                            print_hint(hint, guess);
+                        }
+                        Step::Filter(description) => print!("filter {}", description),
+                    }
+                    println!(
+                        " - Removed {} of {} ({:.2}%). {} Remaining.",
+                        n_removed,
+                        n_words,
+                        percent_removed,
+                        n_words - n_removed
+                    );
+                    n_words -= n_removed;
+                }
+            }
+            SolverCommand::Quick => {
+                let start = Instant::now();
+                let answers = session.remaining_answers.to_vec();
+                let scores = session.word_scores[generation].as_deref();
+                let source = if scores.is_some() {
+                    "scores"
+                } else {
+                    "estimate"
+                };
+                let guess = suggest_quick(&session.remaining_guesses, &answers, scores);
+                if options.json {
+                    let output = json!({
+                        "command": "quick",
+                        "guess": guess.as_ref().map(shown),
+                        "source": source,
+                    });
+                    println!("{}", output);
+                    continue;
+                }
+                match guess {
+                    Some(guess) if source == "scores" => {
+                        println!(
+                            "Quick suggestion: {} (from the calculated scores)",
+                            shown(&guess)
+                        )
+                    }
+                    Some(guess) => println!("Quick suggestion: {} (estimated)", shown(&guess)),
+                    None => println!("No guesses left to suggest."),
+                }
+                if !common.deterministic {
+                    println!("Found in {:.1?}", start.elapsed());
+                }
+            }
+            SolverCommand::Suggest => {
+                let answers = session.remaining_answers.to_vec();
+                let history = &session.guess_history;
+                let legal: Vec<Word> = session
+                    .remaining_guesses
+                    .iter()
+                    .filter(|w| settings.rules.check(w, history).is_ok())
+                    .cloned()
+                    .collect();
+                let suggestion = match options.strategy {
+                    StrategyKind::Entropy => {
+                        let scores: Vec<(Word, f32, f32)> = current_scores
+                            .iter()
+                            .filter(|(w, _, _)| settings.rules.check(w, history).is_ok())
+                            .cloned()
+                            .collect();
+                        best_guess(&scores, &answers).cloned()
+                    }
+                    _ => strategy.choose(&legal, &answers, history),
+                };
+                match suggestion {
+                    Some(guess) => {
+                        print_suggestion(&guess, &answers, options.strategy, settings.rules)
+                    }
+                    None => println!("No guesses left to suggest."),
+                }
+            }
+            SolverCommand::Set { name, value, save } => {
+                match settings.set(&name, &value, vocab.is_some()) {
+                    Ok(message) => println!("{}", message),
+                    Err(e) => {
+                        println!("Error: {}", e);
+                        continue;
+                    }
+                }
+                guard_spoilers(&settings, &loaded.answers);
+                // The scores were ranked at the old depth, so they are ranked again: the opening
+                // scores right away from every guess's score, and the rest when needed
+                if depth.get() != settings.depth {
+                    depth.set(settings.depth);
+                    for session in sessions.iter_mut() {
+                        session.word_scores.fill(None);
+                        session.scores_complete.fill(true);
+                        session.word_scores[0] = Some(lookahead(
+                            session.opening_scores.clone(),
+                            &session.word_list,
+                            session.remaining_answers.all(),
+                        ));
+                    }
+                }
+                if save {
+                    match settings.save() {
+                        Ok(()) => println!("Saved the settings."),
+                        Err(e) => println!("Error: failed to save the settings: {}", e),
+                    }
+                }
+            }
+            SolverCommand::Show { what } if what == "settings" => {
+                let rows: Vec<Vec<String>> = SETTING_NAMES
+                    .iter()
+                    .map(|&name| vec![name.to_string(), settings.value(name)])
+                    .collect();
+                print_table(&["Setting", "Value"], &rows);
+            }
+            SolverCommand::Show { .. } => {
+                println!("Bad command. Type 'help' for commands.");
+            }
+            SolverCommand::Legal { word } => {
+                let word = match Word::from_string(&word) {
+                    Ok(w) => w,
+                    Err(e) => {
+                        println!("Error: {}", e);
+                        continue;
+                    }
+                };
+                if session.word_list.binary_search(&word).is_err() {
+                    println!("{} is not in the word list.", word);
+                    continue;
+                }
+                for ruleset in Ruleset::ALL {
+                    match ruleset.check(&word, &session.guess_history) {
+                        Ok(()) => println!("{:<10}  allowed", ruleset.name()),
+                        Err(e) => println!("{:<10}  not allowed: {}", ruleset.name(), e),
+                    }
+                }
+            }
+            SolverCommand::Clusters { word } => {
+                let answers = session.remaining_answers.to_vec();
+                let guess = match word.as_deref().map(Word::from_string) {
+                    Some(Ok(w)) => w,
+                    Some(Err(e)) => {
+                        println!("Error: {}", e);
+                        continue;
+                    }
+                    None => match best_guess(current_scores, &answers) {
+                        Some(w) => w.clone(),
+                        None => {
+                            println!("No guesses left to split the answers with.");
+                            continue;
+                        }
+                    },
+                };
+                if guess.len() != session.word_size {
+                    println!("Guess must have a size of {}", session.word_size);
+                    continue;
+                }
+                print_clusters(&guess, &answers);
+            }
+            SolverCommand::Explain { word } => {
+                let guess = match Word::from_string(&word) {
+                    Ok(w) => w,
+                    Err(e) => {
+                        println!("Error: {}", e);
+                        continue;
+                    }
+                };
+                if guess.len() != session.word_size {
+                    println!("Guess must have a size of {}", session.word_size);
+                    continue;
+                }
+                print_explanation(&guess, &session.remaining_answers.to_vec());
+            }
+            SolverCommand::Explore { position } => {
+                if !(1..=session.word_size).contains(&position) {
+                    println!("Position must be between 1 and {}", session.word_size);
+                    continue;
+                }
+                print_position_letters(&session.remaining_answers.to_vec(), position - 1);
+            }
+            SolverCommand::Doubles => print_doubles(&session.remaining_answers.to_vec()),
+            SolverCommand::Heatmap { export: None } => {
+                print_heatmap(&session.remaining_answers.to_vec(), session.word_size)
+            }
+            SolverCommand::Heatmap { export: Some(file) } => {
+                let answers = session.remaining_answers.to_vec();
+                match write_heatmap_html(&file, &answers, session.word_size) {
+                    Ok(()) => println!(
+                        "Wrote the heatmap of {} answers to {}.",
+                        answers.len(),
+                        file
+                    ),
+                    Err(e) => println!("Error: failed to write {}: {}", file, e),
+                }
+            }
+            SolverCommand::Filter { command } => {
+                let (kind, argument) = match &command {
+                    FilterCommand::Regex { pattern } => ("regex", pattern),
+                    FilterCommand::Contains { letters } => ("contains", letters),
+                    FilterCommand::Excludes { letters } => ("excludes", letters),
+                };
+                let (description, keep) = match make_filter(kind, argument) {
+                    Ok(filter) => filter,
+                    Err(e) => {
+                        println!("Error: {}", e);
+                        continue;
+                    }
+                };
+                let n_removed = session.apply_filter(description, &keep);
+                println!("Removed {} words.", n_removed);
+                println!(
+                    "{} possible answers remaining.",
+                    session.remaining_answers.len()
+                );
+
+                // The guesses are scored again once a command needs their scores
+                session.word_scores.push(None);
+                session.scores_complete.push(true);
+            }
+            SolverCommand::Remaining { n } => {
+                print_remaining(&session.remaining_answers.to_vec(), n, weights.as_ref())
+            }
+            SolverCommand::Probs { n, export } => {
+                let answers = session.remaining_answers.to_vec();
+                let probabilities = answer_probabilities(&answers, weights.as_ref());
+                match export {
+                    Some(file) => {
+                        let n_hints = session.guess_history.len();
+                        match write_probabilities(&file, &probabilities, n_hints) {
+                            Ok(()) => println!(
+                                "Wrote the probabilities of {} answers to {}.",
+                                probabilities.len(),
+                                file
+                            ),
+                            Err(e) => println!("Error: failed to write {}: {}", file, e),
+                        }
+                    }
+                    None if probabilities.is_empty() => println!("No possible answers remain."),
+                    None => {
+                        let rows: Vec<Vec<String>> = probabilities
+                            .iter()
+                            .take(n)
+                            .enumerate()
+                            .map(|(i, (w, p))| {
+                                vec![(i + 1).to_string(), shown(w), format!("{:.2}%", p * 100.0)]
+                            })
+                            .collect();
+                        print_table(&["Rank", "Word", "Probability"], &rows);
+                    }
+                }
+            }
+            SolverCommand::Tree {
+                command: TreeCommand::Load { file },
+            } => match load_tree(&file) {
+                Ok(loaded) => {
+                    println!(
+                        "Loaded a tree opening with {} over {} answers.",
+                        shown(&loaded.guess),
+                        loaded.n_answers
+                    );
+                    session.tree = Some(loaded);
+                }
+                Err(e) => println!("Error: failed to load {}: {}", file, e),
+            },
+            SolverCommand::Tree {
+                command: TreeCommand::Next,
+            } => match session
+                .tree
+                .as_ref()
+                .map(|tree| tree.follow(&session.guess_history))
+            {
+                None => println!("No tree loaded. Load one with 'tree load <file>'."),
+                Some(None) => println!("The game has left the tree."),
+                Some(Some(node)) => println!(
+                    "The tree plays {} next ({} answers left).",
+                    shown(&node.guess),
+                    node.n_answers
+                ),
+            },
+            SolverCommand::Save { file } => save_session(session, &settings, &file),
+            SolverCommand::Load { file } => {
+                let saved = match load_session(&file) {
+                    Ok(saved) => saved,
+                    Err(e) => {
+                        println!("Error: failed to load {}: {}", file, e);
+                        continue;
+                    }
+                };
+                if saved.word_size != session.word_size {
+                    println!(
+                        "Error: {} has {}-letter words, but the session has {}-letter words.",
+                        file, saved.word_size, session.word_size
+                    );
+                    continue;
+                }
+
+                session.reset();
+                for step in saved.steps {
+                    match step {
+                        SavedStep::Guess(guess, hint) => {
+                            session.apply_hint(&guess, &hint);
+                        }
+                        SavedStep::Filter(description, keep) => {
+                            session.apply_filter(description, &keep);
+                        }
+                    }
+                    session.word_scores.push(None);
+                    session.scores_complete.push(true);
+                }
+                settings.rules = saved.rules;
+                settings.vocab = saved.use_vocab && vocab.is_some();
+                settings.budget = saved.budget;
+                println!(
+                    "Loaded {} guesses and {} filters. {} possible answers remaining.",
+                    session.guess_history.len(),
+                    session.filters.len(),
+                    session.remaining_answers.len()
+                );
+                if saved.n_remaining != session.remaining_answers.len() {
+                    println!(
+                        "Warning: {} answers remained when it was saved, so the word lists differ.",
+                        saved.n_remaining
+                    );
+                }
+            }
+            SolverCommand::Undo => {
+                let undo_filter = session
+                    .filters
+                    .last()
+                    .is_some_and(|&(n_guesses, _)| n_guesses == session.guess_history.len());
+                if undo_filter {
+                    let (_, description) = session.filters.pop().expect("No filter to undo");
+                    println!("Undoing last filter: {}", description);
+                } else if let Some((guess, hint)) = session.guess_history.pop() {
+                    print!("Undoing last guess: ");
+                    print_hint(&hint, &guess);
+                    println!();
+                    session.remaining_guesses.push(guess);
+                } else {
+                    println!("Nothing to undo.");
+                    continue;
+                }
+                session.answers_changed();
+                assert!(
+                    session.remaining_answers.undo(),
+                    "No words to undo, mismatch between history and answer generations"
+                );
+                session
+                    .word_scores
+                    .pop()
+                    .expect("No word score lists to remove. Something went wrong.");
+                session.scores_complete.pop();
+                println!(
+                    "Restored word list to {} words.",
+                    session.remaining_answers.len()
+                );
+            }
+            SolverCommand::Exit => {
+                if !options.json {
+                    println!("Exiting solver...");
+                }
+                break;
+            }
+        }
+    }
+
+    // Only a person at the terminal can be asked, and a new session has nothing to save
+    if input::is_interrupted() && reader.is_interactive() {
+        input::clear_interrupt();
+        for session in sessions.iter().filter(|s| s.generation() > 0) {
+            let prompt = format!(
+                "Save session '{}' before exiting? Enter a file, or nothing to skip: ",
+                session.name
+            );
+            let Some(file) = reader.read_line(&prompt) else {
+                break;
+            };
+            if !file.trim().is_empty() {
+                save_session(session, &settings, file.trim());
+            }
+        }
+    }
+}
+
+/// Save the hints, filters and settings of a session to a JSON file that `load` reads
+fn save_session(session: &Session, settings: &Settings, file: &str) {
+    let steps: Vec<Value> = session
+        .steps()
+        .into_iter()
+        .map(|step| match step {
+            Step::Guess(guess, hint) => json!({
+                "guess": guess.to_string(),
+                "hint": hint.to_pattern(guess),
+            }),
+            Step::Filter(description) => json!({ "filter": description }),
+        })
+        .collect();
+    let remaining: Vec<String> = session
+        .remaining_answers
+        .iter()
+        .map(|w| w.to_string())
+        .collect();
+    let document = json!({
+        "version": SESSION_VERSION,
+        "word_size": session.word_size,
+        "steps": steps,
+        "remaining": remaining,
+        "settings": {
+            "rules": settings.rules.name(),
+            "vocab": if settings.vocab { "mine" } else { "all" },
+            "budget_ms": settings.budget.map(|b| b.as_millis() as u64),
+        },
+    });
+    match fs::write(file, format!("{:#}\n", document)) {
+        Ok(()) => println!(
+            "Saved {} guesses and {} filters to {}.",
+            session.guess_history.len(),
+            session.filters.len(),
+            file
+        ),
+        Err(e) => println!("Error: failed to save {}: {}", file, e),
+    }
+}
+
+/// Read the guesses and rows of an emoji share grid given to `import-share`
+///
+/// Rows that are not among `tokens` are read from the input, skipping the header line of the
+/// share post, until every guess has a row, a row solves the game, or a blank line ends the
+/// grid. Any other line is an error rather than a command, so it is not run by mistake.
+fn read_share_rows(
+    tokens: &[String],
+    word_size: usize,
+    reader: &mut LineReader,
+) -> Result<Vec<(Word, Hint)>, String> {
+    let mut guesses: Vec<Word> = vec![];
+    let mut hints: Vec<Hint> = vec![];
+    for token in tokens.iter() {
+        match parse_share_row(token, word_size) {
+            Ok((_, hint)) => hints.push(hint),
+            // A token with no squares is a guess
+            Err(ParseError::RowLength { found: 0, .. }) => {
+                guesses.push(Word::from_string(token).map_err(|e| e.to_string())?)
+            }
+            Err(e) => return Err(format!("'{}': {}", token, e)),
+        }
+    }
+    let solved = |hints: &[Hint]| {
+        hints
+            .last()
+            .is_some_and(|hint| hint.iter().all(|&h| h == LetterHint::Correct))
+    };
+
+    let n_expected = guesses.len();
+    while (n_expected == 0 || hints.len() < n_expected) && !solved(&hints) {
+        let Some(line) = reader.read_line("  ") else {
+            break;
+        };
+        let line = line.trim();
+        if line.is_empty() && !hints.is_empty() {
+            break;
+        }
+        let error = match parse_share_row(line, word_size) {
+            Ok((guess, hint)) => {
+                if let Some(guess) = guess.filter(|_| guesses.len() == hints.len()) {
+                    guesses.push(guess);
+                }
+                hints.push(hint);
+                continue;
+            }
+            Err(e) => e,
+        };
+
+        // Before the grid, skip blank lines and the "Wordle 1,234 4/6" header
+        let is_header = line
+            .split_whitespace()
+            .last()
+            .and_then(|score| score.trim_end_matches('*').split_once('/'))
+            .is_some_and(|(n, max)| {
+                (n == "X" || n.parse::<usize>().is_ok()) && max.parse::<usize>().is_ok()
+            });
+        if !(hints.is_empty() && (line.is_empty() || is_header)) {
+            return Err(format!("'{}' is not a row of the grid: {}", line, error));
+        }
+    }
+
+    if hints.is_empty() {
+        return Err("no rows of the grid were given".to_string());
+    }
+    if guesses.len() != hints.len() {
+        return Err(format!(
+            "found {} guesses but {} rows of the grid",
+            guesses.len(),
+            hints.len()
+        ));
+    }
+    if let Some(guess) = guesses.iter().find(|w| w.len() != word_size) {
+        return Err(format!(
+            "the guess {} does not have {} letters",
+            guess, word_size
+        ));
+    }
+    Ok(zip(guesses, hints).collect())
+}
+
+/// A session read back from a file written by `save`
+struct SavedSession {
+    word_size: usize,
+    steps: Vec<SavedStep>,
+    /// The number of answers that remained when the session was saved
+    n_remaining: usize,
+    rules: Ruleset,
+    use_vocab: bool,
+    budget: Option<Duration>,
+}
+
+/// A hint or filter read back by `load`, ready to be applied
+enum SavedStep {
+    Guess(Word, Hint),
+    Filter(String, Box<WordFilter>),
+}
+
+/// Read a session written by `save`
+fn load_session(path: &str) -> Result<SavedSession, String> {
+    let text = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let document: Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    match document["version"].as_u64() {
+        Some(SESSION_VERSION) => {}
+        Some(version) => return Err(format!("unsupported session version {}", version)),
+        None => return Err("not a saved session".to_string()),
+    }
+
+    let word_size = document["word_size"]
+        .as_u64()
+        .ok_or("the session has no word size")? as usize;
+    let steps = document["steps"]
+        .as_array()
+        .ok_or("the session has no steps")?
+        .iter()
+        .map(|step| {
+            if let Some(description) = step["filter"].as_str() {
+                let (kind, argument) = description.split_once(' ').unwrap_or((description, ""));
+                let (description, keep) = make_filter(kind, argument)?;
+                return Ok(SavedStep::Filter(description, keep));
+            }
+            let guess = step["guess"].as_str().ok_or("a step has no guess")?;
+            let guess = Word::from_string(guess).map_err(|e| e.to_string())?;
+            let hint = step["hint"].as_str().ok_or("a guess has no hint")?;
+            let hint = Hint::from_string(hint, &guess).map_err(|e| e.to_string())?;
+            if guess.len() != word_size || hint.len() != word_size {
+                return Err(format!(
+                    "the guess {} does not have {} letters",
+                    guess, word_size
+                ));
+            }
+            Ok(SavedStep::Guess(guess, hint))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    let n_remaining = document["remaining"]
+        .as_array()
+        .ok_or("the session has no remaining answers")?
+        .len();
+
+    let settings = &document["settings"];
+    let rules = match settings["rules"].as_str() {
+        Some(rules) => rules.parse()?,
+        None => Ruleset::Easy,
+    };
+    Ok(SavedSession {
+        word_size,
+        steps,
+        n_remaining,
+        rules,
+        use_vocab: settings["vocab"].as_str() == Some("mine"),
+        budget: settings["budget_ms"].as_u64().map(Duration::from_millis),
+    })
+}
+
+/// Print the error of a command, as a JSON line with --json
+fn print_error(json: bool, command: &str, message: &str) {
+    if json {
+        println!("{}", json!({ "command": command, "error": message }));
+    } else {
+        println!("{}", message);
+    }
+}
+
+/// Round a score to the precision the tables show, so JSON does not carry float noise
+fn json_percent(score: f32) -> f64 {
+    (score as f64 * 1000.0).round() / 1000.0
+}
+
+/// Round an expected number of guesses to the two decimals it is printed with
+fn json_guesses(guesses: f32) -> f64 {
+    (guesses as f64 * 100.0).round() / 100.0
+}
+
+/// Let the solver play against a known answer, printing every guess it makes
+fn auto_solve(
+    answer: &str,
+    guesses: &[Word],
+    answers: &[Word],
+    opening_scores: &[(Word, f32, f32)],
+    common: &CommonArgs,
+    options: &SolveArgs,
+) {
+    let secret = match Word::from_string(answer) {
+        Ok(w) => w,
+        Err(e) => {
+            println!("Error: {}", e);
+            return;
+        }
+    };
+    if !answers.contains(&secret) {
+        println!("{} is not in the answer list.", secret);
+        return;
+    }
+    let strategy = options.strategy.build(make_rng(common).gen());
+    let opener = match options.strategy {
+        StrategyKind::Entropy => best_guess(opening_scores, answers).cloned(),
+        _ => strategy.choose(guesses, answers, &[]),
+    };
+    let Some(opener) = opener else {
+        return;
+    };
+
+    let history = auto_play(
+        strategy.as_ref(),
+        guesses,
+        answers,
+        &secret,
+        &opener,
+        options.max_attempts,
+    );
+    for (i, (guess, hint, n_remaining)) in history.iter().enumerate() {
+        print!("{}: ", i + 1);
+        print_hint(hint, guess);
+        println!(" - answers left: {}", n_remaining);
+    }
+    match history.last() {
+        Some((guess, _, _)) if *guess == secret => {
+            println!("Solved {} in {} guesses.", secret, history.len())
+        }
+        _ => println!(
+            "Did not find {} within {} guesses.",
+            secret, options.max_attempts
+        ),
+    }
+}
+
+/// Print the groups of answers that share a hint for the guess, largest first
+fn print_clusters(guess: &Word, answers: &[Word]) {
+    let mut clusters: HashMap<u32, Vec<&Word>> = HashMap::new();
+    for answer in answers.iter() {
+        if let Ok(hint) = Hint::from_guess_and_answer(guess, answer) {
+            clusters.entry(hint.to_code()).or_default().push(answer);
+        }
+    }
+    let mut clusters: Vec<(u32, Vec<&Word>)> = clusters.into_iter().collect();
+    clusters.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+
+    // A group holding a share p of the answers adds -p * log2(p) bits to the entropy
+    let n_answers = answers.len() as f64;
+    let group_bits = |size: usize| {
+        let p = size as f64 / n_answers;
+        -p * p.log2()
+    };
+    let entropy: f64 = clusters
+        .iter()
+        .map(|(_, words)| group_bits(words.len()))
+        .sum();
+    println!(
+        "{} splits the {} answers into {} groups ({:.3} bits):",
+        shown(guess),
+        answers.len(),
+        clusters.len(),
+        entropy
+    );
+    let symbols = FeedbackSymbols::default();
+    for (code, words) in clusters.iter() {
+        let hint = Hint::from_code(*code, guess.len());
+        let squares: String = hint.iter().map(|&h| symbols.symbol(h)).collect();
+        let examples: Vec<String> = words.iter().take(8).map(|w| shown(w)).collect();
+        let more = if words.len() > examples.len() {
+            format!(", ... ({} more)", words.len() - examples.len())
+        } else {
+            String::new()
+        };
+        println!(
+            "{} {:>4} {:>5.1}% {:>5.3} bits  {}{}",
+            squares,
+            words.len(),
+            words.len() as f64 * 100.0 / n_answers,
+            group_bits(words.len()),
+            examples.join(", "),
+            more
+        );
+    }
+}
+
+/// Print a suggested guess with the answers it is expected to leave and a one-line reason
+fn print_suggestion(guess: &Word, answers: &[Word], strategy: StrategyKind, rules: Ruleset) {
+    println!(
+        "Suggestion: {} ({} strategy, {} rules)",
+        shown(guess),
+        strategy,
+        rules
+    );
+    let rationale = Rationale::new(guess, answers);
+    if answers.len() > 1 {
+        println!(
+            "Expected answers left: {:.2} of {} (at most {})",
+            rationale.expected,
+            answers.len(),
+            rationale.largest
+        );
+    }
+    println!("Why: {}.", rationale.why);
+}
+
+/// Why a guess is worth playing against the remaining answers
+pub struct Rationale {
+    /// The number of answers the guess is expected to leave
+    pub expected: f64,
+    /// The most answers it can leave
+    pub largest: usize,
+    /// The reason in words, without a final period
+    pub why: String,
+}
+
+impl Rationale {
+    pub fn new(guess: &Word, answers: &[Word]) -> Self {
+        let mut groups: HashMap<u32, usize> = HashMap::new();
+        for answer in answers.iter() {
+            if let Ok(hint) = Hint::from_guess_and_answer(guess, answer) {
+                *groups.entry(hint.to_code()).or_insert(0) += 1;
+            }
+        }
+        // An answer in a group of n leaves n answers, so on average sum(n^2) / N are left
+        let n_answers = answers.len().max(1);
+        let expected = groups.values().map(|&n| (n * n) as f64).sum::<f64>() / n_answers as f64;
+        let largest = groups.values().copied().max().unwrap_or(0);
+        let is_answer = answers.contains(guess);
+        if answers.len() <= 1 {
+            return Self {
+                expected,
+                largest,
+                why: "it is the only answer left".to_string(),
+            };
+        }
+
+        // Answers that differ in a single position can only be told apart by trying the
+        // differing letters, which a probe word does several at a time
+        let mut families: HashMap<String, Vec<char>> = HashMap::new();
+        for answer in answers.iter() {
+            for i in 0..answer.len() {
+                let pattern: String = answer
+                    .iter()
+                    .enumerate()
+                    .map(|(j, c)| if i == j { '_' } else { c })
+                    .collect();
+                if let Some(c) = answer.iter().nth(i) {
+                    families.entry(pattern).or_default().push(c);
+                }
+            }
+        }
+        let probed = families
+            .iter()
+            .filter(|(_, letters)| letters.len() >= 3)
+            .map(|(pattern, letters)| {
+                let tried: Vec<char> = letters
+                    .iter()
+                    .copied()
+                    .filter(|&c| guess.iter().any(|g| g == c))
+                    .collect();
+                (pattern, letters.len(), tried)
+            })
+            .filter(|(_, _, tried)| tried.len() >= 2)
+            .max_by(|a, b| {
+                a.2.len()
+                    .cmp(&b.2.len())
+                    .then(a.1.cmp(&b.1))
+                    .then(b.0.cmp(a.0))
+            });
+
+        let why = if largest == 1 {
+            match is_answer {
+                true => {
+                    "it could be the answer, and any other answer is certain after it".to_string()
+                }
+                false => "it leaves every answer in a group of its own".to_string(),
+            }
+        } else if let Some((pattern, size, tried)) = probed.filter(|_| !is_answer) {
+            let tried: Vec<String> = tried.iter().map(|c| c.to_string()).collect();
+            format!(
+                "probe word to split the {} family of {} answers, trying {} at once",
+                pattern,
+                size,
+                tried.join(", ")
+            )
+        } else if is_answer {
+            format!(
+                "it could be the answer and splits the rest into {} groups",
+                groups.len() - 1
+            )
+        } else {
+            format!(
+                "not a possible answer, but splits the answers into {} groups",
+                groups.len()
+            )
+        };
+        Self {
+            expected,
+            largest,
+            why,
+        }
+    }
+}
+
+/// The entropy in bits of a distribution given by counts
+fn entropy_bits(counts: impl Iterator<Item = usize>, total: usize) -> f64 {
+    counts
+        .filter(|&n| n > 0)
+        .map(|n| n as f64 / total as f64)
+        .map(|p| p * (1.0 / p).log2())
+        .sum()
+}
+
+/// Print the information a guess gives over the answers, in total and per letter
+fn print_explanation(guess: &Word, answers: &[Word]) {
+    if answers.is_empty() {
+        println!("No possible answers remain.");
+        return;
+    }
+    let hints: Vec<Hint> = answers
+        .iter()
+        .filter_map(|answer| Hint::from_guess_and_answer(guess, answer).ok())
+        .collect();
+    let mut groups: HashMap<u32, usize> = HashMap::new();
+    for hint in hints.iter() {
+        *groups.entry(hint.to_code()).or_insert(0) += 1;
+    }
+    println!(
+        "{} gives {:.3} bits over the {} answers, splitting them into {} groups.\n",
+        guess,
+        entropy_bits(groups.values().copied(), hints.len()),
+        hints.len(),
+        groups.len()
+    );
+
+    let mut rows = vec![];
+    for (i, letter) in guess.iter().enumerate() {
+        let count = |kind: LetterHint| {
+            hints
+                .iter()
+                .filter(|h| h.iter().nth(i) == Some(&kind))
+                .count()
+        };
+        let counts = LetterHint::ALL.map(count);
+        let bits = entropy_bits(counts.into_iter(), hints.len());
+        let note = if counts[0] == hints.len() {
+            "known: green in every answer"
+        } else if !answers.iter().any(|w| w.iter().any(|c| c == letter)) {
+            "dead: in no answer"
+        } else if bits == 0.0 {
+            "wasted: the same hint for every answer"
+        } else {
+            ""
+        };
+        let percent = |n: usize| format!("{:.1}%", n as f64 * 100.0 / hints.len() as f64);
+        rows.push(vec![
+            (i + 1).to_string(),
+            letter.to_string(),
+            percent(counts[0]),
+            percent(counts[1]),
+            percent(counts[2]),
+            format!("{:.3}", bits),
+            note.to_string(),
+        ]);
+    }
+    print_table(
+        &[
+            "Position", "Letter", "Green", "Yellow", "Grey", "Bits", "Note",
+        ],
+        &rows,
+    );
+    println!(
+        "\nThe letters' hints depend on each other, so their bits add up to more than the total."
+    );
+}
+
+/// Print the letters the answers have at a position, with how many answers have each
+fn print_position_letters(answers: &[Word], position: usize) {
+    let mut counts: HashMap<char, usize> = HashMap::new();
+    for word in answers.iter() {
+        if let Some(c) = word.iter().nth(position) {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+    }
+    let mut letters: Vec<(char, usize)> = counts.into_iter().collect();
+    letters.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+    println!("Possible letters at position {}:", position + 1);
+    let rows: Vec<Vec<String>> = letters
+        .into_iter()
+        .map(|(c, count)| {
+            let share = count as f32 * 100.0 / answers.len() as f32;
+            vec![c.to_string(), count.to_string(), format!("{:.1}%", share)]
+        })
+        .collect();
+    print_table(&["Letter", "Answers left", "Share"], &rows);
+}
+
+/// Classify a guess in `top` by whether it can win and whether its worst case can still be
+/// finished in the guesses left, as judged by `solvable_within`
+fn classify_risk(word: &Word, answers: &[Word], guesses_left: usize) -> &'static str {
+    let mut group_sizes: HashMap<usize, usize> = HashMap::new();
+    for group in partition_key(word, answers) {
+        *group_sizes.entry(group).or_insert(0) += 1;
+    }
+    let worst_group = group_sizes.into_values().max().unwrap_or(0);
+    let solvable = guesses_left
+        .checked_sub(1)
+        .is_some_and(|after| solvable_within(worst_group, after));
+    match (solvable, answers.contains(word)) {
+        (true, true) => "possible answer",
+        (true, false) => "safe probe",
+        (false, _) => "gamble",
+    }
+}
+
+/// Print the first `n` answers, ten to a line, in alphabetical order or, with weights, from
+/// the most likely to the least with the chance of each
+fn print_remaining(answers: &[Word], n: usize, weights: Option<&HashMap<Word, f64>>) {
+    match answers.len() {
+        0 => println!("No possible answers remain."),
+        len if len <= n => println!("{} possible answers remain:", len),
+        len => println!("{} possible answers remain, the first {}:", len, n),
+    }
+    let Some(weights) = weights else {
+        let mut answers: Vec<String> = answers.iter().map(shown).collect();
+        answers.sort();
+        for line in answers[..n.min(answers.len())].chunks(10) {
+            println!("{}", line.join(" "));
+        }
+        return;
+    };
+
+    let answers: Vec<String> = answer_probabilities(answers, Some(weights))
+        .iter()
+        .take(n)
+        .map(|(w, p)| format!("{} {:.1}%", shown(w), p * 100.0))
+        .collect();
+    for line in answers.chunks(5) {
+        println!("{}", line.join("  "));
+    }
+}
+
+/// The probability of each answer being the secret word, from the likeliest
+/// Answers missing from the weights weigh 1, and every answer weighs the same without weights.
+fn answer_probabilities(
+    answers: &[Word],
+    weights: Option<&HashMap<Word, f64>>,
+) -> Vec<(Word, f64)> {
+    let weight = |w: &Word| {
+        weights
+            .and_then(|weights| weights.get(w).copied())
+            .unwrap_or(1.0)
+            .max(0.0)
+    };
+    let total = answers
+        .iter()
+        .map(weight)
+        .sum::<f64>()
+        .max(f64::MIN_POSITIVE);
+    let mut probabilities: Vec<(Word, f64)> = answers
+        .iter()
+        .map(|w| (w.clone(), weight(w) / total))
+        .collect();
+    probabilities.sort_by(|a, b| b.1.total_cmp(&a.1).then(Ord::cmp(&a.0, &b.0)));
+    probabilities
+}
+
+/// Write the probabilities of the answers to a CSV file, one row per answer
+/// The "hints" column holds the number of hints they follow, so files written after each
+/// hint of a game can be concatenated.
+fn write_probabilities(
+    path: &str,
+    probabilities: &[(Word, f64)],
+    n_hints: usize,
+) -> io::Result<()> {
+    let mut csv = String::from("hints,rank,word,probability\n");
+    for (i, (w, p)) in probabilities.iter().enumerate() {
+        csv += &format!("{},{},{},{:.6}\n", n_hints, i + 1, shown(w), p);
+    }
+    fs::write(path, csv)
+}
+
+/// Load how common each answer is from --weights-file, or else from a frequency column in
+/// the answer list
+fn load_answer_weights(common: &CommonArgs, options: &SolveArgs) -> Option<HashMap<Word, f64>> {
+    let path = match (&options.weights_file, &common.answers_file, &common.file) {
+        (Some(path), _, _) => path,
+        (None, Some(path), _) | (None, None, Some(path)) => path,
+        (None, None, None) => return None,
+    };
+    match word::load_weights(path) {
+        Ok(weights) if !weights.is_empty() => {
+            println!("Loaded {} answer weights", weights.len());
+            Some(weights)
+        }
+        Ok(_) if options.weights_file.is_some() => {
+            println!("Warning: no weights found in {}", path);
+            None
+        }
+        Err(e) if options.weights_file.is_some() => {
+            println!("Warning: failed to load the answer weights {}: {}", path, e);
+            None
+        }
+        _ => None,
+    }
+}
+
+/// Print how many answers contain a repeated letter and which repeats are most common
+fn print_doubles(answers: &[Word]) {
+    let mut n_doubles = 0;
+    let mut repeats: HashMap<(char, usize), Vec<&Word>> = HashMap::new();
+    for word in answers.iter() {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for c in word.iter() {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+        let mut repeated = counts.into_iter().filter(|&(_, n)| n > 1).peekable();
+        if repeated.peek().is_some() {
+            n_doubles += 1;
+        }
+        for repeat in repeated {
+            repeats.entry(repeat).or_default().push(word);
+        }
+    }
+
+    println!(
+        "{} of {} possible answers ({:.1}%) contain a repeated letter.",
+        n_doubles,
+        answers.len(),
+        n_doubles as f32 * 100.0 / answers.len().max(1) as f32
+    );
+    if repeats.is_empty() {
+        return;
+    }
+
+    let mut repeats: Vec<((char, usize), Vec<&Word>)> = repeats.into_iter().collect();
+    repeats.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+    let mut rows = vec![];
+    for ((c, n), words) in repeats.iter().take(10) {
+        let examples: Vec<String> = words.iter().take(5).map(|w| shown(w)).collect();
+        rows.push(vec![
+            c.to_string().repeat(*n),
+            words.len().to_string(),
+            examples.join(", "),
+        ]);
+    }
+    print_table(&["Repeat", "Answers", "Examples"], &rows);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(words: &[&str]) -> Session {
+        let words: Vec<Word> = words
+            .iter()
+            .map(|w| Word::from_string(w).unwrap())
+            .collect();
+        Session::new("test", 5, WordLists::single(words), vec![])
+    }
+
+    fn word(word: &str) -> Word {
+        Word::from_string(word).unwrap()
+    }
+
+    #[test]
+    fn changing_the_answers_drops_the_background_rescore() {
+        let mut session = session(&["crane", "slate", "trace", "sheep", "queue"]);
+        let scorer = Scorer {
+            weights: None,
+            matrix: None,
+            show_progress: false,
+        };
+        let hint = Hint::from_guess_and_answer(&word("slate"), &word("queue")).unwrap();
+        session.apply_hint(&word("slate"), &hint);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let answers = session.remaining_answers.to_vec();
+        session.rescore = Some(Rescore {
+            state: session.state,
+            n_candidates: session.remaining_guesses.len(),
+            handle: scorer.spawn(
+                session.remaining_guesses.clone(),
+                answers,
+                None,
+                cancelled.clone(),
+            ),
+            cancelled: cancelled.clone(),
+        });
+        let state = session.state;
+
+        // Undoing the hint, as `undo` does, and narrowing the answers down another way
+        session.answers_changed();
+        session.remaining_answers.undo();
+        assert!(session.rescore.is_none());
+        assert!(cancelled.load(Ordering::Relaxed));
+        session.apply_filter("contains Q".to_string(), &|w: &Word| {
+            w.iter().any(|c| c == 'Q')
+        });
+        assert_ne!(session.state, state);
+        assert_eq!(session.remaining_answers.to_vec(), vec![word("queue")]);
+    }
+
+    #[test]
+    fn resetting_the_session_restores_every_answer() {
+        let mut session = session(&["crane", "slate", "trace"]);
+        let hint = Hint::from_guess_and_answer(&word("crane"), &word("trace")).unwrap();
+        session.apply_hint(&word("crane"), &hint);
+        session.word_scores.push(None);
+        session.scores_complete.push(true);
+        assert_eq!(session.generation(), 1);
+        session.reset();
+        assert_eq!(session.generation(), 0);
+        assert_eq!(session.remaining_answers.len(), 3);
+        assert_eq!(session.word_scores.len(), 1);
+    }
+}
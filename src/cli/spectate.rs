@@ -0,0 +1,69 @@
+//! Games broadcast over TCP to spectators watching from their own terminals
+//!
+//! The host sends every spectator the board as plain text after each guess, each frame
+//! starting with the ANSI codes that clear the screen, so `rudle watch` and even `nc` show the
+//! game as it is played. The secret word is only sent once the game is lost.
+
+use std::io::{self, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Clear the screen and move the cursor to its top left corner
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// The spectators connected to a game, and the latest frame sent to them
+#[derive(Default)]
+struct Audience {
+    spectators: Vec<TcpStream>,
+    latest: String,
+}
+
+/// A game spectators can connect to
+pub struct SpectatorHost {
+    audience: Arc<Mutex<Audience>>,
+}
+
+impl SpectatorHost {
+    /// Listen for spectators, sending each the latest frame as soon as they connect
+    /// # Arguments
+    /// * `address` - The address to listen on, e.g. "0.0.0.0:7878"
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let listener = TcpListener::bind(address)?;
+        let audience = Arc::new(Mutex::new(Audience::default()));
+        let accepting = Arc::clone(&audience);
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let mut audience = accepting.lock().unwrap_or_else(|e| e.into_inner());
+                if stream.write_all(audience.latest.as_bytes()).is_ok() {
+                    audience.spectators.push(stream);
+                }
+            }
+        });
+        Ok(Self { audience })
+    }
+
+    /// Send a frame to every spectator, dropping the ones that have disconnected
+    pub fn broadcast(&self, frame: &str) {
+        let mut audience = self.audience.lock().unwrap_or_else(|e| e.into_inner());
+        audience.latest = format!("{}{}", CLEAR_SCREEN, frame);
+        let Audience { spectators, latest } = &mut *audience;
+        spectators.retain_mut(|stream| stream.write_all(latest.as_bytes()).is_ok());
+    }
+}
+
+/// Watch a game hosted with `play --host`, until the host closes the connection
+pub fn watch(address: &str) {
+    let mut stream = match TcpStream::connect(address) {
+        Ok(stream) => stream,
+        Err(e) => {
+            println!("Error: failed to connect to {}: {}", address, e);
+            return;
+        }
+    };
+    if let Err(e) = io::copy(&mut stream, &mut io::stdout()) {
+        println!("\nError: lost the connection: {}", e);
+        return;
+    }
+    println!("\nThe host closed the game.");
+}
@@ -0,0 +1,566 @@
+//! Statistics over the games played, kept between sessions
+
+use super::cache::opening_scores;
+use super::persist::{self, Format};
+use super::rating::{load_ratings, print_rating};
+use super::render::print_table;
+use super::{current_day, data_dir};
+use crate::CommonArgs;
+use rudle::hint::parse_share_row;
+use rudle::{Hint, LetterHint, Word, WordLists};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// The format of the list of finished games
+const GAMES_FORMAT: Format = Format {
+    name: "games",
+    migrations: &[persist::unchanged],
+};
+
+/// How a game ended
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum GameEnd {
+    Solved,
+    OutOfAttempts,
+    /// The player gave up with `!giveup`, which counts as a loss
+    GaveUp,
+    /// The input ended before the game did, which is not counted as played
+    Quit,
+}
+
+/// A finished game
+///
+/// Games are stored as one `DAY SECRET RESULT GUESS...` line each, where the result is the
+/// number of guesses it took, `X` for a lost game, `F` for a game given up or `Q` for one
+/// quit before it ended. Imported games may not know the secret, stored as `?`, or every
+/// guess, in which case only the guesses that are known are listed.
+pub struct GameRecord {
+    /// The day since the Unix epoch the game was played on
+    pub day: u64,
+    pub secret: Option<Word>,
+    pub guesses: Vec<Word>,
+    /// The number of guesses the game was solved in, or None if it was not
+    pub result: Option<usize>,
+    pub end: GameEnd,
+}
+
+impl GameRecord {
+    /// The record of a game played here on a day, given how many attempts were spent on
+    /// reveals
+    fn played(
+        day: u64,
+        secret_word: &Word,
+        board: &[(Word, Hint)],
+        n_reveals: usize,
+        end: GameEnd,
+    ) -> Self {
+        Self {
+            day,
+            secret: Some(secret_word.clone()),
+            guesses: board.iter().map(|(guess, _)| guess.clone()).collect(),
+            result: (end == GameEnd::Solved).then_some(board.len() + n_reveals),
+            end,
+        }
+    }
+
+    fn to_line(&self) -> String {
+        let secret = self
+            .secret
+            .as_ref()
+            .map_or("?".to_string(), |w| w.to_string());
+        let result = match (self.result, self.end) {
+            (Some(n), _) => n.to_string(),
+            (None, GameEnd::GaveUp) => "F".to_string(),
+            (None, GameEnd::Quit) => "Q".to_string(),
+            (None, _) => "X".to_string(),
+        };
+        let guesses: Vec<String> = self.guesses.iter().map(|w| w.to_string()).collect();
+        format!("{} {} {} {}", self.day, secret, result, guesses.join(" "))
+    }
+
+    /// Read a `DAY SECRET RESULT GUESS...` line
+    pub fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        let day: u64 = fields.next()?.parse().ok()?;
+        let secret = match fields.next()? {
+            "?" => None,
+            secret => Some(Word::from_string(secret).ok()?),
+        };
+        let (result, end) = match fields.next()? {
+            "X" => (None, GameEnd::OutOfAttempts),
+            "F" => (None, GameEnd::GaveUp),
+            "Q" => (None, GameEnd::Quit),
+            n => (Some(n.parse().ok()?), GameEnd::Solved),
+        };
+        let guesses: Option<Vec<Word>> = fields.map(|w| Word::from_string(w).ok()).collect();
+        Some(Self {
+            day,
+            secret,
+            guesses: guesses?,
+            result,
+            end,
+        })
+    }
+}
+
+fn games_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("games.txt"))
+}
+
+/// Load the finished games, treating a missing file as no games played
+pub fn load_games() -> Vec<GameRecord> {
+    let contents = games_path()
+        .and_then(|path| GAMES_FORMAT.load(&path))
+        .unwrap_or_default();
+    contents.lines().filter_map(GameRecord::from_line).collect()
+}
+
+/// Add a finished game to the games played so far
+/// Attempts spent on reveals count towards the result, though only the guesses are listed.
+pub fn record_game(
+    secret_word: &Word,
+    board: &[(Word, Hint)],
+    n_reveals: usize,
+    end: GameEnd,
+) -> io::Result<()> {
+    let path =
+        games_path().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+    let game = GameRecord::played(current_day(), secret_word, board, n_reveals, end);
+
+    let mut contents = GAMES_FORMAT.load(&path).unwrap_or_default();
+    contents.push_str(&game.to_line());
+    contents.push('\n');
+    GAMES_FORMAT.save(&path, &contents)
+}
+
+/// The share of the recent recorded games with secret words of a length that were won
+/// # Returns
+/// The win rate and how many games it is taken over, or None if no such game was recorded
+pub fn recent_win_rate(word_size: usize, n_games: usize) -> Option<(f64, usize)> {
+    let games = load_games();
+    let recent: Vec<&GameRecord> = games
+        .iter()
+        .rev()
+        .filter(|game| game.secret.as_ref().is_some_and(|w| w.len() == word_size))
+        .filter(|game| game.end != GameEnd::Quit)
+        .take(n_games)
+        .collect();
+    let won = recent.iter().filter(|game| game.result.is_some()).count();
+    (!recent.is_empty()).then(|| (won as f64 / recent.len() as f64, recent.len()))
+}
+
+/// The day since the Unix epoch of Wordle puzzle 0, from which puzzle numbers are counted
+pub const WORDLE_EPOCH_DAY: u64 = 18_797;
+
+/// The format of the list of daily puzzles played
+const DAILY_FORMAT: Format = Format {
+    name: "daily",
+    migrations: &[],
+};
+
+/// The file listing every daily puzzle played, as one `DAY RESULT` line each
+fn daily_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("daily.txt"))
+}
+
+/// Load the result of every daily puzzle played, by day
+/// The result is the number of guesses it took, or None if the puzzle was lost.
+pub fn load_daily() -> HashMap<u64, Option<usize>> {
+    daily_path()
+        .and_then(|path| DAILY_FORMAT.load(&path))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let (day, result) = line.split_once(' ')?;
+            let result = match result.trim() {
+                "X" => None,
+                n => Some(n.parse().ok()?),
+            };
+            Some((day.parse().ok()?, result))
+        })
+        .collect()
+}
+
+/// Add the result of a daily puzzle to the puzzles played
+pub fn record_daily(day: u64, result: Option<usize>) -> io::Result<()> {
+    let path =
+        daily_path().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+    let mut contents = DAILY_FORMAT.load(&path).unwrap_or_default();
+    let result = result.map_or("X".to_string(), |n| n.to_string());
+    contents.push_str(&format!("{} {}\n", day, result));
+    DAILY_FORMAT.save(&path, &contents)
+}
+
+/// The current and the longest run of daily puzzles won on consecutive days
+/// A streak is still current on the day after its last puzzle, until that day's puzzle is lost.
+pub fn daily_streaks(daily: &HashMap<u64, Option<usize>>, today: u64) -> (usize, usize) {
+    let mut won: Vec<u64> = daily
+        .iter()
+        .filter(|(_, result)| result.is_some())
+        .map(|(&day, _)| day)
+        .collect();
+    won.sort();
+
+    let (mut longest, mut streak, mut last) = (0, 0, None);
+    for &day in won.iter() {
+        streak = match last {
+            Some(last) if day == last + 1 => streak + 1,
+            _ => 1,
+        };
+        longest = longest.max(streak);
+        last = Some(day);
+    }
+    let current = match last {
+        Some(last) if last == today || (last + 1 == today && !daily.contains_key(&today)) => streak,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+/// A game read from share text, with the guess of each row if the text lists it
+pub struct SharedGame {
+    /// The header line, which names the game in messages
+    pub title: String,
+    pub day: u64,
+    pub result: Option<usize>,
+    pub rows: Vec<(Option<Word>, Hint)>,
+}
+
+/// Read a `Wordle 1,234 4/6` (or `Rudle 4/6`) header line
+/// # Returns
+/// The day the game was played and the number of guesses it took, or None if it was lost
+fn parse_header(line: &str) -> Option<(u64, Option<usize>)> {
+    let mut fields = line.split_whitespace();
+    let name = fields.next()?;
+    let (day, score) = match name {
+        "Wordle" => {
+            let number: u64 = fields.next()?.replace([',', '.'], "").parse().ok()?;
+            (WORDLE_EPOCH_DAY + number, fields.next()?)
+        }
+        "Rudle" => (current_day(), fields.next()?),
+        _ => return None,
+    };
+    // Hard mode scores are marked with a trailing '*'
+    let (result, _max_attempts) = score.trim_end_matches('*').split_once('/')?;
+    let result = match result {
+        "X" => None,
+        n => Some(n.parse().ok()?),
+    };
+    Some((day, result))
+}
+
+/// Read every game in share text, each a header line followed by its grid
+pub fn parse_shared_games(text: &str, word_size: usize) -> Vec<SharedGame> {
+    let mut games: Vec<SharedGame> = vec![];
+    for line in text.lines() {
+        if let Some((day, result)) = parse_header(line) {
+            games.push(SharedGame {
+                title: line.trim().to_string(),
+                day,
+                result,
+                rows: vec![],
+            });
+        } else if let (Some(game), Some(row)) =
+            (games.last_mut(), parse_share_row(line, word_size).ok())
+        {
+            game.rows.push(row);
+        }
+    }
+    games
+}
+
+/// Turn a shared game into a record, checking that its grid matches its header and guesses
+fn to_record(game: &SharedGame, opener: Option<&Word>) -> Result<GameRecord, String> {
+    let solved = game
+        .rows
+        .last()
+        .is_some_and(|(_, hint)| hint.iter().all(|&h| h == LetterHint::Correct));
+    match game.result {
+        Some(n) if !solved || n != game.rows.len() => {
+            return Err(format!("the score {} does not match its grid", n));
+        }
+        None if solved => return Err("a lost game ends with a solved row".to_string()),
+        _ => {}
+    }
+
+    let mut guesses: Vec<Word> = game
+        .rows
+        .iter()
+        .map_while(|(guess, _)| guess.clone())
+        .collect();
+    if guesses.is_empty() {
+        guesses.extend(opener.cloned());
+    }
+    if guesses.is_empty() {
+        return Err("the grid does not list its guesses (pass --opener)".to_string());
+    }
+
+    let secret = match solved {
+        true if guesses.len() == game.rows.len() => guesses.last().cloned(),
+        true if game.rows.len() == 1 => guesses.first().cloned(),
+        _ => None,
+    };
+    if let Some(secret) = &secret {
+        for (guess, (_, row_hint)) in guesses.iter().zip(game.rows.iter()) {
+            let hint = Hint::from_guess_and_answer(guess, secret).map_err(|e| e.to_string())?;
+            if hint != *row_hint {
+                return Err(format!("the guess {} does not match its row", guess));
+            }
+        }
+    }
+
+    Ok(GameRecord {
+        day: game.day,
+        secret,
+        guesses,
+        result: game.result,
+        end: match solved {
+            true => GameEnd::Solved,
+            false => GameEnd::OutOfAttempts,
+        },
+    })
+}
+
+/// Add the games in WordleBot share text to the games played, so `stats` includes them
+///
+/// The text holds any number of `Wordle 1,234 4/6` headers, each followed by its grid. The
+/// NYT grid only shows the hints, so each row may also name its guess (e.g. `🟨⬛⬛⬛🟩 CRANE`);
+/// grids without guesses are recorded with `opener` as their first guess. Games that are
+/// already recorded are skipped, so the same export can be imported again.
+pub fn import(path: &str, opener: Option<&str>, common: &CommonArgs) {
+    let opener = match opener.map(Word::from_string).transpose() {
+        Ok(opener) => opener,
+        Err(e) => {
+            println!("Warning: Invalid opener: {}", e);
+            return;
+        }
+    };
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => {
+            println!("Warning: Could not read {}: {}", path, e);
+            return;
+        }
+    };
+    let Some(games_path) = games_path() else {
+        println!("Warning: Could not find a data directory to save the games in");
+        return;
+    };
+
+    let games = parse_shared_games(&text, common.word_size);
+    if games.is_empty() {
+        println!("No games found in {}.", path);
+        return;
+    }
+
+    let mut contents = GAMES_FORMAT.load(&games_path).unwrap_or_default();
+    let (mut n_imported, mut n_duplicates) = (0, 0);
+    for game in games.iter() {
+        match to_record(game, opener.as_ref()) {
+            Ok(record) => {
+                let line = record.to_line();
+                if contents.lines().any(|l| l == line) {
+                    n_duplicates += 1;
+                } else {
+                    contents.push_str(&line);
+                    contents.push('\n');
+                    n_imported += 1;
+                }
+            }
+            Err(e) => println!("Warning: Skipped '{}': {}", game.title, e),
+        }
+    }
+
+    if let Err(e) = GAMES_FORMAT.save(&games_path, &contents) {
+        println!("Warning: Could not save the games: {}", e);
+        return;
+    }
+    println!(
+        "Imported {} of {} games ({} already recorded).",
+        n_imported,
+        games.len(),
+        n_duplicates
+    );
+}
+
+/// The longest bar of the guess distribution, in characters
+const DISTRIBUTION_WIDTH: usize = 40;
+
+/// Print the win rate and streaks of the games, in the order they were played, and a bar
+/// chart of the guesses the won games took
+fn print_summary(games: &[GameRecord]) {
+    let n_won = games.iter().filter(|game| game.result.is_some()).count();
+    let n_given_up = games
+        .iter()
+        .filter(|game| game.end == GameEnd::GaveUp)
+        .count();
+    let (mut current, mut longest) = (0, 0);
+    for game in games.iter() {
+        current = match game.result {
+            Some(_) => current + 1,
+            None => 0,
+        };
+        longest = longest.max(current);
+    }
+    println!(
+        "Played {}, won {} ({:.1}%), current streak {}, longest streak {}.",
+        games.len(),
+        n_won,
+        n_won as f64 * 100.0 / games.len() as f64,
+        current,
+        longest
+    );
+    if n_given_up > 0 {
+        println!("{} of the games lost were given up.", n_given_up);
+    }
+    println!();
+
+    let max_guesses = games
+        .iter()
+        .filter_map(|game| game.result)
+        .max()
+        .unwrap_or(0)
+        .max(6);
+    let mut counts = vec![0; max_guesses + 1];
+    for game in games.iter() {
+        counts[game.result.unwrap_or(0)] += 1;
+    }
+    let most = counts.iter().copied().max().unwrap_or(0).max(1);
+    println!("Guess distribution:");
+    for (n_guesses, &count) in counts.iter().enumerate().skip(1).chain([(0, &counts[0])]) {
+        let label = match n_guesses {
+            0 => "X".to_string(),
+            n => n.to_string(),
+        };
+        let bar = "#".repeat((count * DISTRIBUTION_WIDTH).div_ceil(most));
+        println!("{:>2} | {} {}", label, bar, count);
+    }
+    println!();
+}
+
+/// Report how each opening word has done in the games played, next to its expected score
+pub fn stats(words: WordLists, common: &CommonArgs) {
+    let (quit, games): (Vec<GameRecord>, Vec<GameRecord>) = load_games()
+        .into_iter()
+        .filter(|game| {
+            game.guesses
+                .first()
+                .is_some_and(|w| w.len() == common.word_size)
+        })
+        .partition(|game| game.end == GameEnd::Quit);
+    print_rating(&load_ratings());
+    let daily = load_daily();
+    if !daily.is_empty() {
+        let (current, longest) = daily_streaks(&daily, current_day());
+        let n_won = daily.values().filter(|result| result.is_some()).count();
+        println!(
+            "Daily puzzles: {} played, {} won, current streak {}, longest streak {}.\n",
+            daily.len(),
+            n_won,
+            current,
+            longest
+        );
+    }
+    if games.is_empty() {
+        println!("No {}-letter games played yet.", common.word_size);
+        return;
+    }
+    print_summary(&games);
+    if !quit.is_empty() {
+        println!(
+            "{} games quit before they ended are not counted.\n",
+            quit.len()
+        );
+    }
+
+    // The guesses each opener's solved games took, and how many games it lost
+    let mut openers: HashMap<&Word, (Vec<usize>, usize)> = HashMap::new();
+    for game in games.iter() {
+        let (solved, n_failed) = openers.entry(&game.guesses[0]).or_default();
+        match game.result {
+            Some(n_guesses) => solved.push(n_guesses),
+            None => *n_failed += 1,
+        }
+    }
+    let mut openers: Vec<(&Word, (Vec<usize>, usize))> = openers.into_iter().collect();
+    openers.sort_by(|(a, (a_solved, a_failed)), (b, (b_solved, b_failed))| {
+        let n_games = |solved: &Vec<usize>, failed| solved.len() + failed;
+        n_games(b_solved, *b_failed)
+            .cmp(&n_games(a_solved, *a_failed))
+            .then(a.cmp(b))
+    });
+
+    let scores = opening_scores(&words, common);
+    println!("Openers over {} games:\n", games.len());
+    let mut rows = vec![];
+    for (opener, (solved, n_failed)) in openers.iter() {
+        let average = match solved.is_empty() {
+            true => "-".to_string(),
+            false => format!(
+                "{:.3}",
+                solved.iter().sum::<usize>() as f32 / solved.len() as f32
+            ),
+        };
+        let (expected, rank) = match scores.iter().position(|(w, _, _)| w == *opener) {
+            Some(i) => (format!("{:.3}%", scores[i].1), (i + 1).to_string()),
+            None => ("-".to_string(), "-".to_string()),
+        };
+        rows.push(vec![
+            opener.to_string(),
+            (solved.len() + n_failed).to_string(),
+            average,
+            n_failed.to_string(),
+            expected,
+            rank,
+        ]);
+    }
+    print_table(
+        &["Opener", "Games", "Average", "Failed", "Expected", "Rank"],
+        &rows,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(guess: &str, answer: &str) -> (Word, Hint) {
+        let guess = Word::from_string(guess).unwrap();
+        let answer = Word::from_string(answer).unwrap();
+        let hint = Hint::from_guess_and_answer(&guess, &answer).unwrap();
+        (guess, hint)
+    }
+
+    #[test]
+    fn a_win_with_a_reveal_records_an_attempt_more_than_its_guesses() {
+        let secret = Word::from_string("petal").unwrap();
+        let board = [row("sheep", "petal"), row("petal", "petal")];
+        let game = GameRecord::played(0, &secret, &board, 1, GameEnd::Solved);
+        assert_eq!(game.result, Some(3));
+        assert_eq!(game.guesses.len(), 2);
+        assert!(game.to_line().starts_with("0 PETAL 3 SHEEP PETAL"));
+    }
+
+    #[test]
+    fn a_lost_game_records_no_result() {
+        let secret = Word::from_string("petal").unwrap();
+        let board = [row("sheep", "petal")];
+        let game = GameRecord::played(0, &secret, &board, 1, GameEnd::OutOfAttempts);
+        assert_eq!(game.result, None);
+        assert!(game.to_line().starts_with("0 PETAL X "));
+    }
+
+    #[test]
+    fn games_given_up_and_quit_are_told_apart() {
+        let secret = Word::from_string("petal").unwrap();
+        let board = [row("sheep", "petal")];
+        for (end, token) in [(GameEnd::GaveUp, "F"), (GameEnd::Quit, "Q")] {
+            let line = GameRecord::played(0, &secret, &board, 0, end).to_line();
+            assert_eq!(line, format!("0 PETAL {} SHEEP", token));
+            let game = GameRecord::from_line(&line).unwrap();
+            assert_eq!((game.day, game.end, game.guesses.len()), (0, end, 1));
+        }
+    }
+}
@@ -0,0 +1,592 @@
+//! Tournaments between built-in strategies and bots run as child processes
+//!
+//! Bots speak a JSON-lines protocol on their stdin and stdout, so they can be written in any
+//! language. Rudle writes one JSON object per line, and the bot answers each `guess` message
+//! with one line of its own:
+//! * `{"type": "start", "version": 1, "word_size": 5, "max_attempts": 6, "guesses": [...],
+//!   "answers": [...]}` once, before the first game
+//! * `{"type": "guess", "game": 1, "history": [{"guess": "CRANE", "hint": "bybbg"}]}` before
+//!   each guess, answered with `{"guess": "SLATE"}`. Hints have a letter per square: g for a
+//!   correct letter, y for a misplaced one and b for one not in the word.
+//! * `{"type": "result", "game": 1, "answer": "SLATE", "solved": true, "guesses": 2}` after
+//!   each game
+//!
+//! Once every game is played, the bot's stdin is closed and it should exit. What it writes to
+//! stderr is shown as it is.
+
+use super::bench::choose_opener;
+use super::render::print_table;
+use super::results::{create_results, Entrant, GameResult, ResultsWriter, RunInfo};
+use super::{input, make_rng, print_stage, rng_seed};
+use crate::{CommonArgs, TournamentArgs};
+use indicatif::ProgressBar;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rayon::prelude::*;
+use rudle::strategy::{auto_play, StrategyKind};
+use rudle::{Hint, LetterHint, Word, WordLists};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::io::{self, BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The version of the bot protocol, sent in the `start` message
+pub const BOT_PROTOCOL_VERSION: u64 = 1;
+
+/// How one entrant did over the games of a tournament
+struct Standing {
+    name: String,
+    /// "strategy" for a built-in strategy, "bot" for a bot
+    kind: &'static str,
+    /// The first guess of every game, for a strategy that plays one
+    opener: Option<Word>,
+    /// The guesses each game took, or None if it was not solved
+    results: Vec<Option<usize>>,
+    /// The replies that were not an allowed guess, each of which lost its game
+    n_faults: usize,
+    /// Why a bot stopped playing before the last game, if it did
+    dropped_out: Option<String>,
+}
+
+impl Standing {
+    fn n_solved(&self) -> usize {
+        self.results.iter().flatten().count()
+    }
+
+    /// The average guesses over the solved games, or None if none were solved
+    fn average(&self) -> Option<f64> {
+        let n_solved = self.n_solved();
+        let total: usize = self.results.iter().flatten().sum();
+        (n_solved > 0).then(|| total as f64 / n_solved as f64)
+    }
+}
+
+/// Let every entrant play the same answers, then rank them by the games they solved and the
+/// guesses they needed
+pub fn tournament(words: WordLists, common: &CommonArgs, options: &TournamentArgs) {
+    if options.strategies.is_empty() && options.bots.is_empty() {
+        println!(
+            "No entrants. Name built-in strategies, e.g. 'entropy minimax', or bots with --bot."
+        );
+        return;
+    }
+    if words.answers.is_empty() {
+        println!("The answer list is empty.");
+        return;
+    }
+
+    let mut rng = make_rng(common);
+    let mut answers = words.answers.clone();
+    if let Some(n_games) = options.games {
+        answers.shuffle(&mut rng);
+        answers.truncate(n_games);
+    }
+    let n_entrants = options.strategies.len() + options.bots.len();
+    println!(
+        "{} entrants play the same {} answers.\n",
+        n_entrants,
+        answers.len()
+    );
+
+    // Each entrant and game is written as soon as it is played, so an interrupted tournament
+    // keeps its games
+    let run = RunInfo::new(
+        "tournament",
+        &words,
+        options.max_attempts,
+        rng_seed(common),
+        json!({ "games": options.games, "move_timeout": options.move_timeout }),
+    );
+    let file = create_results(options.output.as_deref(), &run);
+    let log = GameLog {
+        file: file.as_ref(),
+        timed: !common.deterministic,
+    };
+
+    input::stop_between_steps();
+    let show_progress = !common.deterministic;
+    let timeout = Duration::from_secs(options.move_timeout);
+    let mut standings: Vec<Standing> = vec![];
+    let mut stage = 0;
+    let mut next_stage = |name: &str| {
+        stage += 1;
+        print_stage(stage, n_entrants, &format!("{} plays", name));
+    };
+    for &kind in options.strategies.iter() {
+        if input::is_interrupted() {
+            break;
+        }
+        next_stage(kind.name());
+        let seed = rng.gen();
+        let standing = play_strategy(
+            kind,
+            seed,
+            &words,
+            &answers,
+            common,
+            options.max_attempts,
+            &log,
+        );
+        standings.push(standing);
+    }
+    for command in options.bots.iter() {
+        if input::is_interrupted() {
+            break;
+        }
+        next_stage(command);
+        let pb = progress_bar(answers.len(), show_progress);
+        let standing = play_bot(
+            command,
+            &words,
+            &answers,
+            options.max_attempts,
+            timeout,
+            &pb,
+            &log,
+        );
+        pb.finish_and_clear();
+        standings.push(standing);
+    }
+
+    let complete = !input::is_interrupted();
+    if let Some(file) = &file {
+        file.finish(complete);
+    }
+    if !complete {
+        println!("Interrupted, so the standings are over the games played before it stopped.");
+    }
+    println!();
+    print_standings(&mut standings);
+}
+
+/// Where a tournament writes its entrants and games, if anywhere
+struct GameLog<'a> {
+    file: Option<&'a ResultsWriter>,
+    /// Whether game times are written, which they are not when output must be reproducible
+    timed: bool,
+}
+
+impl GameLog<'_> {
+    fn entrant(&self, standing: &Standing) {
+        if let Some(file) = self.file {
+            file.entrant(&Entrant {
+                name: standing.name.clone(),
+                kind: standing.kind.to_string(),
+                opener: standing.opener.clone(),
+            });
+        }
+    }
+
+    /// Write a game, with the time since it started if it was played
+    fn game(&self, name: &str, answer: &Word, guesses: Option<usize>, started: Option<Instant>) {
+        if let Some(file) = self.file {
+            file.game(&GameResult {
+                entrant: name.to_string(),
+                answer: answer.clone(),
+                guesses,
+                elapsed_ms: started
+                    .filter(|_| self.timed)
+                    .map(|started| started.elapsed().as_millis() as u64),
+            });
+        }
+    }
+}
+
+/// A progress bar over the games an entrant plays, hidden when output must be reproducible
+fn progress_bar(n_games: usize, show_progress: bool) -> ProgressBar {
+    match show_progress {
+        true => ProgressBar::new(n_games as u64),
+        false => ProgressBar::hidden(),
+    }
+}
+
+/// Let a built-in strategy play every answer, as `bench` does
+/// An interrupt stops it after the games being played, and its standing is over the games
+/// it played.
+fn play_strategy(
+    kind: StrategyKind,
+    seed: u64,
+    words: &WordLists,
+    answers: &[Word],
+    common: &CommonArgs,
+    max_attempts: usize,
+    log: &GameLog,
+) -> Standing {
+    let strategy = kind.build(seed);
+    let opener = choose_opener(kind, strategy.as_ref(), words, common);
+    let mut standing = Standing {
+        name: kind.to_string(),
+        kind: "strategy",
+        opener: opener.clone(),
+        results: vec![],
+        n_faults: 0,
+        dropped_out: None,
+    };
+    log.entrant(&standing);
+    let Some(opener) = opener else {
+        // Without a guess to play, the strategy loses every game
+        for answer in answers.iter() {
+            log.game(&standing.name, answer, None, None);
+        }
+        standing.results = vec![None; answers.len()];
+        return standing;
+    };
+
+    let pb = progress_bar(answers.len(), !common.deterministic);
+    standing.results = answers
+        .par_iter()
+        .filter_map(|secret| {
+            if input::is_interrupted() {
+                return None;
+            }
+            let started = Instant::now();
+            let history = auto_play(
+                strategy.as_ref(),
+                &words.guesses,
+                &words.answers,
+                secret,
+                &opener,
+                max_attempts,
+            );
+            pb.inc(1);
+            let solved = history.last().is_some_and(|(guess, _, _)| guess == secret);
+            let guesses = solved.then_some(history.len());
+            log.game(&standing.name, secret, guesses, Some(started));
+            Some(guesses)
+        })
+        .collect();
+    pb.finish_and_clear();
+    standing
+}
+
+/// A bot running as a child process
+struct Bot {
+    child: Child,
+    stdin: ChildStdin,
+    /// The lines the bot writes, read on a thread of their own so that a reply can time out
+    lines: Receiver<String>,
+    timeout: Duration,
+}
+
+impl Bot {
+    /// Start a bot with the shell, so it can be given with its arguments
+    fn spawn(command: &str, timeout: Duration) -> io::Result<Self> {
+        #[cfg(windows)]
+        let (shell, flag) = ("cmd", "/C");
+        #[cfg(not(windows))]
+        let (shell, flag) = ("sh", "-c");
+        let mut child = Command::new(shell)
+            .args([flag, command])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .spawn()?;
+        let stdin = child.stdin.take().expect("The bot's stdin is piped");
+        let stdout = child.stdout.take().expect("The bot's stdout is piped");
+
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            stdin,
+            lines,
+            timeout,
+        })
+    }
+
+    fn send(&mut self, message: &Value) -> Result<(), String> {
+        writeln!(self.stdin, "{}", message)
+            .and_then(|()| self.stdin.flush())
+            .map_err(|e| format!("failed to write to the bot: {}", e))
+    }
+
+    /// Read the bot's next line, skipping blank ones
+    fn reply(&self) -> Result<String, String> {
+        let deadline = Instant::now() + self.timeout;
+        loop {
+            let wait = deadline.saturating_duration_since(Instant::now());
+            match self.lines.recv_timeout(wait) {
+                Ok(line) if line.trim().is_empty() => {}
+                Ok(line) => return Ok(line),
+                Err(RecvTimeoutError::Timeout) => {
+                    return Err(format!("no reply within {:?}", self.timeout))
+                }
+                Err(RecvTimeoutError::Disconnected) => return Err("the bot exited".to_string()),
+            }
+        }
+    }
+
+    /// Close the bot's input, and kill it if it has not exited within the time a reply may take
+    fn finish(mut self) {
+        drop(self.stdin);
+        let deadline = Instant::now() + self.timeout;
+        while Instant::now() < deadline {
+            if let Ok(Some(_)) = self.child.try_wait() {
+                return;
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Let a bot play every answer
+/// A bot that cannot be started, stops replying or exits loses every game it has not played.
+/// An interrupt stops it after the game being played, and its standing is over the games it
+/// played, as the interrupt may have stopped the bot too.
+fn play_bot(
+    command: &str,
+    words: &WordLists,
+    answers: &[Word],
+    max_attempts: usize,
+    timeout: Duration,
+    pb: &ProgressBar,
+    log: &GameLog,
+) -> Standing {
+    let mut standing = Standing {
+        name: command.to_string(),
+        kind: "bot",
+        opener: None,
+        results: vec![],
+        n_faults: 0,
+        dropped_out: None,
+    };
+    log.entrant(&standing);
+    let played = match Bot::spawn(command, timeout) {
+        Ok(mut bot) => {
+            let played = play_bot_games(
+                &mut bot,
+                &mut standing,
+                words,
+                answers,
+                max_attempts,
+                pb,
+                log,
+            );
+            bot.finish();
+            played
+        }
+        Err(e) => Err(format!("failed to start: {}", e)),
+    };
+    if let Err(e) = played {
+        if !input::is_interrupted() {
+            standing.dropped_out = Some(format!("{} (game {})", e, standing.results.len() + 1));
+            for answer in answers.iter().skip(standing.results.len()) {
+                log.game(&standing.name, answer, None, None);
+            }
+            standing.results.resize(answers.len(), None);
+        }
+    }
+    standing
+}
+
+fn play_bot_games(
+    bot: &mut Bot,
+    standing: &mut Standing,
+    words: &WordLists,
+    answers: &[Word],
+    max_attempts: usize,
+    pb: &ProgressBar,
+    log: &GameLog,
+) -> Result<(), String> {
+    let allowed: HashSet<&Word> = words.guesses.iter().collect();
+    let names = |words: &[Word]| -> Vec<String> { words.iter().map(Word::to_string).collect() };
+    bot.send(&json!({
+        "type": "start",
+        "version": BOT_PROTOCOL_VERSION,
+        "word_size": answers.first().map_or(0, Word::len),
+        "max_attempts": max_attempts,
+        "guesses": names(&words.guesses),
+        "answers": names(&words.answers),
+    }))?;
+
+    for (i, secret) in answers.iter().enumerate() {
+        if input::is_interrupted() {
+            break;
+        }
+        let game = i + 1;
+        let started = Instant::now();
+        let mut history: Vec<(Word, Hint)> = vec![];
+        while history.len() < max_attempts && history.last().is_none_or(|(w, _)| w != secret) {
+            let rows: Vec<Value> = history
+                .iter()
+                .map(|(guess, hint)| json!({ "guess": guess.to_string(), "hint": colors(hint) }))
+                .collect();
+            bot.send(&json!({ "type": "guess", "game": game, "history": rows }))?;
+            let guess = match parse_reply(&bot.reply()?, &allowed) {
+                Ok(guess) => guess,
+                Err(e) => {
+                    pb.println(format!(
+                        "Warning: {} lost game {}: {}",
+                        standing.name, game, e
+                    ));
+                    standing.n_faults += 1;
+                    break;
+                }
+            };
+            let hint = Hint::from_guess_and_answer(&guess, secret)
+                .expect("Allowed guesses have as many letters as the answers");
+            history.push((guess, hint));
+        }
+
+        let solved = history.last().is_some_and(|(w, _)| w == secret);
+        let guesses = solved.then_some(history.len());
+        log.game(&standing.name, secret, guesses, Some(started));
+        standing.results.push(guesses);
+        bot.send(&json!({
+            "type": "result",
+            "game": game,
+            "answer": secret.to_string(),
+            "solved": solved,
+            "guesses": history.len(),
+        }))?;
+        pb.inc(1);
+    }
+    Ok(())
+}
+
+/// Write a hint with a letter per square, as the bot protocol does: g, y or b
+fn colors(hint: &Hint) -> String {
+    hint.iter()
+        .map(|h| match h {
+            LetterHint::Correct => 'g',
+            LetterHint::Misplaced => 'y',
+            LetterHint::Incorrect => 'b',
+        })
+        .collect()
+}
+
+/// Read a bot's reply, `{"guess": "SLATE"}`, checking that the guess is allowed
+fn parse_reply(line: &str, allowed: &HashSet<&Word>) -> Result<Word, String> {
+    let reply: Value =
+        serde_json::from_str(line).map_err(|e| format!("'{}' is not JSON: {}", line, e))?;
+    let guess = reply["guess"]
+        .as_str()
+        .ok_or_else(|| format!("'{}' has no \"guess\" string", line))?;
+    let word = Word::from_string(guess).map_err(|e| format!("'{}': {}", guess, e))?;
+    match allowed.contains(&word) {
+        true => Ok(word),
+        false => Err(format!("{} is not an allowed guess", word)),
+    }
+}
+
+/// Print the entrants ranked by the games they solved, then by their average guesses
+fn print_standings(standings: &mut [Standing]) {
+    standings.sort_by(|a, b| {
+        let average = |s: &Standing| s.average().unwrap_or(f64::INFINITY);
+        b.n_solved()
+            .cmp(&a.n_solved())
+            .then(average(a).total_cmp(&average(b)))
+    });
+    let rows: Vec<Vec<String>> = standings
+        .iter()
+        .enumerate()
+        .map(|(i, standing)| {
+            vec![
+                (i + 1).to_string(),
+                standing.name.clone(),
+                standing.n_solved().to_string(),
+                standing
+                    .average()
+                    .map_or("-".to_string(), |average| format!("{:.3}", average)),
+                (standing.results.len() - standing.n_solved()).to_string(),
+                standing.n_faults.to_string(),
+            ]
+        })
+        .collect();
+    print_table(
+        &["Rank", "Entrant", "Solved", "Average", "Failed", "Faults"],
+        &rows,
+    );
+
+    for standing in standings.iter() {
+        if let Some(reason) = &standing.dropped_out {
+            println!("{} dropped out: {}.", standing.name, reason);
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn words() -> WordLists {
+        let words = ["CRANE", "SLATE", "TRACE"].map(|w| Word::from_string(w).unwrap());
+        WordLists::single(words.to_vec())
+    }
+
+    fn play(command: &str) -> Standing {
+        let words = words();
+        let timeout = Duration::from_millis(500);
+        play_bot(
+            command,
+            &words,
+            &words.answers,
+            3,
+            timeout,
+            &ProgressBar::hidden(),
+            &GameLog {
+                file: None,
+                timed: false,
+            },
+        )
+    }
+
+    #[test]
+    fn bots_play_every_answer() {
+        // Guess CRANE, then SLATE, then TRACE, whatever the hints say
+        let bot = r#"n=0; while read -r line; do case "$line" in
+            *'"type":"result"'*) n=0 ;;
+            *'"type":"guess"'*) n=$((n+1)); case $n in
+                1) echo '{"guess":"crane"}' ;; 2) echo '{"guess":"slate"}' ;; *) echo '{"guess":"trace"}' ;;
+            esac ;;
+        esac; done"#;
+        let standing = play(bot);
+        assert_eq!(standing.results, [Some(1), Some(2), Some(3)]);
+        assert_eq!(standing.n_faults, 0);
+        assert_eq!(standing.dropped_out, None);
+    }
+
+    #[test]
+    fn replies_that_are_not_allowed_guesses_lose_their_game() {
+        let bot = r#"while read -r line; do case "$line" in
+            *'"type":"guess"'*) echo '{"guess":"zzzzz"}' ;;
+        esac; done"#;
+        let standing = play(bot);
+        assert_eq!(standing.results, [None, None, None]);
+        assert_eq!(standing.n_faults, 3);
+        assert_eq!(standing.dropped_out, None);
+    }
+
+    #[test]
+    fn bots_that_stop_replying_drop_out() {
+        let standing = play("cat > /dev/null");
+        assert_eq!(standing.results, [None, None, None]);
+        assert!(standing.dropped_out.unwrap().starts_with("no reply"));
+
+        let standing = play("exit 0");
+        assert_eq!(standing.results, [None, None, None]);
+        assert!(standing.dropped_out.is_some());
+    }
+
+    #[test]
+    fn hints_are_sent_as_colors() {
+        let guess = Word::from_string("trace").unwrap();
+        let answer = Word::from_string("crane").unwrap();
+        let hint = Hint::from_guess_and_answer(&guess, &answer).unwrap();
+        assert_eq!(colors(&hint), "bggyg");
+    }
+}
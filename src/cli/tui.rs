@@ -0,0 +1,298 @@
+//! A full-screen game with a board grid, an on-screen keyboard and a solver panel
+
+use super::input;
+use super::play::{
+    assist_suggestions, finish_game, keyboard_lines, parse_guess, ASSIST_SUGGESTIONS,
+};
+use super::print_hint;
+use super::reload::{forget_scores, ListWatcher, Reload};
+use super::render::{symbol_cell, theme, visible_width};
+use super::stats::GameEnd;
+use crate::{CommonArgs, PlayArgs};
+use colored::*;
+use console::{Key, Term};
+use rudle::hint::keyboard_state;
+use rudle::picker::AnswerPicker;
+use rudle::rules::Ruleset;
+use rudle::{Hint, LetterHint, Word, WordLists};
+use std::time::{Duration, Instant};
+
+/// The columns between the board and the solver panel
+const PANEL_GAP: usize = 4;
+
+/// What the solver panel shows for the answers left: their number and the best guesses
+struct Panel {
+    /// The number of guesses the panel was calculated after
+    n_guesses: usize,
+    lines: Vec<String>,
+}
+
+/// Play a game in the whole terminal, redrawing it after every key
+///
+/// Letters are colored by what the earlier hints say about them while they are typed, and Tab
+/// shows the solver's panel, which is open from the start with --assist. The word lists are
+/// reloaded when their files change, checked before each key. The game is printed the usual
+/// way once it ends, so it stays in the scrollback.
+pub fn play_tui(
+    words: &WordLists,
+    picker: &mut dyn AnswerPicker,
+    common: &CommonArgs,
+    options: &PlayArgs,
+) -> (Word, Option<usize>) {
+    let mut secret_word = picker.pick(&words.answers).expect("Word list is empty");
+    let term = Term::stdout();
+
+    let started = Instant::now();
+    let mut guess_times: Vec<Duration> = vec![];
+    let mut board: Vec<(Word, Hint)> = vec![];
+    let mut remaining_answers = words.answers.clone();
+    let mut meter_history: Vec<(Word, Vec<Word>)> = vec![];
+    let mut typed = String::new();
+    let mut message = String::new();
+    let mut show_panel = options.assist;
+    let mut panel: Option<Panel> = None;
+    let mut end = GameEnd::Quit;
+    // The lists reloaded after their files changed during the game
+    let mut watcher = ListWatcher::new(common);
+    let mut reloaded: Option<WordLists> = None;
+
+    let _ = term.hide_cursor();
+    let _ = term.clear_screen();
+    loop {
+        match watcher.poll(reloaded.as_ref().unwrap_or(words)) {
+            Reload::Unchanged => {}
+            // The game keeps its secret word, so the new lists must still have it
+            Reload::Reloaded(new, _) if new.answers.binary_search(&secret_word).is_err() => {
+                message = "Kept the old word lists, which the secret word is not in.".to_string();
+            }
+            Reload::Reloaded(new, summary) => {
+                remaining_answers = new.answers.clone();
+                for (guess, hint) in board.iter() {
+                    remaining_answers
+                        .retain(|w| Hint::from_guess_and_answer(guess, w).as_ref() == Ok(hint));
+                }
+                forget_scores(reloaded.as_ref().unwrap_or(words));
+                reloaded = Some(new);
+                panel = None;
+                message = summary;
+            }
+            Reload::Failed(e) => message = format!("Kept the old word lists: {}.", e),
+        }
+        let words = reloaded.as_ref().unwrap_or(words);
+        if board.last().is_some_and(|(guess, _)| guess == &secret_word) {
+            end = GameEnd::Solved;
+            break;
+        }
+        if board.len() == options.max_attempts {
+            end = GameEnd::OutOfAttempts;
+            break;
+        }
+        if show_panel && panel.as_ref().is_none_or(|p| p.n_guesses != board.len()) {
+            draw(&term, &board, &typed, "Thinking...", None, common, options);
+            panel = Some(Panel {
+                n_guesses: board.len(),
+                lines: panel_lines(&words.guesses, &remaining_answers, options.mode, &board),
+            });
+        }
+        let lines = panel.as_ref().filter(|_| show_panel).map(|p| &p.lines[..]);
+        draw(&term, &board, &typed, &message, lines, common, options);
+
+        message.clear();
+        let Ok(key) = term.read_key() else {
+            break;
+        };
+        match key {
+            Key::Char(c) if c.is_alphabetic() && typed.chars().count() < common.word_size => {
+                typed.extend(c.to_uppercase());
+            }
+            Key::Backspace => {
+                typed.pop();
+            }
+            Key::Tab => show_panel = !show_panel,
+            Key::Escape => {
+                end = GameEnd::GaveUp;
+                break;
+            }
+            // In raw mode Ctrl-C is a key, which stops the program like the signal would
+            Key::CtrlC => {
+                input::interrupt();
+                break;
+            }
+            Key::Enter => {
+                let guess = match parse_guess(&typed, words, common.word_size) {
+                    Ok(guess) => guess,
+                    Err(e) => {
+                        message = e;
+                        continue;
+                    }
+                };
+                if let Err(e) = options.mode.check(&guess, &board) {
+                    message = format!("Not allowed in {} mode: {}.", options.mode, e);
+                    continue;
+                }
+                if options.meter == "post" || options.post_mortem {
+                    meter_history.push((guess.clone(), remaining_answers.clone()));
+                }
+                guess_times.push(started.elapsed());
+                secret_word = picker.respond(&secret_word, &guess, &remaining_answers);
+                let hint = Hint::from_guess_and_answer(&guess, &secret_word).unwrap();
+                remaining_answers
+                    .retain(|w| Hint::from_guess_and_answer(&guess, w).as_ref() == Ok(&hint));
+                board.push((guess, hint));
+                typed.clear();
+            }
+            _ => {}
+        }
+    }
+    let _ = term.clear_screen();
+    let _ = term.show_cursor();
+    let words = reloaded.as_ref().unwrap_or(words);
+
+    for (guess, hint) in board.iter() {
+        print_hint(hint, guess);
+        println!();
+    }
+    if end == GameEnd::Solved {
+        println!("{}", "Congratulations! You guessed the word!".green());
+    }
+    if options.obs_output.is_some() || options.host.is_some() || options.meter == "live" {
+        println!("Warning: --obs-output, --host and --meter live are ignored with --tui.");
+    }
+    let guesses_used = finish_game(
+        words,
+        common,
+        options,
+        &secret_word,
+        &board,
+        &guess_times,
+        &meter_history,
+        &[],
+        end,
+    );
+    (secret_word, guesses_used)
+}
+
+/// Redraw the whole screen: the board with the word being typed, the keyboard and the status
+/// bar, with the solver panel to the right of the board if it is open
+fn draw(
+    term: &Term,
+    board: &[(Word, Hint)],
+    typed: &str,
+    message: &str,
+    panel: Option<&[String]>,
+    common: &CommonArgs,
+    options: &PlayArgs,
+) {
+    let mut left = vec![format!("{}", "R U D L E".bold()), String::new()];
+    let known = keyboard_state(board);
+    for row in 0..options.max_attempts {
+        let cells: Vec<String> = match board.get(row) {
+            Some((guess, hint)) => guess
+                .iter()
+                .zip(hint.iter())
+                .map(|(c, &h)| cell(c, h))
+                .collect(),
+            None if row == board.len() => (0..common.word_size)
+                .map(|i| match typed.chars().nth(i) {
+                    Some(c) => typed_cell(c, i, board, known.get(&c).copied()),
+                    None => " _ ".to_string(),
+                })
+                .collect(),
+            None => vec![" · ".to_string(); common.word_size],
+        };
+        left.push(cells.join(""));
+    }
+    left.push(String::new());
+    left.extend(keyboard_lines(board));
+
+    let width = left.iter().map(|l| visible_width(l)).max().unwrap_or(0) + PANEL_GAP;
+    let panel = panel.unwrap_or(&[]);
+    let mut lines: Vec<String> = (0..left.len().max(panel.len()))
+        .map(|i| {
+            let l = left.get(i).map_or("", String::as_str);
+            match panel.get(i) {
+                Some(p) => format!("{}{}{}", l, " ".repeat(width - visible_width(l)), p),
+                None => l.to_string(),
+            }
+        })
+        .collect();
+
+    lines.push(String::new());
+    let attempt = (board.len() + 1).min(options.max_attempts);
+    lines.push(
+        format!(
+            "Attempt {}/{}  Enter: guess  Backspace: delete  Tab: solver  Esc: give up",
+            attempt, options.max_attempts
+        )
+        .reversed()
+        .to_string(),
+    );
+    lines.push(message.to_string());
+
+    let _ = term.move_cursor_to(0, 0);
+    for line in lines {
+        let _ = term.clear_line();
+        let _ = term.write_line(&line);
+    }
+    let _ = term.clear_to_end_of_screen();
+    let _ = term.flush();
+}
+
+/// A letter of a guessed word, on the color of its hint, or marked with symbols without colors
+fn cell(letter: char, hint: LetterHint) -> String {
+    match colored::control::SHOULD_COLORIZE.should_colorize() {
+        true => theme().fill(&format!(" {} ", letter), hint).to_string(),
+        false => symbol_cell(letter, hint),
+    }
+}
+
+/// A letter being typed, colored by what the hints so far say about it: in the correct color
+/// if it is known to be in this position, the misplaced color if it is known to be in the word,
+/// and red if it is not
+fn typed_cell(
+    letter: char,
+    position: usize,
+    board: &[(Word, Hint)],
+    known: Option<LetterHint>,
+) -> String {
+    let in_place = board.iter().any(|(guess, hint)| {
+        guess.iter().nth(position) == Some(letter)
+            && hint.iter().nth(position) == Some(&LetterHint::Correct)
+    });
+    let text = format!(" {} ", letter);
+    match known {
+        _ if in_place => theme().paint(&text, LetterHint::Correct).bold(),
+        Some(LetterHint::Incorrect) => text.red(),
+        Some(_) => theme().paint(&text, LetterHint::Misplaced).bold(),
+        None => text.bold(),
+    }
+    .to_string()
+}
+
+/// The solver panel: how many answers are left, and the answers themselves or the best guesses
+fn panel_lines(
+    guesses: &[Word],
+    answers: &[Word],
+    rules: Ruleset,
+    board: &[(Word, Hint)],
+) -> Vec<String> {
+    let mut lines = vec![
+        format!("{}", "Solver".bold()),
+        String::new(),
+        format!("{} possible answers", answers.len()),
+        String::new(),
+    ];
+    if answers.len() <= ASSIST_SUGGESTIONS {
+        lines.push("They are:".to_string());
+        lines.extend(answers.iter().map(|w| format!("  {}", w)));
+        return lines;
+    }
+    lines.push("Best guesses:".to_string());
+    for (i, (word, score)) in assist_suggestions(guesses, answers, rules, board, false)
+        .iter()
+        .enumerate()
+    {
+        lines.push(format!("  {}. {} {:.2}%", i + 1, word, score));
+    }
+    lines
+}
@@ -0,0 +1,173 @@
+//! Checking whether a newer release of Rudle is out, and what changed in it
+//!
+//! Nothing is sent anywhere unless `update --check` is run. The release feed is fetched with
+//! curl, which every platform Rudle runs on ships, rather than an HTTP client built into the
+//! binary; curl also reads `file://` feeds, which is how the check is tried out offline.
+
+use serde_json::Value;
+use std::cmp::Reverse;
+use std::process::{self, Command};
+
+/// The releases of Rudle, newest first, as the GitHub API lists them
+const RELEASE_FEED: &str = "https://api.github.com/repos/ianpotpie/Rudle/releases";
+
+/// The longest the feed may take to download
+const TIMEOUT_SECONDS: &str = "10";
+
+/// A published release from the feed
+#[derive(Debug, PartialEq)]
+struct Release {
+    version: (u64, u64, u64),
+    /// The day it was published, as YYYY-MM-DD
+    date: String,
+    url: String,
+    notes: String,
+}
+
+/// Read a version such as "0.2.1" or "v0.2.1", which pre-releases like "0.3.0-rc1" are not
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let mut parts = version.split('.').map(|part| part.parse().ok());
+    let parsed = (parts.next()??, parts.next()??, parts.next()??);
+    parts.next().is_none().then_some(parsed)
+}
+
+/// The published releases of a feed, skipping drafts, pre-releases and tags that are not
+/// versions
+fn parse_feed(text: &str) -> Result<Vec<Release>, String> {
+    let feed: Value =
+        serde_json::from_str(text).map_err(|e| format!("not a release feed: {}", e))?;
+    let releases = feed
+        .as_array()
+        .ok_or("not a release feed: expected a list of releases")?;
+    Ok(releases
+        .iter()
+        .filter(|release| release["draft"] != true && release["prerelease"] != true)
+        .filter_map(|release| {
+            Some(Release {
+                version: parse_version(release["tag_name"].as_str()?)?,
+                date: release["published_at"]
+                    .as_str()
+                    .unwrap_or("")
+                    .chars()
+                    .take(10)
+                    .collect(),
+                url: release["html_url"].as_str().unwrap_or("").to_string(),
+                notes: release["body"]
+                    .as_str()
+                    .unwrap_or("")
+                    .trim()
+                    .replace("\r\n", "\n"),
+            })
+        })
+        .collect())
+}
+
+/// Download a feed with curl
+fn fetch(url: &str) -> Result<String, String> {
+    let output = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location"])
+        .args(["--max-time", TIMEOUT_SECONDS])
+        .args(["--header", "Accept: application/vnd.github+json"])
+        .arg(url)
+        .output()
+        .map_err(|e| format!("failed to run curl: {}", e))?;
+    match output.status.success() {
+        true => String::from_utf8(output.stdout).map_err(|e| e.to_string()),
+        false => Err(format!(
+            "failed to fetch {}: {}",
+            url,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+    }
+}
+
+/// Write a version as "0.2.1"
+fn version_name((major, minor, patch): (u64, u64, u64)) -> String {
+    format!("{}.{}.{}", major, minor, patch)
+}
+
+/// Report whether the feed has a release newer than this build, with the notes of every
+/// release since
+pub fn check(feed: Option<&str>) {
+    let current = parse_version(env!("CARGO_PKG_VERSION")).expect("The crate has a version");
+    let url = feed.unwrap_or(RELEASE_FEED);
+    let mut newer = match fetch(url).and_then(|text| parse_feed(&text)) {
+        Ok(releases) => releases,
+        Err(e) => {
+            println!("Error: {}", e);
+            process::exit(1);
+        }
+    };
+    newer.retain(|release| release.version > current);
+    newer.sort_by_key(|release| Reverse(release.version));
+    let Some(latest) = newer.first() else {
+        println!("Rudle {} is up to date.", version_name(current));
+        return;
+    };
+
+    println!(
+        "Rudle {} is out (this is {}). Released {}: {}",
+        version_name(latest.version),
+        version_name(current),
+        latest.date,
+        latest.url
+    );
+    for release in newer.iter() {
+        println!("\n## {} ({})", version_name(release.version), release.date);
+        match release.notes.is_empty() {
+            true => println!("No release notes."),
+            false => println!("{}", release.notes),
+        }
+    }
+    // Saved data is upgraded by the new version, never by this one, so updating is safe
+    let mentions_saved_data = newer.iter().any(|release| {
+        let notes = release.notes.to_lowercase();
+        ["cache", "format", "saved"]
+            .iter()
+            .any(|word| notes.contains(word))
+    });
+    if mentions_saved_data {
+        println!(
+            "\nFiles saved by {} are upgraded when the new version first loads them.",
+            version_name(current)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versions_are_read_with_or_without_a_v() {
+        assert_eq!(parse_version("0.2.1"), Some((0, 2, 1)));
+        assert_eq!(parse_version("v1.10.0"), Some((1, 10, 0)));
+        assert_eq!(parse_version("0.3.0-rc1"), None);
+        assert_eq!(parse_version("0.3"), None);
+        assert_eq!(parse_version("0.3.0.1"), None);
+    }
+
+    #[test]
+    fn the_feed_keeps_published_releases_only() {
+        let feed = r#"[
+            {"tag_name": "v0.3.0", "draft": true, "prerelease": false, "body": "Draft"},
+            {"tag_name": "v0.3.0-rc1", "draft": false, "prerelease": true, "body": "RC"},
+            {"tag_name": "v0.2.0", "draft": false, "prerelease": false,
+             "published_at": "2026-09-01T12:00:00Z",
+             "html_url": "https://github.com/ianpotpie/Rudle/releases/tag/v0.2.0",
+             "body": "New cache format\r\nFaster solver\r\n"},
+            {"tag_name": "nightly", "draft": false, "prerelease": false}
+        ]"#;
+        assert_eq!(
+            parse_feed(feed).unwrap(),
+            vec![Release {
+                version: (0, 2, 0),
+                date: "2026-09-01".to_string(),
+                url: "https://github.com/ianpotpie/Rudle/releases/tag/v0.2.0".to_string(),
+                notes: "New cache format\nFaster solver".to_string(),
+            }]
+        );
+        assert!(parse_feed("{}").is_err());
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt;
+
+/// Errors produced when parsing words and hints
+///
+/// Every parsing entry point (`Word::new`, `Word::from_string`, `Hint::from_string`,
+/// `Hint::from_guess_and_answer` and `parse_share_row`) reports failures through this type
+/// instead of panicking, so they are safe to call on arbitrary input.
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub enum ParseError {
+    /// The input contained a character that is not alphabetic
+    NonAlphabetic(char),
+    /// The input contained a letter that is not an uppercase ASCII letter
+    NotUppercase(char),
+    /// Two inputs that must line up letter-for-letter have different lengths
+    LengthMismatch { expected: usize, found: usize },
+    /// A hint character is neither '*', '_', a color ('g', 'y' or 'b'), nor the guessed
+    /// letter at that position
+    InvalidHintChar { position: usize, found: char },
+    /// A row of a share grid has a colored square or circle that is not a feedback square
+    InvalidSquare { position: usize, found: char },
+    /// A row of a share grid does not have one square per letter; a line with none at all
+    /// is not a row
+    RowLength { expected: usize, found: usize },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::NonAlphabetic(c) => {
+                write!(f, "'{}' is not an alphabetic character", c)
+            }
+            ParseError::NotUppercase(c) => {
+                write!(f, "'{}' is not an uppercase ASCII letter", c)
+            }
+            ParseError::LengthMismatch { expected, found } => {
+                write!(f, "expected {} letters but found {}", expected, found)
+            }
+            ParseError::InvalidHintChar { position, found } => write!(
+                f,
+                "invalid hint character '{}' at position {} (expected the guessed letter, \
+                 '*', '_', or one of g, y and b)",
+                found,
+                position + 1
+            ),
+            ParseError::InvalidSquare { position, found } => write!(
+                f,
+                "'{}' at square {} is not a feedback square",
+                found,
+                position + 1
+            ),
+            ParseError::RowLength { expected, found } => {
+                write!(
+                    f,
+                    "expected a row of {} squares but found {}",
+                    expected, found
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Reasons a guess is not allowed under a ruleset, produced by `Ruleset::check`
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
+pub enum RuleViolation {
+    /// A letter that was correct in an earlier guess is not reused in the same position
+    MissingCorrect { position: usize, letter: char },
+    /// A letter revealed by an earlier guess is used fewer times than it was revealed
+    MissingMisplaced { letter: char, count: usize },
+    /// The guess could not be the answer given the hint for an earlier guess
+    Inconsistent { guess: String },
+}
+
+impl fmt::Display for RuleViolation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleViolation::MissingCorrect { position, letter } => {
+                write!(f, "letter {} must be {}", position + 1, letter)
+            }
+            RuleViolation::MissingMisplaced { letter, count: 1 } => {
+                write!(f, "the guess must contain {}", letter)
+            }
+            RuleViolation::MissingMisplaced { letter, count } => {
+                write!(f, "the guess must contain {} {}s", count, letter)
+            }
+            RuleViolation::Inconsistent { guess } => {
+                write!(
+                    f,
+                    "the guess cannot be the answer given the hint for {}",
+                    guess
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for RuleViolation {}
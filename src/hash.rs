@@ -0,0 +1,22 @@
+//! A hash that is stable between builds and platforms, for anything derived from word lists
+//! that has to come out the same everywhere
+
+use crate::word::Word;
+
+/// The hash of no bytes at all, which `fnv1a` starts from
+pub const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+/// Feed bytes into an FNV-1a hash, which unlike the std hasher is stable between builds
+pub fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().fold(hash, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// FNV-1a over a word list written one word per line, which identifies the list in caches,
+/// results files and experiment manifests
+pub fn word_list_hash(words: &[Word]) -> u64 {
+    words.iter().fold(FNV_OFFSET, |hash, word| {
+        fnv1a(fnv1a(hash, word.as_bytes()), b"\n")
+    })
+}
@@ -0,0 +1,553 @@
+use crate::error::ParseError;
+use crate::profile::{profiled, Phase};
+use crate::word::{Word, INLINE_LEN, MAX_WORD_SIZE};
+use smallvec::SmallVec;
+use std::collections::HashMap;
+use std::iter::zip;
+use std::str::FromStr;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A hint for a given letter
+#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
+pub enum LetterHint {
+    /// The letter is in the word and in the correct position
+    Correct,
+    /// The letter is in the word but in the wrong position
+    Misplaced,
+    /// The letter is not in the word
+    Incorrect,
+}
+
+impl LetterHint {
+    pub const ALL: [LetterHint; 3] = [
+        LetterHint::Correct,
+        LetterHint::Misplaced,
+        LetterHint::Incorrect,
+    ];
+}
+
+/// The feedback for a whole guess, one `LetterHint` per letter
+#[derive(Hash, Eq, PartialEq, Clone, Debug)]
+pub struct Hint {
+    letter_hints: Vec<LetterHint>,
+}
+
+impl Hint {
+    pub fn new(letter_hints: Vec<LetterHint>) -> Self {
+        Self { letter_hints }
+    }
+
+    /// Parse a hint typed by the user for the given guess
+    ///
+    /// Each character of `hint` is either the guessed letter (correct), '*' (misplaced)
+    /// or '_' (incorrect). Hints in the color format, such as "gybbg", are also accepted:
+    /// 'g' (green), 'y' (yellow) and 'b' (black or grey), in any case, mixed freely with
+    /// the characters above. A hint that is valid in the first format is read that way, so
+    /// "b____" for BEGIN is a correct B, and "bybbg" is read by colors.
+    pub fn from_string(hint: &str, guess: &Word) -> Result<Self, ParseError> {
+        let hint_len = hint.chars().count();
+        if hint_len != guess.len() {
+            return Err(ParseError::LengthMismatch {
+                expected: guess.len(),
+                found: hint_len,
+            });
+        }
+
+        let parse = |by_color: bool| -> Result<Vec<LetterHint>, ParseError> {
+            zip(hint.chars(), guess.iter())
+                .enumerate()
+                .map(|(position, (c, w))| match c.to_ascii_lowercase() {
+                    'g' if by_color => Ok(LetterHint::Correct),
+                    'y' if by_color => Ok(LetterHint::Misplaced),
+                    'b' if by_color => Ok(LetterHint::Incorrect),
+                    _ if c.to_ascii_uppercase() == w => Ok(LetterHint::Correct),
+                    '*' => Ok(LetterHint::Misplaced),
+                    '_' => Ok(LetterHint::Incorrect),
+                    _ => Err(ParseError::InvalidHintChar { position, found: c }),
+                })
+                .collect()
+        };
+        // If neither format fits, report the character where the reading got furthest
+        let letter_hints = parse(false).or_else(|letters_error| {
+            parse(true).map_err(|colors_error| match (&letters_error, &colors_error) {
+                (
+                    ParseError::InvalidHintChar { position: a, .. },
+                    ParseError::InvalidHintChar { position: b, .. },
+                ) if b > a => colors_error,
+                _ => letters_error,
+            })
+        })?;
+
+        Ok(Self::new(letter_hints))
+    }
+
+    /// Write the hint in the format `from_string` parses, such as "h*ll_" for HELLO
+    pub fn to_pattern(&self, guess: &Word) -> String {
+        zip(self.letter_hints.iter(), guess.iter())
+            .map(|(h, c)| match h {
+                LetterHint::Correct => c.to_ascii_lowercase(),
+                LetterHint::Misplaced => '*',
+                LetterHint::Incorrect => '_',
+            })
+            .collect()
+    }
+
+    /// Compute the hint that the game would give for a guess against an answer
+    pub fn from_guess_and_answer(guess: &Word, answer: &Word) -> Result<Self, ParseError> {
+        profiled(Phase::HintComputation, || Self::compute(guess, answer))
+    }
+
+    fn compute(guess: &Word, answer: &Word) -> Result<Self, ParseError> {
+        if guess.len() != answer.len() {
+            return Err(ParseError::LengthMismatch {
+                expected: answer.len(),
+                found: guess.len(),
+            });
+        };
+        if let Some(c) = guess
+            .iter()
+            .chain(answer.iter())
+            .find(|c| !c.is_alphabetic())
+        {
+            return Err(ParseError::NonAlphabetic(c));
+        }
+        let mut letter_hints: Vec<LetterHint> = vec![LetterHint::Incorrect; guess.len()];
+        // Work on the ASCII bytes directly, copying the answer onto the stack
+        let guess_bytes = guess.as_bytes();
+        let mut answer_bytes: SmallVec<[u8; INLINE_LEN]> = SmallVec::from_slice(answer.as_bytes());
+
+        // First pass: Check for correct letters (LetterHint::Correct)
+        for (i, (g, a)) in zip(guess_bytes, answer.as_bytes()).enumerate() {
+            if g == a {
+                letter_hints[i] = LetterHint::Correct;
+                answer_bytes[i] = b'_'; // Mark this character as used
+            }
+        }
+
+        // Second pass: Check for misplaced letters (LetterHint::Misplaced)
+        for (i, g) in guess_bytes.iter().enumerate() {
+            if letter_hints[i] == LetterHint::Correct {
+                continue; // Skip already correct letters
+            }
+
+            if let Some(pos) = answer_bytes.iter().position(|a| a == g) {
+                letter_hints[i] = LetterHint::Misplaced;
+                answer_bytes[pos] = b'_'; // Mark this character as used
+            }
+        }
+
+        Ok(Self { letter_hints })
+    }
+
+    /// Pack the hint into a base-3 number with one digit per letter, first letter first
+    ///
+    /// Incorrect, misplaced and correct letters are the digits 0, 1 and 2, so hints of the
+    /// same length get distinct codes below `3^len`. The code is cheap to hash and compare,
+    /// which makes it a better map key than the hint itself when scoring.
+    ///
+    /// Codes only fit in a `u32` for hints of at most [`MAX_WORD_SIZE`] letters, which is why
+    /// word lists of longer words are not loaded.
+    pub fn to_code(&self) -> u32 {
+        debug_assert!(self.letter_hints.len() <= MAX_WORD_SIZE);
+        self.letter_hints.iter().fold(0, |code, h| {
+            let digit = match h {
+                LetterHint::Incorrect => 0,
+                LetterHint::Misplaced => 1,
+                LetterHint::Correct => 2,
+            };
+            code * 3 + digit
+        })
+    }
+
+    /// Unpack a hint of `len` letters from a code made by `to_code`
+    pub fn from_code(code: u32, len: usize) -> Self {
+        let mut letter_hints = vec![LetterHint::Incorrect; len];
+        let mut code = code;
+        for letter_hint in letter_hints.iter_mut().rev() {
+            *letter_hint = match code % 3 {
+                0 => LetterHint::Incorrect,
+                1 => LetterHint::Misplaced,
+                _ => LetterHint::Correct,
+            };
+            code /= 3;
+        }
+        Self { letter_hints }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, LetterHint> {
+        self.letter_hints.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.letter_hints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letter_hints.is_empty()
+    }
+}
+
+impl Iterator for Hint {
+    type Item = LetterHint;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.letter_hints.first().copied()
+    }
+}
+
+/// The best feedback seen so far for each guessed letter, as shown on a game's keyboard
+///
+/// A letter that was correct anywhere counts as correct, otherwise one that was misplaced
+/// anywhere counts as misplaced.
+pub fn keyboard_state(history: &[(Word, Hint)]) -> HashMap<char, LetterHint> {
+    let rank = |h: LetterHint| match h {
+        LetterHint::Correct => 2,
+        LetterHint::Misplaced => 1,
+        LetterHint::Incorrect => 0,
+    };
+
+    let mut letters: HashMap<char, LetterHint> = HashMap::new();
+    for (guess, hint) in history.iter() {
+        for (c, &h) in zip(guess.iter(), hint.iter()) {
+            let best = letters.entry(c).or_insert(h);
+            if rank(h) > rank(*best) {
+                *best = h;
+            }
+        }
+    }
+    letters
+}
+
+/// The symbols that represent each kind of letter feedback in shared results
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct FeedbackSymbols {
+    pub correct: String,
+    pub misplaced: String,
+    pub incorrect: String,
+}
+
+impl FeedbackSymbols {
+    /// The symbol for a letter's feedback
+    pub fn symbol(&self, letter_hint: LetterHint) -> &str {
+        match letter_hint {
+            LetterHint::Correct => &self.correct,
+            LetterHint::Misplaced => &self.misplaced,
+            LetterHint::Incorrect => &self.incorrect,
+        }
+    }
+}
+
+impl Default for FeedbackSymbols {
+    /// The green, yellow, and black squares used by Wordle
+    fn default() -> Self {
+        Self {
+            correct: "🟩".to_string(),
+            misplaced: "🟨".to_string(),
+            incorrect: "⬛".to_string(),
+        }
+    }
+}
+
+impl FromStr for FeedbackSymbols {
+    type Err = String;
+
+    /// Parse a preset name ("default" or "colorblind") or exactly three symbols given in the
+    /// order correct, misplaced, incorrect, e.g. "🟦🟧⬛" or "+?.".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "default" => return Ok(Self::default()),
+            "colorblind" => {
+                return Ok(Self {
+                    correct: "🟧".to_string(),
+                    misplaced: "🟦".to_string(),
+                    incorrect: "⬛".to_string(),
+                })
+            }
+            _ => {}
+        }
+
+        let symbols: Vec<&str> = s.graphemes(true).collect();
+        match symbols[..] {
+            [correct, misplaced, incorrect] => Ok(Self {
+                correct: correct.to_string(),
+                misplaced: misplaced.to_string(),
+                incorrect: incorrect.to_string(),
+            }),
+            _ => Err(format!(
+                "expected 'default', 'colorblind', or three symbols but found {} symbols",
+                symbols.len()
+            )),
+        }
+    }
+}
+
+/// The row of a share grid for an attempt spent revealing a letter instead of guessing
+pub const REVEAL_ROW: &str = "🔍";
+
+/// Render guesses as the emoji grid used to share Wordle results
+///
+/// The header is the title followed by the score, e.g. "Rudle 4/6", or "Rudle X/6" if no guess
+/// was fully correct. `reveals` holds, for each attempt spent revealing a letter, the number of
+/// guesses made before it; each is a row of [`REVEAL_ROW`] and counts towards the score.
+pub fn share_grid(
+    title: &str,
+    history: &[(Word, Hint)],
+    reveals: &[usize],
+    max_attempts: usize,
+    symbols: &FeedbackSymbols,
+) -> String {
+    let solved = history
+        .last()
+        .is_some_and(|(_, hint)| hint.iter().all(|&h| h == LetterHint::Correct));
+    let score = if solved {
+        (history.len() + reveals.len()).to_string()
+    } else {
+        "X".to_string()
+    };
+
+    let mut grid = format!("{} {}/{}\n", title, score, max_attempts);
+    for n_guessed in 0..=history.len() {
+        for _ in reveals.iter().filter(|&&n| n == n_guessed) {
+            grid.push('\n');
+            grid.push_str(REVEAL_ROW);
+        }
+        if let Some((_, hint)) = history.get(n_guessed) {
+            grid.push('\n');
+            grid.extend(hint.iter().map(|&h| symbols.symbol(h)));
+        }
+    }
+    grid
+}
+
+/// Read the hint of one square of a share grid, in the default or the colorblind colors
+fn square_hint(square: char) -> Option<LetterHint> {
+    match square {
+        '🟩' | '🟧' => Some(LetterHint::Correct),
+        '🟨' | '🟦' => Some(LetterHint::Misplaced),
+        '⬛' | '⬜' => Some(LetterHint::Incorrect),
+        _ => None,
+    }
+}
+
+/// Read one row of a share grid such as "⬛🟨⬛⬛🟩", with its guess written before or after
+/// the squares if the row names it
+/// # Returns
+/// The guess, if named, and the hint
+/// # Errors
+/// `RowLength` if the row does not have `word_size` squares (with `found: 0` for a line that
+/// is not a row at all), or `InvalidSquare` for a colored square or circle such as 🟥 that
+/// is not one of the feedback squares
+pub fn parse_share_row(line: &str, word_size: usize) -> Result<(Option<Word>, Hint), ParseError> {
+    let mut letter_hints: Vec<LetterHint> = vec![];
+    for c in line.chars() {
+        match square_hint(c) {
+            Some(letter_hint) => letter_hints.push(letter_hint),
+            // The colored circles and squares of Unicode's Geometric Shapes Extended block
+            None if ('\u{1F7E0}'..='\u{1F7EB}').contains(&c) => {
+                return Err(ParseError::InvalidSquare {
+                    position: letter_hints.len(),
+                    found: c,
+                })
+            }
+            None => {}
+        }
+    }
+    if letter_hints.len() != word_size {
+        return Err(ParseError::RowLength {
+            expected: word_size,
+            found: letter_hints.len(),
+        });
+    }
+    let guess = line
+        .split(|c: char| !c.is_ascii_alphabetic())
+        .find(|token| token.len() == word_size)
+        .and_then(|token| Word::from_string(token).ok());
+    Ok((guess, Hint::new(letter_hints)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    fn row(guess: &str, answer: &str) -> (Word, Hint) {
+        let guess = Word::from_string(guess).unwrap();
+        let answer = Word::from_string(answer).unwrap();
+        let hint = Hint::from_guess_and_answer(&guess, &answer).unwrap();
+        (guess, hint)
+    }
+
+    #[test]
+    fn share_grid_counts_reveals_as_attempts() {
+        let history = [row("sheep", "petal"), row("petal", "petal")];
+        let grid = share_grid("Rudle", &history, &[1], 6, &FeedbackSymbols::default());
+        assert_eq!(grid, "Rudle 3/6\n\n⬛⬛🟨⬛🟨\n🔍\n🟩🟩🟩🟩🟩");
+    }
+
+    #[test]
+    fn share_grid_of_a_lost_game_is_scored_x() {
+        let history = [row("sheep", "petal")];
+        let grid = share_grid("Rudle", &history, &[0], 6, &FeedbackSymbols::default());
+        assert_eq!(grid, "Rudle X/6\n\n🔍\n⬛⬛🟨⬛🟨");
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        for len in [1, 5, 11, MAX_WORD_SIZE] {
+            for code in [
+                0,
+                1,
+                2,
+                3_u32.pow(len as u32) / 2,
+                3_u32.pow(len as u32) - 1,
+            ] {
+                let hint = Hint::from_code(code, len);
+                assert_eq!(hint.len(), len);
+                assert_eq!(hint.to_code(), code);
+            }
+        }
+    }
+
+    #[test]
+    fn the_longest_hints_have_codes_that_fit() {
+        let hint = Hint::new(vec![LetterHint::Correct; MAX_WORD_SIZE]);
+        assert_eq!(hint.to_code() as u64, 3_u64.pow(MAX_WORD_SIZE as u32) - 1);
+        assert!(3_u64.pow(MAX_WORD_SIZE as u32 + 1) > u32::MAX as u64);
+    }
+
+    #[test]
+    fn share_rows_name_their_guess_on_either_side() {
+        let (guess, hint) = row("sheep", "petal");
+        for line in [
+            "⬛⬛🟨⬛🟨",
+            "SHEEP ⬛⬛🟨⬛🟨",
+            "⬛⬛🟨⬛🟨 sheep",
+            "⬜⬜🟦⬜🟦",
+        ] {
+            let (named, parsed) = parse_share_row(line, 5).unwrap();
+            assert_eq!(parsed, hint);
+            assert_eq!(
+                named.is_some(),
+                line.contains("SHEEP") || line.contains("sheep")
+            );
+            assert!(named.is_none_or(|w| w == guess));
+        }
+    }
+
+    #[test]
+    fn share_rows_report_why_they_are_not_rows() {
+        let row_length = |expected, found| ParseError::RowLength { expected, found };
+        assert_eq!(
+            parse_share_row("Wordle 1,234 4/6", 5),
+            Err(row_length(5, 0))
+        );
+        assert_eq!(parse_share_row("", 5), Err(row_length(5, 0)));
+        assert_eq!(parse_share_row("⬛🟨⬛🟩", 5), Err(row_length(5, 4)));
+        assert_eq!(parse_share_row("⬛🟨⬛🟩🟩🟩", 5), Err(row_length(5, 6)));
+        assert_eq!(
+            parse_share_row("⬛🟨🟥⬛🟩", 5),
+            Err(ParseError::InvalidSquare {
+                position: 2,
+                found: '🟥'
+            })
+        );
+    }
+
+    #[test]
+    fn hint_strings_report_where_they_go_wrong() {
+        let guess = Word::from_string("crane").unwrap();
+        assert_eq!(
+            Hint::from_string("c*_", &guess),
+            Err(ParseError::LengthMismatch {
+                expected: 5,
+                found: 3
+            })
+        );
+        assert_eq!(
+            Hint::from_string("c*_?_", &guess),
+            Err(ParseError::InvalidHintChar {
+                position: 3,
+                found: '?'
+            })
+        );
+        assert_eq!(
+            Hint::from_string("gybbg", &guess),
+            Ok(Hint::new(vec![
+                LetterHint::Correct,
+                LetterHint::Misplaced,
+                LetterHint::Incorrect,
+                LetterHint::Incorrect,
+                LetterHint::Correct,
+            ]))
+        );
+    }
+
+    /// Characters the property tests draw arbitrary input from: letters, hint and pattern
+    /// characters, every square, other emoji, combining marks and control characters
+    const ALPHABET: &[char] = &[
+        'a', 'e', 'z', 'A', 'Q', 'g', 'y', 'b', '*', '_', '?', ' ', '\t', '\n', '/', '1', 'é', 'ß',
+        '\u{301}', '\u{0}', '\u{FEFF}', '🟩', '🟧', '🟨', '🟦', '⬛', '⬜', '🟥', '🟪', '🔍', '日',
+    ];
+
+    fn arbitrary_string(rng: &mut impl Rng, max_len: usize) -> String {
+        let len = rng.gen_range(0..=max_len);
+        (0..len)
+            .map(|_| ALPHABET[rng.gen_range(0..ALPHABET.len())])
+            .collect()
+    }
+
+    fn arbitrary_word(rng: &mut impl Rng, len: usize) -> Word {
+        let letters = (0..len).map(|_| rng.gen_range(b'A'..=b'Z') as char);
+        Word::from_string(&letters.collect::<String>()).unwrap()
+    }
+
+    #[test]
+    fn parsers_never_panic_on_arbitrary_input() {
+        let mut rng = StdRng::seed_from_u64(735);
+        for _ in 0..20_000 {
+            let s = arbitrary_string(&mut rng, 12);
+            let word_size = rng.gen_range(0..=MAX_WORD_SIZE + 1);
+            if let Ok(word) = Word::from_string(&s) {
+                assert!(word.iter().all(|c| c.is_ascii_uppercase()));
+                assert_eq!(word.len(), s.len());
+                let _ = Hint::from_string(&s, &word);
+            }
+            let guess = arbitrary_word(&mut rng, s.chars().count().min(MAX_WORD_SIZE));
+            if let Ok(hint) = Hint::from_string(&s, &guess) {
+                assert_eq!(hint.len(), guess.len());
+            }
+            match parse_share_row(&s, word_size) {
+                Ok((guess, hint)) => {
+                    assert_eq!(hint.len(), word_size);
+                    assert!(guess.is_none_or(|w| w.len() == word_size));
+                }
+                Err(ParseError::RowLength { expected, found }) => {
+                    assert_eq!(expected, word_size);
+                    assert_ne!(found, word_size);
+                }
+                Err(ParseError::InvalidSquare { found, .. }) => assert!(s.contains(found)),
+                Err(e) => panic!("unexpected error {:?} for {:?}", e, s),
+            }
+        }
+    }
+
+    #[test]
+    fn patterns_and_share_rows_round_trip() {
+        let mut rng = StdRng::seed_from_u64(735);
+        let symbols = FeedbackSymbols::default();
+        for _ in 0..2_000 {
+            let len = rng.gen_range(1..=MAX_WORD_SIZE);
+            let guess = arbitrary_word(&mut rng, len);
+            let answer = arbitrary_word(&mut rng, len);
+            let hint = Hint::from_guess_and_answer(&guess, &answer).unwrap();
+
+            let pattern = hint.to_pattern(&guess);
+            assert_eq!(Hint::from_string(&pattern, &guess), Ok(hint.clone()));
+
+            let squares: String = hint.iter().map(|&h| symbols.symbol(h)).collect();
+            let line = format!("{} {}", guess, squares);
+            assert_eq!(parse_share_row(&line, len), Ok((Some(guess), hint)));
+        }
+    }
+}
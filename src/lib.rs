@@ -0,0 +1,40 @@
+//! Rudle: a Wordle player and solver
+//!
+//! The library exposes the building blocks the `rudle` binary is made of: [`Word`] and
+//! [`Hint`] for representing guesses and their feedback, and the [`solver`] module for
+//! narrowing down and scoring candidate guesses.
+//!
+//! ```
+//! use rudle::{solver, Hint, Word};
+//!
+//! let words: Vec<Word> = ["CRANE", "SLATE", "TRACE"]
+//!     .iter()
+//!     .map(|w| Word::from_string(w).unwrap())
+//!     .collect();
+//! let guess = Word::from_string("crane").unwrap();
+//! let hint = Hint::from_guess_and_answer(&guess, &words[2]).unwrap();
+//! assert_eq!(hint, Hint::from_string("*ra_e", &guess).unwrap());
+//!
+//! let scores = solver::get_scores(&words, &words, false);
+//! println!("Best guess: {}", scores[0].0);
+//! ```
+
+pub mod error;
+pub mod hash;
+pub mod hint;
+pub mod optimal;
+pub mod picker;
+pub mod profile;
+pub mod rules;
+pub mod solver;
+pub mod strategy;
+pub mod tree;
+pub mod variant;
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+pub mod wasm;
+pub mod web;
+pub mod word;
+
+pub use error::{ParseError, RuleViolation};
+pub use hint::{Hint, LetterHint};
+pub use word::{Word, WordLists};
@@ -1,615 +1,925 @@
-use clap::{Parser, Subcommand};
-use colored::*;
-use indicatif::{ProgressBar, ProgressStyle};
-use rand::seq::SliceRandom;
-use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
-use std::fmt;
-use std::fs::File;
-use std::hash::Hash;
-use std::io::{self, BufRead, BufReader, Write};
-use std::iter::zip;
-
+use clap::parser::ValueSource;
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
+use cli::render::{OutputFormat, Theme};
+use rudle::hint::FeedbackSymbols;
+use rudle::picker::PickerKind;
+use rudle::rules::Ruleset;
+use rudle::strategy::{Objective, StrategyKind};
+use rudle::variant::Variant;
+use rudle::{profile, word, Word, WordLists};
+use std::env;
+use std::io;
+
+mod cli;
+
+/// A Wordle player and solver
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
-struct Args {
-    /// Whether to solve the wordle or play it
-    /// Possible values: "play", "solve"
-    #[arg(short, long)]
-    task: String,
-
-    /// Whether the game is in hard mode or easy mode
-    /// If it is in hard mode, hints MUST be used
-    /// i.e. words disqualified from being the answer by previous hints
-    /// cannot be played
-    #[arg(short, long, default_value = "easy")]
-    mode: String,
-
-    /// The file containing the word list
-    #[arg(short, long, default_value = "words.txt")]
-    file: String,
-
-    /// The number of letters in the guesses of the game
-    #[arg(long, default_value = "5")]
-    word_size: usize,
+struct Cli {
+    #[command(flatten)]
+    common: CommonArgs,
 
-    /// The maximum number of attempts allowed in the game
-    #[arg(long, default_value = "6")]
-    max_attempts: usize,
+    #[command(subcommand)]
+    command: Command,
 }
 
-fn main() -> Result<(), io::Error> {
-    let config: Args = Args::parse();
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Play a game against a random secret word
+    Play(PlayArgs),
+    /// Play a game against a word you struggled with before
+    Review(PlayArgs),
+    /// Start a REPL that narrows down the answer from the hints of a game played elsewhere
+    Solve(SolveArgs),
+    /// Practice positions and compare your guess to the solver's best
+    Drill(DrillArgs),
+    /// Let the solver play against every possible answer and report how it did
+    Bench(BenchArgs),
+    /// Let built-in strategies and bots run as child processes play the same answers, and
+    /// rank them
+    Tournament(TournamentArgs),
+    /// Run strategy comparisons pinned down by a manifest, so that others can reproduce them
+    Experiment {
+        #[command(subcommand)]
+        command: ExperimentCommand,
+    },
+    /// Write the decision tree of a strategy as JSON or Graphviz DOT
+    ExportTree(ExportTreeArgs),
+    /// Search the game tree for the guesses that need the fewest guesses on average
+    Optimal(OptimalArgs),
+    /// Find the secret words that take longest to solve for a player with a known opener
+    Compose(ComposeArgs),
+    /// List the words that fit a crossword pattern such as "c_a__e"
+    Fit(FitArgs),
+    /// Report how the openers you played have done, next to their expected scores
+    Stats,
+    /// Grade games against the solver and report on them together, by turn and by week
+    Grade(GradeArgs),
+    /// Add games played elsewhere, from WordleBot share text, to the games `stats` reports on
+    Import {
+        /// The file holding the share text of one or more games
+        path: String,
+        /// The first guess of grids that do not list their guesses
+        #[arg(long)]
+        opener: Option<String>,
+    },
+    /// Print the games of a results file written by `bench --output`, `tournament --output` or
+    /// `experiment run`
+    Results {
+        /// The results file
+        path: String,
+    },
+    /// Print the JSON Schema of a machine-readable output, such as "overlay"
+    Schema {
+        /// The output to describe
+        name: String,
+    },
+    /// Watch a game hosted with `play --host`
+    Watch {
+        /// The address the game is hosted on, e.g. "192.168.1.20:7878"
+        address: String,
+    },
+    /// Check this build against the transcripts of known solver sessions and games
+    Selftest {
+        /// Replace the transcripts in the source tree with the output of this build
+        #[arg(long)]
+        bless: bool,
+    },
+    /// Check whether a newer release is out and print its release notes
+    Update {
+        /// Look the releases up, which is the only time Rudle goes online
+        #[arg(long, required = true)]
+        check: bool,
+        /// The release feed to read, as a URL curl can fetch, e.g. a file:// URL [default:
+        /// the GitHub releases of Rudle]
+        #[arg(long, value_name = "URL")]
+        feed: Option<String>,
+    },
+    /// Analyze word lists
+    Words {
+        #[command(subcommand)]
+        command: WordsCommand,
+    },
+}
 
-    let word_list = load_words(&config)?;
+#[derive(Subcommand, Debug)]
+enum ExperimentCommand {
+    /// Let every strategy of a manifest play every answer, and write the games with the
+    /// environment they were played in to a results file
+    ///
+    /// The manifest is TOML naming the word lists with their hashes, the seed and the
+    /// strategies. The word lists and sizes of the manifest are used, not --file.
+    Run {
+        /// The manifest
+        #[arg(value_name = "MANIFEST")]
+        path: String,
+        /// The results file [default: the manifest's output, or the manifest with the
+        /// extension .jsonl]
+        #[arg(long, value_name = "FILE")]
+        output: Option<String>,
+    },
+}
 
-    match config.task.as_str() {
-        "play" => play(word_list, config),
-        "solve" => solve(word_list, config),
-        _ => println!("Invalid mode"),
-    }
+#[derive(Subcommand, Debug)]
+enum WordsCommand {
+    /// Print statistics that help compare candidate word lists
+    Audit {
+        /// The word list to audit
+        #[arg(value_name = "FILE")]
+        path: String,
+    },
+    /// Let the solver play every answer and sort the answers into difficulty tiers by the
+    /// guesses they took
+    Difficulty {
+        /// The answer list; guesses come from --file if it is given, or else the same list
+        #[arg(value_name = "FILE")]
+        path: String,
+        /// Write each answer with its guesses and tier, for `play --pick adaptive --tiers`
+        #[arg(long, value_name = "FILE")]
+        output: Option<String>,
+        /// The number of guesses after which an answer counts as not solved
+        #[arg(long, default_value = "6")]
+        max_attempts: usize,
+    },
+    /// Print the words added and removed between two word lists and the effect on the solver
+    Compare {
+        /// The word list in use
+        #[arg(value_name = "OLD")]
+        old: String,
+        /// The word list to switch to
+        #[arg(value_name = "NEW")]
+        new: String,
+    },
+}
 
-    Ok(())
+/// Options shared by every command
+#[derive(clap::Args, Debug)]
+pub struct CommonArgs {
+    /// The file containing the word list [default: words.txt, or the variant's own list]
+    #[arg(short, long, global = true)]
+    pub file: Option<String>,
+
+    /// The file containing the possible answers, if they are fewer than the allowed guesses
+    ///
+    /// The solver still scores every word in --file, but only narrows down these answers,
+    /// and games only pick their secret word from this list
+    #[arg(long, value_name = "FILE", global = true)]
+    pub answers_file: Option<String>,
+
+    /// Play a popular variant: wordle or sixes
+    ///
+    /// Sets the word size and attempt count unless --word-size or --max-attempts gives them,
+    /// and uses the variant's own word list when Rudle ships one and no --file is given
+    #[arg(long, global = true)]
+    pub variant: Option<Variant>,
+
+    /// The number of letters in the guesses of the game, at most 20
+    #[arg(long, default_value = "5", global = true, value_parser = parse_word_size)]
+    pub word_size: usize,
+
+    /// Print a summary of where time was spent when the program exits
+    #[arg(long, global = true)]
+    pub profile: bool,
+
+    /// Make output reproducible for golden-file tests
+    ///
+    /// Fixes the random seed, hides progress bars, and sorts all word lists
+    #[arg(long, global = true)]
+    pub deterministic: bool,
+
+    /// Seed the random choices, such as the secret word of `play` and the random strategy,
+    /// so that the same seed plays out the same way again
+    #[arg(long, global = true)]
+    pub seed: Option<u64>,
+
+    /// How hints and tables are printed: color, plain, markdown or html
+    ///
+    /// Markdown and HTML can be pasted into blogs, chats and issue reports
+    #[arg(long, default_value = "color", global = true)]
+    pub format: OutputFormat,
+
+    /// The colors of the hints: default (green and yellow) or colorblind (orange and blue)
+    #[arg(long, default_value = "default", global = true)]
+    pub color_theme: Theme,
+
+    /// Print without colors, marking hints with symbols such as [C], (m) and .x. instead
+    ///
+    /// Setting the NO_COLOR environment variable does the same.
+    #[arg(long, global = true)]
+    pub no_color: bool,
 }
 
-/// Load words from a file and return a Vec of unique words of length WORDLE_SZ (5)
-/// # Arguments
-/// * `file` - The file containing the word list
-/// # Returns
-/// A Vec of unique words of length WORDLE_SZ (5)
-/// # Errors
-/// If the file cannot be opened
-fn load_words(config: &Args) -> Result<Vec<Word>, io::Error> {
-    let file = File::open(config.file.clone())?;
-    let reader = BufReader::new(file);
-
-    // Use a HashSet to remove duplicates
-    let mut unique_words: HashSet<Word> = HashSet::new();
-
-    for word in reader.lines().map_while(Result::ok) {
-        if is_valid_word(&word, config) {
-            let word = Word::from_string(&word).unwrap();
-            unique_words.insert(word);
-        }
-    }
+/// Options for playing a game
+#[derive(clap::Args, Debug)]
+pub struct PlayArgs {
+    /// The rules guesses must follow: easy, hard or ultra-hard
+    ///
+    /// In hard mode correct letters must be reused in place and misplaced letters reused;
+    /// in ultra-hard mode every guess must be a possible answer given the hints so far
+    #[arg(short, long, default_value = "easy")]
+    pub mode: Ruleset,
 
-    let words: Vec<Word> = unique_words.into_iter().collect();
-    println!("Loaded {} unique words", words.len());
-    Ok(words)
+    /// The maximum number of attempts allowed in the game
+    #[arg(long, default_value = "6")]
+    pub max_attempts: usize,
+
+    /// Play this many boards at once, each with its own secret word, as in Dordle (2) or
+    /// Quordle (4); every board after the first adds an attempt to --max-attempts
+    #[arg(long, default_value = "1", value_parser = clap::value_parser!(u8).range(1..=32))]
+    pub boards: u8,
+
+    /// Keep playing new words for as long as every one is solved, and report the run and the
+    /// longest run once a word is missed
+    #[arg(long, conflicts_with = "boards")]
+    pub survival: bool,
+
+    /// Play today's puzzle, the same for everyone with the same answer list, once a day;
+    /// `rudle stats` reports the streak of daily puzzles won
+    #[arg(long, conflicts_with_all = ["boards", "survival", "pick"])]
+    pub daily: bool,
+
+    /// When to show how good each guess was compared to the solver's best guess
+    ///
+    /// "live" shows it after every guess, "post" only once the game is over
+    #[arg(long, default_value = "off", value_parser = ["off", "live", "post"])]
+    pub meter: String,
+
+    /// Once the game is over, show what the solver would have played at every guess and
+    /// where the game was won or lost
+    #[arg(long)]
+    pub post_mortem: bool,
+
+    /// Do not show the keyboard, colored by the feedback so far, after each guess
+    #[arg(long)]
+    pub no_keyboard: bool,
+
+    /// Play in the whole terminal, with the board, the keyboard and a solver panel shown
+    /// with Tab, and letters colored by the hints so far as they are typed
+    #[arg(long, conflicts_with = "boards")]
+    pub tui: bool,
+
+    /// Play together with the engine: before each guess it proposes three guesses with the
+    /// reasons for them, and you pick one by its number or enter your own
+    ///
+    /// The game ends with how your picks compared to the engine's first choices.
+    #[arg(long, conflicts_with_all = ["boards", "tui"])]
+    pub coop: bool,
+
+    /// Let `!undo` take back the last guess and its attempt, for casual games and teaching
+    ///
+    /// Games with undos cannot be attested.
+    #[arg(long, conflicts_with_all = ["boards", "tui", "attest"])]
+    pub allow_undo: bool,
+
+    /// How many times `!reveal` may be used in a game, each showing a letter of the answer
+    /// that no hint has placed yet at the cost of an attempt
+    #[arg(long, default_value = "0", conflicts_with_all = ["boards", "tui", "attest"])]
+    pub powerups: usize,
+
+    /// The symbols of the share grid printed after the game
+    ///
+    /// Either "default" (🟩🟨⬛), "colorblind" (🟧🟦⬛), or three symbols such as "🟦🟧⬛"
+    #[arg(long, default_value = "default")]
+    pub share_symbols: FeedbackSymbols,
+
+    /// Also copy the share grid to the clipboard, with pbcopy, clip, wl-copy, xclip or xsel
+    #[arg(long)]
+    pub copy: bool,
+
+    /// After each guess, show how many answers remain and the solver's top 3 guesses
+    #[arg(long)]
+    pub assist: bool,
+
+    /// How the secret word is picked: uniform, weighted (by --answer-weights), daily (the same
+    /// word for everyone each day), never-repeat (skip words already played), adversarial
+    /// (keep changing the word to give away as little as possible) or adaptive (rarer words
+    /// and repeated letters the more of the recent games were won)
+    #[arg(long, default_value = "uniform")]
+    pub pick: PickerKind,
+
+    /// Weights for --pick weighted or adaptive, one "WORD WEIGHT" line per answer; unlisted
+    /// answers weigh 1
+    #[arg(long, value_name = "FILE")]
+    pub answer_weights: Option<String>,
+
+    /// Difficulty tiers for --pick adaptive, as written by `rudle words difficulty --output`
+    ///
+    /// Answers are then ranked by the guesses the solver needed instead of by rarity.
+    #[arg(long, value_name = "FILE")]
+    pub tiers: Option<String>,
+
+    /// Keep a file updated with the board and keyboard for streaming overlays such as OBS
+    ///
+    /// Files ending in ".json" get JSON, anything else plain text
+    #[arg(long, value_name = "FILE")]
+    pub obs_output: Option<String>,
+
+    /// Let spectators watch the game live with `rudle watch`, listening on this address,
+    /// e.g. "0.0.0.0:7878"; the secret word is only shown to them once the game is lost
+    #[arg(long, value_name = "ADDRESS")]
+    pub host: Option<String>,
+
+    /// Write an attestation of the game for tournament organizers: the puzzle, the time of
+    /// every guess, the assists used and the version of Rudle, as JSON
+    #[arg(long, value_name = "FILE")]
+    pub attest: Option<String>,
 }
 
-fn is_valid_word(word: &str, config: &Args) -> bool {
-    word.len() == config.word_size && word.chars().all(char::is_alphabetic)
+/// Options for the solver REPL
+#[derive(clap::Args, Debug)]
+pub struct SolveArgs {
+    /// The maximum number of attempts allowed in the game being solved
+    #[arg(long, default_value = "6")]
+    pub max_attempts: usize,
+
+    /// The symbols used for correct, misplaced, and incorrect letters in share grids
+    ///
+    /// Either "default" (🟩🟨⬛), "colorblind" (🟧🟦⬛), or three symbols such as "🟦🟧⬛"
+    /// Defaults to the saved `theme` setting, or "default".
+    #[arg(long)]
+    pub share_symbols: Option<FeedbackSymbols>,
+
+    /// A list of words you know, which `top` only recommends from while `set vocab mine` is on
+    ///
+    /// It is on from the start when a list is given
+    #[arg(long, value_name = "FILE")]
+    pub vocab: Option<String>,
+
+    /// How common each answer is, one "WORD FREQUENCY" line each, used as a prior so that
+    /// `top` and `remaining` favor common answers; unlisted answers weigh 1
+    ///
+    /// Without it, a frequency column after a tab in the answer list is used.
+    #[arg(long, value_name = "FILE")]
+    pub weights_file: Option<String>,
+
+    /// Instead of starting the REPL, let the solver play against this answer and print each
+    /// guess, hint and the number of answers left
+    #[arg(long, value_name = "ANSWER")]
+    pub auto: Option<String>,
+
+    /// How the solver picks its guesses with --auto
+    ///
+    /// One of entropy, minimax (smallest worst case), frequent-letters, random,
+    /// expected-guesses (fewest guesses on average) or win-in-2
+    #[arg(long, default_value = "entropy")]
+    pub strategy: StrategyKind,
+
+    /// What the solver aims for, which sets the strategy with the tie-breaks and endgame that
+    /// suit it: expected-guesses, max-info, min-worst or win-in-2
+    #[arg(long, conflicts_with = "strategy")]
+    pub objective: Option<Objective>,
+
+    /// How many guesses ahead to look when ranking: 1, or 2 to re-rank the best guesses by
+    /// the expected information of the best follow-up guess, which is much slower
+    ///
+    /// Defaults to the saved `depth` setting, or 1.
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub depth: Option<u8>,
+
+    /// Run the REPL commands in a file, one per line, and exit at its end instead of reading
+    /// them from stdin
+    #[arg(long, value_name = "FILE")]
+    pub script: Option<String>,
+
+    /// Print the output of top, score, hint and history as one JSON object per line, and
+    /// no prompt
+    #[arg(long)]
+    pub json: bool,
+
+    /// Score every guess after each hint instead of only the most promising few thousand
+    #[arg(long)]
+    pub exhaustive: bool,
+
+    /// Calculate the opening scores from scratch instead of using or saving the cache
+    #[arg(long)]
+    pub no_cache: bool,
+
+    /// Mask today's daily answer wherever a command would print it, so that analyzing the
+    /// daily puzzle mid-game shows only statistics
+    ///
+    /// The same as the `spoiler-guard` setting; `set spoiler-guard off` lifts it.
+    #[arg(long)]
+    pub spoiler_guard: bool,
+
+    /// Print today's daily answer like any other word, even if the saved `spoiler-guard`
+    /// setting is on
+    #[arg(long, conflicts_with = "spoiler_guard")]
+    pub reveal: bool,
 }
 
-fn play(word_list: Vec<Word>, config: Args) {
-    // Select a random word from the word list
-    let secret_word = word_list
-        .choose(&mut rand::thread_rng())
-        .expect("Word list is empty");
-
-    println!(
-        "Welcome to Wordle! Guess the {}-letter word. You have 6 attempts.\n",
-        config.word_size
-    );
-    println!("Letters are marked grey if they don't appear in the word.");
-    println!(
-        "Letters are marked {} if they are in the wrong position.",
-        "yellow".yellow()
-    );
-    println!(
-        "Letters are marked {} if they correct position.\n",
-        "green".green()
-    );
-
-    let mut attempts = 0;
-
-    while attempts < config.max_attempts {
-        println!("You have {} attempts left.", config.max_attempts - attempts);
-        print!("Enter your guess: ");
-        Write::flush(&mut std::io::stdout()).unwrap();
-
-        let mut guess = String::new();
-        std::io::stdin()
-            .read_line(&mut guess)
-            .expect("Failed to read input");
-        let guess = guess.trim();
-
-        if guess.len() != config.word_size {
-            println!("Please enter a {}-letter word.\n", config.word_size);
-            continue;
-        }
+/// Options for benchmarking the solver
+#[derive(clap::Args, Debug)]
+pub struct BenchArgs {
+    /// The number of guesses after which a game counts as failed
+    #[arg(long, default_value = "6")]
+    pub max_attempts: usize,
+
+    /// How the solver picks its guesses
+    ///
+    /// One of entropy, minimax (smallest worst case), frequent-letters, random,
+    /// expected-guesses (fewest guesses on average) or win-in-2
+    #[arg(long, default_value = "entropy")]
+    pub strategy: StrategyKind,
+
+    /// What the solver aims for, which sets the strategy with the tie-breaks and endgame that
+    /// suit it: expected-guesses, max-info, min-worst or win-in-2
+    #[arg(long, conflicts_with = "strategy")]
+    pub objective: Option<Objective>,
+
+    /// The probability that each letter of a hint is entered wrong, to test how the solver
+    /// copes with mistakes
+    #[arg(long, default_value = "0", value_parser = parse_probability)]
+    pub noise: f64,
+
+    /// Write every game to a results file, as JSON lines described by `rudle schema results`
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<String>,
+}
 
-        if !guess.chars().all(char::is_alphabetic) {
-            println!("Please enter a word containing only alphabetic characters.\n");
-            continue;
-        }
+/// Options for grading games
+#[derive(clap::Args, Debug)]
+pub struct GradeArgs {
+    /// Grade the games in the files of a directory: games stored as `DAY SECRET RESULT
+    /// GUESS...` lines, as in games.txt, or share text that names the guess of each row
+    #[arg(long, value_name = "DIR", conflicts_with = "month")]
+    pub batch: Option<String>,
 
-        let guess = Word::from_string(guess).unwrap();
+    /// Grade the stored games played in a month, e.g. "2026-09" [default: the last 30 days]
+    #[arg(long, value_name = "YYYY-MM")]
+    pub month: Option<String>,
 
-        if !word_list.contains(&guess) {
-            println!("Invalid word. Please try again.\n");
-            continue;
-        }
+    /// The number of guesses the solver gets for each answer it is compared on
+    #[arg(long, default_value = "6")]
+    pub max_attempts: usize,
+}
 
-        if guess == *secret_word {
-            println!("{}", "Congratulations! You guessed the word!".green());
-            break;
-        }
+/// Options for a tournament between strategies and bots
+#[derive(clap::Args, Debug)]
+pub struct TournamentArgs {
+    /// The built-in strategies that enter, e.g. "entropy minimax"
+    ///
+    /// Any of entropy, minimax, frequent-letters, random, expected-guesses or win-in-2
+    #[arg(value_name = "STRATEGY")]
+    pub strategies: Vec<StrategyKind>,
+
+    /// A command that enters as a bot, speaking the JSON-lines protocol on its stdin and
+    /// stdout, e.g. "python3 bot.py"; it may be given more than once
+    ///
+    /// The command is run by the shell. See `rudle schema bot` for the messages it is sent.
+    #[arg(long = "bot", value_name = "COMMAND")]
+    pub bots: Vec<String>,
+
+    /// The number of answers every entrant plays, picked at random; every answer if not given
+    #[arg(long)]
+    pub games: Option<usize>,
+
+    /// The number of guesses after which a game counts as failed
+    #[arg(long, default_value = "6")]
+    pub max_attempts: usize,
 
-        // Provide feedback for the guess
-        let hint = Hint::from_guess_and_answer(&guess, secret_word).unwrap();
-        print_hint(&hint, &guess);
-        println!();
-        attempts += 1;
-    }
+    /// How many seconds a bot may take to reply before it is dropped from the tournament
+    #[arg(long, default_value = "10", value_name = "SECONDS")]
+    pub move_timeout: u64,
 
-    if attempts == config.max_attempts {
-        let secret_word: String = secret_word.iter().collect();
-        println!(
-            "{} The correct word was: {}",
-            "Game Over!".red(),
-            secret_word.green()
-        );
-    }
+    /// Write every game to a results file, as JSON lines described by `rudle schema results`
+    #[arg(long, value_name = "FILE")]
+    pub output: Option<String>,
 }
 
-/// Command-line arguments for the REPL
-#[derive(Parser)]
-#[command(author, version, about)]
-struct SolverArgs {
-    #[command(subcommand)]
-    command: SolverCommand,
-}
+/// Options for exporting decision trees
+#[derive(clap::Args, Debug)]
+pub struct ExportTreeArgs {
+    /// The files to write: DOT for files ending in ".dot" or ".gv", JSON for anything else
+    #[arg(required = true, value_name = "FILE")]
+    pub outputs: Vec<String>,
 
-/// REPL commands
-#[derive(Subcommand)]
-enum SolverCommand {
-    /// Print the top n best guesses with their scores
-    Top {
-        /// Number of guesses to print
-        n: usize,
-        strict: Option<String>,
-    },
-    /// Print the score of a word
-    Score {
-        /// The word to score
-        word: String,
-    },
-    /// Add a word and its hint to narrow the list
-    Hint {
-        /// The guessed word
-        guess: String,
-        /// Feedback for the guessed word (e.g., "g*y**")
-        hint: String,
-    },
-    /// Print the history of guesses and feedback
-    History,
-    /// Undo the last guess and restore the word list
-    Undo,
-    /// Exit the REPL
-    Exit,
+    /// The number of guesses after which the strategy gives up
+    #[arg(long, default_value = "6")]
+    pub max_attempts: usize,
+
+    /// How the solver picks its guesses
+    ///
+    /// One of entropy, minimax (smallest worst case), frequent-letters, random,
+    /// expected-guesses (fewest guesses on average) or win-in-2
+    #[arg(long, default_value = "entropy")]
+    pub strategy: StrategyKind,
+
+    /// What the solver aims for, which sets the strategy with the tie-breaks and endgame that
+    /// suit it: expected-guesses, max-info, min-worst or win-in-2
+    #[arg(long, conflicts_with = "strategy")]
+    pub objective: Option<Objective>,
+
+    /// Export the exact optimal policy of `rudle optimal` instead of a strategy's
+    #[arg(long, conflicts_with_all = ["strategy", "objective"])]
+    pub optimal: bool,
 }
 
-const HELP_MESSAGE: &str =
-    "top <n> [strict]     Print the top n best guesses with their scores, given the
-                     remaining possible answers. Scores are the percentage by 
-                     which a guessed word reduces the list of possible remaining 
-                     answers. If 'strict' is provided, only consider words that
-                     score words that are still in the list of possible answers.
-
-score <word>         Print the scores of a word, given the remaining possible 
-                     answers. Scores are the percentage by which a guessed word 
-                     reduces the list of possible remaining answers. 
-
-hint <word> <hint>   Add a word and its hint to reduce the possible answers.
-                     For <word> retype the guessed word.
-                     Here is how to type <hint>:
-                     - If a letter is green/guessed correctly, retype the letter
-                     - If a letter is yellow/misplaced, type '*' in its position
-                     - If a letter is grey/incorrect, type '_' in its position
-                     Example: 'hint hello h*ll_'
-
-history              Print the history of guesses and feedback
-
-undo                 Undo the last guess and restore the word list
-
-help                 Print the help message, listing the available commands.
-
-exit                 Exit the REPL";
-
-fn solve(word_list: Vec<Word>, config: Args) {
-    let mut remaining_guesses = word_list.clone();
-    let mut remaining_answers = word_list;
-    let mut removed_answers: Vec<Vec<Word>> = vec![];
-    let mut guess_history: Vec<(Word, Hint)> = vec![];
-
-    let mut word_scores: Vec<Vec<(Word, f32, f32)>> = vec![];
-    word_scores.push(get_scores(&remaining_guesses, &remaining_answers));
-
-    println!("Starting Wordle Solver REPL. Type 'help' for commands.");
-
-    loop {
-        // Print the REPL prompt
-        print!("> ");
-        io::stdout().flush().unwrap();
-
-        // Read user input
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read input");
-        let input = input.trim();
-        let mut args: Vec<&str> = vec!["repl"];
-        args.extend(input.split_whitespace());
-
-        if args.len() == 2 && args[1] == "help" {
-            println!("{}", HELP_MESSAGE);
-            continue;
-        }
-
-        // Parse the input into commands
-        let args = match SolverArgs::try_parse_from(args) {
-            Ok(parsed) => parsed,
-            Err(_) => {
-                println!("Bad command. Type 'help' for commands.");
-                continue;
-            }
-        };
-
-        // Process the parsed command
-        match args.command {
-            SolverCommand::Top { n, strict } => {
-                let answer_scores;
-                let scores = match strict {
-                    None => &word_scores[guess_history.len()],
-                    Some(s) if s == "strict" => {
-                        answer_scores = word_scores[guess_history.len()]
-                            .iter()
-                            .filter(|(w, _, _)| remaining_answers.contains(w))
-                            .cloned()
-                            .collect::<Vec<(Word, f32, f32)>>();
-                        &answer_scores
-                    }
-                    _ => {
-                        println!("Bad command. Type 'help' for commands.");
-                        continue;
-                    }
-                };
-
-                println!("Rank | Word  | Expected | Worst-Case ");
-                println!("-----|-------|----------|------------");
-                for (i, (word, avg_score, min_score)) in scores.iter().enumerate() {
-                    if i >= n {
-                        break;
-                    }
-                    println!(
-                        "{:>4} | {} | {:>7.3}% | {:>9.3}%",
-                        i + 1,
-                        word.iter().collect::<String>(),
-                        avg_score,
-                        min_score
-                    );
-                }
-            }
-            SolverCommand::Score { word } => {
-                let word = match Word::from_string(&word) {
-                    Ok(w) => w,
-                    Err(e) => {
-                        println!("Error: {}", e);
-                        continue;
-                    }
-                };
-                let scores = &word_scores[guess_history.len()];
-
-                if let Some((i, (_, avg_score, min_score))) =
-                    scores.iter().enumerate().find(|(_, (w, _, _))| w == &word)
-                {
-                    println!("Rank: {}", i + 1);
-                    println!("Expected: {:.3}%", avg_score);
-                    println!("Worst-Case: {:.3}%", min_score);
-                } else {
-                    println!("Word not found in word list.");
-                }
-            }
-            SolverCommand::Hint { guess, hint } => {
-                let guess = match Word::from_string(&guess) {
-                    Ok(w) => w,
-                    Err(e) => {
-                        println!("Error: {}", e);
-                        continue;
-                    }
-                };
-                let hint = match Hint::from_string(&hint, &guess) {
-                    Ok(h) => h,
-                    Err(e) => {
-                        println!("Error: {}", e);
-                        continue;
-                    }
-                };
-                if guess.len() != config.word_size || hint.len() != config.word_size {
-                    println!(
-                        "Guess and hint must both have a size of {}",
-                        config.word_size
-                    );
-                    continue;
-                }
-                if !guess.iter().all(|c| c.is_alphabetic()) {
-                    println!("Guess must only contain alphabetic characters.");
-                    continue;
-                }
-                print_hint(&hint, &guess);
-                println!();
-                remaining_guesses.retain(|w| w != &guess);
-                let removed_words;
-                (remaining_answers, removed_words) = remaining_answers.into_iter().partition(|w| {
-                    let h = Hint::from_guess_and_answer(&guess, w).expect("Invalid hint");
-                    h == hint
-                });
-                println!("Removed {} words.", removed_words.len());
-                println!("{} possible answers remaining.", remaining_answers.len());
-                word_scores.push(get_scores(&remaining_guesses, &remaining_answers));
-                guess_history.push((guess, hint));
-                removed_answers.push(removed_words);
-            }
-            SolverCommand::History => {
-                let mut n_words = remaining_answers.len()
-                    + removed_answers
-                        .iter()
-                        .map(|answers| answers.len())
-                        .sum::<usize>();
-                println!("Starting with {} words", n_words);
-
-                for (i, ((guess, hint), removed_words)) in
-                    zip(guess_history.iter(), removed_answers.iter()).enumerate()
-                {
-                    let percent_removed = removed_words.len() as f32 * 100.0 / n_words as f32;
-                    print!("{}: ", i + 1);
-                    print_hint(hint, guess);
-                    println!(
-                        " - Removed {} of {} ({:.2}%). {} Remaining.",
-                        removed_words.len(),
-                        n_words,
-                        percent_removed,
-                        n_words - removed_words.len()
-                    );
-                    n_words -= removed_words.len();
-                }
-            }
-            SolverCommand::Undo => {
-                if let Some((guess, hint)) = guess_history.pop() {
-                    print!("Undoing last guess: ");
-                    print_hint(&hint, &guess);
-                    println!();
-                    let answers = removed_answers.pop().expect(
-                        "No words to undo, mismatch between history and removed_words_lists",
-                    );
-                    word_scores
-                        .pop()
-                        .expect("No word score lists to remove. Something went wrong.");
-                    remaining_answers.extend(answers);
-                    remaining_guesses.push(guess);
-                    println!("Restored word list to {} words.", remaining_answers.len());
-                } else {
-                    println!("Nothing to undo.");
-                }
-            }
-            SolverCommand::Exit => {
-                println!("Exiting solver...");
-                break;
-            }
-        }
-    }
+/// Options for the exact optimal search
+#[derive(clap::Args, Debug)]
+pub struct OptimalArgs {
+    /// The number of guesses every answer must be solved within
+    #[arg(long, default_value = "6")]
+    pub max_attempts: usize,
 }
 
-fn get_scores(guesses: &[Word], answers: &[Word]) -> Vec<(Word, f32, f32)> {
-    // Create and configure the progress bar
-    println!("Calculating new word scores...");
-    let pb = ProgressBar::new(guesses.len() as u64);
-    pb.set_style(
-        ProgressStyle::default_bar()
-            .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
-            .expect("Invalid progress bar template")
-            .progress_chars("##-"),
-    );
-
-    // Process words in chunks of size 500 in parallel
-    let scores: Vec<(Word, f32, f32)> = guesses
-        .par_chunks(100)
-        .map(|chunk| {
-            let mut chunk_scores = Vec::with_capacity(chunk.len());
-
-            // Process each word in the current chunk (sequentially here)
-            for guess in chunk {
-                let mut hint_counts = HashMap::new();
-
-                // Accumulate frequencies for all possible answers
-                for answer in answers.iter() {
-                    let hint = Hint::from_guess_and_answer(guess, answer);
-                    let count = hint_counts.entry(hint).or_insert(0.0);
-                    *count += 1.0;
-                }
-
-                // Calculate score using the accumulated frequencies
-                let entropy = -hint_counts
-                    .values()
-                    .map(|&c| c / answers.len() as f32)
-                    .map(|p| p * f32::ln(p))
-                    .sum::<f32>();
-
-                let min_score = hint_counts
-                    .values()
-                    .map(|&c| 100.0 * (1.0 - c / answers.len() as f32))
-                    .fold(100.0_f32, |a, b| a.min(b));
-
-                let avg_score = (1.0 - f32::exp(-entropy)) * 100.0;
-                chunk_scores.push((guess.clone(), avg_score, min_score));
-            }
-
-            // To reduce contention, update once per chunk
-            pb.inc(chunk.len() as u64);
-
-            chunk_scores
-        })
-        .flat_map_iter(|chunk_scores| chunk_scores)
-        .collect();
+/// Options for composing hard puzzles
+#[derive(clap::Args, Debug)]
+pub struct ComposeArgs {
+    /// The opener the player always starts with
+    #[arg(long)]
+    pub against: String,
+
+    /// How the player picks their guesses after the opener
+    ///
+    /// One of entropy, minimax (smallest worst case), frequent-letters, random,
+    /// expected-guesses (fewest guesses on average) or win-in-2
+    #[arg(long, default_value = "entropy")]
+    pub strategy: StrategyKind,
+
+    /// What the solver aims for, which sets the strategy with the tie-breaks and endgame that
+    /// suit it: expected-guesses, max-info, min-worst or win-in-2
+    #[arg(long, conflicts_with = "strategy")]
+    pub objective: Option<Objective>,
+
+    /// The number of guesses after which the player loses
+    #[arg(long, default_value = "6")]
+    pub max_attempts: usize,
 
-    pb.finish_with_message("Scoring complete!");
+    /// The number of secret words to list
+    #[arg(long, default_value = "10")]
+    pub count: usize,
+}
 
-    let mut sorted_scores = scores;
-    // Sort by score descending
-    sorted_scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
-    sorted_scores
+/// Options for pattern queries
+#[derive(clap::Args, Debug)]
+pub struct FitArgs {
+    /// The letters of the word, with "_", "?" or "." for unknown letters
+    ///
+    /// The word size is the length of the pattern.
+    pub pattern: String,
+
+    /// Only list words containing every one of these letters, e.g. "r" or "a,e"
+    #[arg(long)]
+    pub contains: Option<String>,
+
+    /// Only list words containing none of these letters, e.g. "st"
+    #[arg(long)]
+    pub excludes: Option<String>,
 }
 
-#[derive(PartialEq, Clone, Hash, Eq, Debug)]
-struct Word {
-    chars: Vec<char>,
+/// Options for practice drills
+#[derive(clap::Args, Debug)]
+pub struct DrillArgs {
+    /// The kind of position to practice
+    #[arg(long, default_value = "endgame", value_parser = ["endgame", "midgame"])]
+    pub scenario: String,
 }
 
-impl Word {
-    fn new(chars: Vec<char>) -> Result<Self, String> {
-        if !chars.iter().all(|c| c.is_alphabetic()) {
-            return Err("Input string must contain only alphabetic characters.".to_string());
+fn main() -> Result<(), io::Error> {
+    let matches = Cli::command().get_matches();
+    let mut args = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+    if let Some(variant) = args.common.variant {
+        apply_variant(&mut args, variant, &matches);
+    }
+    if let Command::Fit(fit_args) = &args.command {
+        args.common.word_size = fit_args.pattern.chars().count();
+        if let Err(e) = parse_word_size(&args.common.word_size.to_string()) {
+            eprintln!("error: the pattern's word size {}", e);
+            std::process::exit(2);
         }
-
-        if !chars.iter().all(|c| c.is_ascii_uppercase()) {
-            return Err("Input string must contain only uppercase characters.".to_string());
+    }
+    // An objective stands for the strategy that plays for it
+    let objective = match &mut args.command {
+        Command::Solve(SolveArgs {
+            objective,
+            strategy,
+            ..
+        })
+        | Command::Bench(BenchArgs {
+            objective,
+            strategy,
+            ..
+        })
+        | Command::ExportTree(ExportTreeArgs {
+            objective,
+            strategy,
+            ..
+        })
+        | Command::Compose(ComposeArgs {
+            objective,
+            strategy,
+            ..
+        }) => Some((*objective, strategy)),
+        _ => None,
+    };
+    if let Some((Some(objective), strategy)) = objective {
+        *strategy = objective.strategy();
+    }
+    // The daily puzzle is picked like --pick daily, which attestations record it as
+    if let Command::Play(play_args) = &mut args.command {
+        if play_args.daily {
+            play_args.pick = PickerKind::Daily;
         }
-
-        Ok(Self { chars })
     }
+    let common = &args.common;
+
+    init_console();
+    cli::render::set_format(common.format);
+    cli::render::set_theme(common.color_theme);
+    if common.no_color || env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty()) {
+        colored::control::set_override(false);
+        console::set_colors_enabled(false);
+    }
+    install_signal_handler();
+    profile::set_enabled(common.profile);
 
-    fn from_string(s: &str) -> Result<Self, String> {
-        if !s.chars().all(char::is_alphabetic) {
-            return Err("Input string must contain only alphabetic characters.".to_string());
+    match &args.command {
+        Command::Play(play_args) => cli::play::play(load_game_words(common)?, common, play_args),
+        Command::Review(play_args) => {
+            cli::review::review(load_game_words(common)?, common, play_args)
         }
-
-        let chars: Vec<char> = s.chars().map(|c| c.to_ascii_uppercase()).collect();
-
-        Self::new(chars)
+        Command::Solve(solve_args) => {
+            cli::solve::solve(load_game_words(common)?, common, solve_args)
+        }
+        Command::Drill(drill_args) => {
+            cli::drill::drill(load_game_words(common)?, common, drill_args)
+        }
+        Command::Bench(bench_args) => {
+            cli::bench::bench(load_game_words(common)?, common, bench_args)
+        }
+        Command::Tournament(tournament_args) => {
+            cli::tournament::tournament(load_game_words(common)?, common, tournament_args)
+        }
+        Command::Experiment {
+            command: ExperimentCommand::Run { path, output },
+        } => cli::experiment::run(path, output.as_deref(), common)?,
+        Command::ExportTree(export_args) => {
+            cli::export::export_tree(load_game_words(common)?, common, export_args)
+        }
+        Command::Optimal(optimal_args) => {
+            cli::optimal::optimal(load_game_words(common)?, common, optimal_args)
+        }
+        Command::Compose(compose_args) => {
+            cli::compose::compose(load_game_words(common)?, common, compose_args)
+        }
+        Command::Fit(fit_args) => cli::fit::fit(load_guess_words(common)?, fit_args),
+        Command::Stats => cli::stats::stats(load_game_words(common)?, common),
+        Command::Grade(grade_args) => {
+            cli::grade::grade(load_game_words(common)?, common, grade_args)
+        }
+        Command::Import { path, opener } => cli::stats::import(path, opener.as_deref(), common),
+        Command::Results { path } => cli::results::print_results_file(path),
+        Command::Schema { name } => cli::schema::schema(name),
+        Command::Watch { address } => cli::spectate::watch(address),
+        Command::Selftest { bless } => cli::selftest::selftest(*bless)?,
+        Command::Update { feed, .. } => cli::update::check(feed.as_deref()),
+        Command::Words {
+            command: WordsCommand::Audit { path },
+        } => cli::audit::audit(load_word_list(path, common)?, path, common),
+        Command::Words {
+            command:
+                WordsCommand::Difficulty {
+                    path,
+                    output,
+                    max_attempts,
+                },
+        } => {
+            let answers = load_word_list(path, common)?;
+            let words = match &common.file {
+                Some(file) => WordLists::new(load_word_list(file, common)?, answers),
+                None => WordLists::single(answers),
+            };
+            cli::difficulty::difficulty(words, common, output.as_deref(), *max_attempts)
+        }
+        Command::Words {
+            command: WordsCommand::Compare { old, new },
+        } => cli::audit::compare(
+            load_word_list(old, common)?,
+            load_word_list(new, common)?,
+            (old, new),
+            common,
+        ),
     }
 
-    fn iter(&self) -> std::slice::Iter<char> {
-        self.chars.iter()
+    if profile::is_enabled() {
+        profile::print_profile();
+    }
+    // A program that stopped gracefully still exits like one stopped by the signal
+    if cli::input::was_interrupted() {
+        std::process::exit(130);
     }
 
-    fn len(&self) -> usize {
-        self.chars.len()
+    Ok(())
+}
+
+/// Use the variant's word size and attempt count where the command line does not give them
+fn apply_variant(args: &mut Cli, variant: Variant, matches: &ArgMatches) {
+    let defaults = |matches: &ArgMatches, id: &str| {
+        matches!(
+            matches.value_source(id),
+            None | Some(ValueSource::DefaultValue)
+        )
+    };
+    if defaults(matches, "word_size") {
+        args.common.word_size = variant.word_size();
+    }
+    let Some((_, matches)) = matches.subcommand() else {
+        return;
+    };
+    let max_attempts = match &mut args.command {
+        Command::Play(PlayArgs { max_attempts, .. })
+        | Command::Review(PlayArgs { max_attempts, .. })
+        | Command::Solve(SolveArgs { max_attempts, .. })
+        | Command::Bench(BenchArgs { max_attempts, .. })
+        | Command::Tournament(TournamentArgs { max_attempts, .. })
+        | Command::Optimal(OptimalArgs { max_attempts, .. })
+        | Command::ExportTree(ExportTreeArgs { max_attempts, .. })
+        | Command::Compose(ComposeArgs { max_attempts, .. })
+        | Command::Grade(GradeArgs { max_attempts, .. }) => max_attempts,
+        Command::Drill(_)
+        | Command::Experiment { .. }
+        | Command::Fit(_)
+        | Command::Stats
+        | Command::Import { .. }
+        | Command::Results { .. }
+        | Command::Schema { .. }
+        | Command::Watch { .. }
+        | Command::Selftest { .. }
+        | Command::Update { .. }
+        | Command::Words { .. } => return,
+    };
+    if defaults(matches, "max_attempts") {
+        *max_attempts = variant.max_attempts();
     }
 }
 
-impl fmt::Display for Word {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.chars.iter().collect::<String>())
+/// Parse a word size between 1 and `word::MAX_WORD_SIZE`
+fn parse_word_size(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(n) if (1..=word::MAX_WORD_SIZE).contains(&n) => Ok(n),
+        Ok(_) => Err(format!("must be between 1 and {}", word::MAX_WORD_SIZE)),
+        Err(e) => Err(e.to_string()),
     }
 }
 
-impl Iterator for Word {
-    type Item = char;
+/// Parse a probability between 0 and 1
+fn parse_probability(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(p) if (0.0..=1.0).contains(&p) => Ok(p),
+        Ok(_) => Err("must be between 0 and 1".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.chars.first().copied()
+/// Load the guesses the game is played with and the answers from --answers-file, which
+/// default to the guesses themselves
+fn load_game_words(common: &CommonArgs) -> Result<WordLists, io::Error> {
+    let guesses = load_guess_words(common)?;
+    match &common.answers_file {
+        Some(path) => {
+            let answers = word::load_words(path, common.word_size)?;
+            println!("Loaded {} unique answers", answers.len());
+            Ok(WordLists::new(guesses, answers))
+        }
+        None => Ok(WordLists::single(guesses)),
     }
 }
 
-/// A hint for a given letter
-#[derive(PartialEq, Eq, Hash, Copy, Clone, Debug)]
-enum LetterHint {
-    /// The letter is in the word and in the correct position
-    Correct,
-    /// The letter is in the word but in the wrong position
-    Misplaced,
-    /// The letter is not in the word
-    Incorrect,
+/// Load the allowed guesses: the --file if one was given, otherwise the variant's
+/// embedded list, otherwise words.txt
+fn load_guess_words(common: &CommonArgs) -> Result<Vec<Word>, io::Error> {
+    let embedded = common.variant.and_then(Variant::embedded_words);
+    match (&common.file, embedded) {
+        (Some(path), _) => load_word_list(path, common),
+        (None, Some(text)) => {
+            let words = word::parse_words(text, common.word_size);
+            println!("Loaded {} unique words", words.len());
+            Ok(words)
+        }
+        (None, None) => load_word_list("words.txt", common),
+    }
 }
 
-#[derive(Hash, Eq, PartialEq, Clone, Debug)]
-struct Hint {
-    letter_hints: Vec<LetterHint>,
+/// Load a word list and report how many words it contains
+fn load_word_list(path: &str, common: &CommonArgs) -> Result<Vec<Word>, io::Error> {
+    let words = word::load_words(path, common.word_size)?;
+    println!("Loaded {} unique words", words.len());
+    Ok(words)
 }
 
-impl Hint {
-    fn new(letter_hints: Vec<LetterHint>) -> Self {
-        Self { letter_hints }
+/// Prepare the console for colored output
+///
+/// Windows Terminal understands ANSI escape codes out of the box, but legacy conhost
+/// (and PowerShell running inside it) only does once virtual terminal processing has
+/// been enabled. If it cannot be enabled, colors are turned off for both the hints
+/// and the progress bar so that raw escape codes are never printed.
+#[cfg(windows)]
+fn init_console() {
+    if colored::control::set_virtual_terminal(true).is_err() {
+        colored::control::set_override(false);
+        console::set_colors_enabled(false);
     }
+}
 
-    fn from_string(hint: &str, guess: &Word) -> Result<Self, String> {
-        for (c, &w) in zip(hint.chars(), guess.iter()) {
-            if c != '*' && c != '_' && c.to_ascii_uppercase() != w {
-                return Err("Invalid hint character".to_string());
-            }
+#[cfg(not(windows))]
+fn init_console() {}
+
+/// Stop the program gracefully when it is interrupted by Ctrl-C or SIGTERM
+///
+/// Once a line reader is in use, the next read ends the input, so a game is recorded as
+/// quit and a solver session can be saved before exiting. Benchmarks, tournaments and tree
+/// building stop after the current game or branch and keep what they computed so far.
+/// Otherwise, or if the line editor is waiting for a key, or on a second interrupt, the
+/// program exits at once, after showing the cursor the progress bar hides and taking the
+/// terminal out of raw mode.
+fn install_signal_handler() {
+    let terminal = TerminalState::save();
+    let result = ctrlc::set_handler(move || {
+        let _ = console::Term::stderr().show_cursor();
+        let _ = console::Term::stdout().show_cursor();
+        if cli::input::interrupt() || !cli::input::can_stop_gracefully() {
+            terminal.restore();
+            println!();
+            std::process::exit(130);
         }
+        eprintln!("\nStopping after the current step. Press Ctrl-C again to exit now.");
+    });
 
-        let hint: Vec<LetterHint> = zip(hint.chars(), guess.iter())
-            .map(|(c, w)| match c {
-                _ if (c.to_ascii_uppercase() == *w) => LetterHint::Correct,
-                '*' => LetterHint::Misplaced,
-                '_' => LetterHint::Incorrect,
-                _ => panic!("This case should have been caught earlier"),
-            })
-            .collect::<Vec<LetterHint>>();
-
-        Ok(Self::new(hint))
+    if let Err(e) = result {
+        eprintln!("Warning: failed to install signal handler: {}", e);
     }
+}
 
-    fn from_guess_and_answer(guess: &Word, answer: &Word) -> Result<Self, String> {
-        if guess.len() != answer.len() {
-            return Err("Guess and answer must have the same length".to_string());
+/// The settings the terminal had when the program started
+#[cfg(unix)]
+struct TerminalState(Option<libc::termios>);
+
+#[cfg(unix)]
+impl TerminalState {
+    fn save() -> Self {
+        let mut termios = std::mem::MaybeUninit::<libc::termios>::uninit();
+        // SAFETY: tcgetattr only writes to `termios`, which is only read if it succeeded
+        let saved = unsafe {
+            (libc::tcgetattr(libc::STDIN_FILENO, termios.as_mut_ptr()) == 0)
+                .then(|| termios.assume_init())
         };
-        if !guess.iter().all(|c| c.is_alphabetic()) && !answer.iter().all(|c| c.is_alphabetic()) {
-            return Err("Guess and answer must contain only alphabetic characters".to_string());
-        }
-        let mut letter_hints: Vec<LetterHint> = vec![LetterHint::Incorrect; guess.len()];
-        let mut answer_chars = answer.chars.clone();
-
-        // First pass: Check for correct letters (LetterHint::Correct)
-        for (i, (g, a)) in zip(guess.iter(), answer.iter()).enumerate() {
-            if g == a {
-                letter_hints[i] = LetterHint::Correct;
-                answer_chars[i] = '_'; // Mark this character as used
-            }
-        }
-
-        // Second pass: Check for misplaced letters (LetterHint::Misplaced)
-        for (i, g) in guess.iter().enumerate() {
-            if letter_hints[i] == LetterHint::Correct {
-                continue; // Skip already correct letters
-            }
+        Self(saved)
+    }
 
-            if let Some(pos) = answer_chars.iter().position(|&a| a == *g) {
-                letter_hints[i] = LetterHint::Misplaced;
-                answer_chars[pos] = '_'; // Mark this character as used
+    fn restore(&self) {
+        if let Some(termios) = &self.0 {
+            // SAFETY: the settings were read from the same terminal by tcgetattr
+            unsafe {
+                libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, termios);
             }
         }
-
-        Ok(Self { letter_hints })
     }
+}
 
-    fn iter(&self) -> std::slice::Iter<LetterHint> {
-        self.letter_hints.iter()
-    }
+#[cfg(not(unix))]
+struct TerminalState;
 
-    fn len(&self) -> usize {
-        self.letter_hints.len()
+#[cfg(not(unix))]
+impl TerminalState {
+    fn save() -> Self {
+        Self
     }
+
+    fn restore(&self) {}
 }
 
-impl Iterator for Hint {
-    type Item = LetterHint;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        self.letter_hints.first().copied()
+    /// The word size and attempt count of `play` with the arguments after "rudle"
+    fn play_sizes(arguments: &[&str]) -> (usize, usize) {
+        let matches = Cli::command().get_matches_from([&["rudle"], arguments].concat());
+        let mut args = Cli::from_arg_matches(&matches).unwrap();
+        if let Some(variant) = args.common.variant {
+            apply_variant(&mut args, variant, &matches);
+        }
+        match args.command {
+            Command::Play(play_args) => (args.common.word_size, play_args.max_attempts),
+            _ => panic!("not a play command"),
+        }
     }
-}
 
-fn print_hint(hint: &Hint, guess: &Word) {
-    let colored_guess: Vec<String> = zip(guess.iter(), hint.iter())
-        .map(|(c, h)| match h {
-            LetterHint::Correct => c.to_string().green().to_string(),
-            LetterHint::Misplaced => c.to_string().yellow().to_string(),
-            LetterHint::Incorrect => c.to_string().white().to_string(),
-        })
-        .collect();
-    print!("{}", colored_guess.join(""));
+    #[test]
+    fn a_variant_sets_only_what_the_command_line_does_not() {
+        assert_eq!(play_sizes(&["--variant", "sixes", "play"]), (6, 7));
+        assert_eq!(
+            play_sizes(&["--variant", "sixes", "play", "--max-attempts", "9"]),
+            (6, 9)
+        );
+        assert_eq!(
+            play_sizes(&["play", "--word-size", "7", "--variant", "sixes"]),
+            (7, 7)
+        );
+        // Giving the default value still counts as giving it
+        assert_eq!(
+            play_sizes(&["--variant", "sixes", "--word-size", "5", "play"]),
+            (5, 7)
+        );
+    }
 }
@@ -0,0 +1,322 @@
+//! An exact search for the guesses that need the fewest guesses on average
+
+use crate::hint::Hint;
+use crate::solver::{progress_bar, HintMatrix};
+use crate::strategy::{Entropy, Strategy};
+use crate::word::Word;
+use std::collections::HashMap;
+
+/// The cost of a set of answers that cannot all be solved in the guesses left
+const UNSOLVABLE: u32 = u32::MAX / 2;
+
+/// What the search knows about a set of answers with a number of guesses left
+#[derive(Clone, Copy)]
+struct Node {
+    /// The total number of guesses over the answers, or a lower bound on it
+    cost: u32,
+    /// Whether `cost` is exact, in which case `guess` is the best guess
+    exact: bool,
+    guess: usize,
+}
+
+/// The policy that minimizes the expected number of guesses, found by searching the game tree
+///
+/// The search tries every guess against every set of answers it can reach. It skips a guess
+/// as soon as a lower bound on its cost (every answer needs one more guess, and at most one
+/// of them is solved by it) shows it cannot beat the best guess found so far, and remembers
+/// the cost of every set of answers it has solved. It is still exponential, so it is meant
+/// for small and medium word lists.
+pub struct OptimalSolver {
+    matrix: HintMatrix,
+    max_guesses: usize,
+    /// Keyed by the indices of the answers in the set and the number of guesses left
+    memo: HashMap<(Vec<u32>, usize), Node>,
+}
+
+/// The result of an optimal search
+pub struct OptimalSolution {
+    /// The best first guess
+    pub opener: Word,
+    /// The number of guesses needed over all answers, when each is played optimally
+    pub total_guesses: usize,
+    pub n_answers: usize,
+}
+
+impl OptimalSolution {
+    /// The expected number of guesses against an answer picked uniformly at random
+    pub fn expected_guesses(&self) -> f64 {
+        self.total_guesses as f64 / self.n_answers as f64
+    }
+}
+
+impl OptimalSolver {
+    /// Prepare a search over the word lists
+    /// # Returns
+    /// None if the lists are too large for a `HintMatrix`
+    pub fn new(
+        guesses: &[Word],
+        answers: &[Word],
+        max_guesses: usize,
+        show_progress: bool,
+    ) -> Option<Self> {
+        let matrix = HintMatrix::new(guesses, answers, show_progress)?;
+        Some(Self {
+            matrix,
+            max_guesses,
+            memo: HashMap::new(),
+        })
+    }
+
+    /// Search the game tree from the first guess
+    /// # Returns
+    /// None if some answer cannot be solved within `max_guesses`
+    pub fn solve(&mut self, show_progress: bool) -> Option<OptimalSolution> {
+        let answers: Vec<u32> = (0..self.matrix.answers().len() as u32).collect();
+        let pb = progress_bar(
+            self.matrix.guesses().len(),
+            show_progress,
+            "Searching for the optimal opener...",
+        );
+        let cost = self.cost(&answers, self.max_guesses, UNSOLVABLE, Some(&|| pb.inc(1)));
+        pb.finish_and_clear();
+        if cost >= UNSOLVABLE {
+            return None;
+        }
+
+        let opener = match answers.len() {
+            0 => return None,
+            1 | 2 => self.matrix.answers()[0].clone(),
+            _ => {
+                let node = self.memo[&(answers.clone(), self.max_guesses)];
+                self.matrix.guesses()[node.guess].clone()
+            }
+        };
+        Some(OptimalSolution {
+            opener,
+            total_guesses: cost as usize,
+            n_answers: answers.len(),
+        })
+    }
+
+    /// The fewest guesses needed over all of `answers` with `guesses_left` guesses
+    /// Returns some value of at least `limit` if the cost is not below `limit`.
+    fn cost(
+        &mut self,
+        answers: &[u32],
+        guesses_left: usize,
+        limit: u32,
+        on_candidate: Option<&dyn Fn()>,
+    ) -> u32 {
+        let n = answers.len() as u32;
+        match (n, guesses_left) {
+            (0, _) => return 0,
+            (_, 0) => return UNSOLVABLE,
+            (1, _) => return 1,
+            (_, 1) => return UNSOLVABLE,
+            // Guess either answer: one is solved right away and the other on the next guess
+            (2, _) => return 3,
+            _ => {}
+        }
+
+        let key = (answers.to_vec(), guesses_left);
+        if let Some(node) = self.memo.get(&key) {
+            if node.exact || node.cost >= limit {
+                return node.cost;
+            }
+        }
+
+        let solved_code = self.matrix.n_codes() - 1;
+        let lower_bound = |size: u32| match size {
+            0 => 0,
+            1 => 1,
+            _ if guesses_left == 2 => UNSOLVABLE,
+            _ => 2 * size - 1,
+        };
+
+        // Order the guesses by their lower bound, so the search finds good guesses early
+        // and can stop once no remaining guess could beat the best one
+        let mut counts = vec![0u32; self.matrix.n_codes()];
+        let mut candidates: Vec<(u32, usize)> = vec![];
+        for guess in 0..self.matrix.guesses().len() {
+            counts.iter_mut().for_each(|c| *c = 0);
+            for &answer in answers {
+                counts[self.matrix.code(guess, answer as usize)] += 1;
+            }
+            if counts.contains(&n) && counts[solved_code] == 0 {
+                continue;
+            }
+            let bound = counts
+                .iter()
+                .enumerate()
+                .filter(|&(code, _)| code != solved_code)
+                .map(|(_, &size)| lower_bound(size))
+                .fold(n, |total, b| total.saturating_add(b).min(UNSOLVABLE));
+            candidates.push((bound, guess));
+        }
+        candidates.sort();
+
+        let mut best = Node {
+            cost: UNSOLVABLE,
+            exact: false,
+            guess: 0,
+        };
+        for &(bound, guess) in candidates.iter() {
+            if let Some(on_candidate) = on_candidate {
+                on_candidate();
+            }
+            let limit = best.cost.min(limit);
+            if bound >= limit {
+                break;
+            }
+
+            let mut groups: HashMap<usize, Vec<u32>> = HashMap::new();
+            for &answer in answers {
+                let code = self.matrix.code(guess, answer as usize);
+                if code != solved_code {
+                    groups.entry(code).or_default().push(answer);
+                }
+            }
+            let mut groups: Vec<Vec<u32>> = groups.into_values().collect();
+            groups.sort_by_key(|g| std::cmp::Reverse(g.len()));
+
+            // Replace each group's lower bound with its cost until the total reaches the limit
+            let mut total = bound;
+            for group in groups.iter() {
+                let group_bound = lower_bound(group.len() as u32);
+                let group_limit = limit - (total - group_bound);
+                let group_cost = self.cost(group, guesses_left - 1, group_limit, None);
+                if group_cost >= group_limit {
+                    total = limit;
+                    break;
+                }
+                total = total - group_bound + group_cost;
+            }
+            if total < best.cost.min(limit) {
+                best = Node {
+                    cost: total,
+                    exact: true,
+                    guess,
+                };
+            }
+        }
+
+        // Without a guess below the limit, the limit is all that is known about the cost
+        let node = if best.exact {
+            best
+        } else {
+            Node {
+                cost: limit,
+                exact: false,
+                guess: 0,
+            }
+        };
+        let keep_existing = self.memo.get(&key).is_some_and(|old| old.exact);
+        if !keep_existing {
+            self.memo.insert(key, node);
+        }
+        node.cost
+    }
+}
+
+impl Strategy for OptimalSolver {
+    /// Play the guess the search found best, which `solve` must have been run for
+    /// Positions the search did not reach fall back to the entropy strategy.
+    fn choose(&self, guesses: &[Word], answers: &[Word], history: &[(Word, Hint)]) -> Option<Word> {
+        if answers.len() <= 2 {
+            return answers.first().cloned();
+        }
+
+        let indices: Option<Vec<u32>> = answers
+            .iter()
+            .map(|w| {
+                self.matrix
+                    .answers()
+                    .binary_search(w)
+                    .ok()
+                    .map(|i| i as u32)
+            })
+            .collect();
+        let guesses_left = self.max_guesses.saturating_sub(history.len());
+        indices
+            .and_then(|indices| self.memo.get(&(indices, guesses_left)))
+            .filter(|node| node.exact)
+            .map(|node| self.matrix.guesses()[node.guess].clone())
+            .or_else(|| Entropy.choose(guesses, answers, history))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<Word> {
+        let mut words: Vec<Word> = words
+            .iter()
+            .map(|w| Word::from_string(w).unwrap())
+            .collect();
+        words.sort();
+        words
+    }
+
+    /// The fewest guesses over all of `answers`, trying every guess at every turn
+    fn brute_force(guesses: &[Word], answers: &[Word], guesses_left: usize) -> Option<usize> {
+        if answers.is_empty() {
+            return Some(0);
+        }
+        if guesses_left == 0 {
+            return None;
+        }
+        guesses
+            .iter()
+            .filter_map(|guess| {
+                let mut groups: HashMap<u32, Vec<Word>> = HashMap::new();
+                for answer in answers {
+                    let hint = Hint::from_guess_and_answer(guess, answer).unwrap();
+                    groups
+                        .entry(hint.to_code())
+                        .or_default()
+                        .push(answer.clone());
+                }
+                groups
+                    .values()
+                    .try_fold(0, |total, group| match group[..] == [guess.clone()] {
+                        true => Some(total + 1),
+                        false => Some(
+                            total + group.len() + brute_force(guesses, group, guesses_left - 1)?,
+                        ),
+                    })
+            })
+            .min()
+    }
+
+    const ANSWERS: [&str; 7] = [
+        "batch", "catch", "hatch", "latch", "match", "patch", "watch",
+    ];
+
+    #[test]
+    fn the_search_needs_as_few_guesses_as_trying_everything() {
+        let answers = words(&ANSWERS);
+        let guesses = words(&[&ANSWERS[..], &["champ", "blows"]].concat());
+        for max_guesses in [3, 4] {
+            let solution = OptimalSolver::new(&guesses, &answers, max_guesses, false)
+                .unwrap()
+                .solve(false)
+                .unwrap();
+            assert_eq!(
+                Some(solution.total_guesses),
+                brute_force(&guesses, &answers, max_guesses)
+            );
+            assert_eq!(solution.n_answers, answers.len());
+        }
+    }
+
+    #[test]
+    fn answers_that_cannot_all_be_solved_in_time_have_no_solution() {
+        let answers = words(&ANSWERS);
+        for max_guesses in [1, 2] {
+            let mut solver = OptimalSolver::new(&answers, &answers, max_guesses, false).unwrap();
+            assert!(solver.solve(false).is_none());
+            assert_eq!(brute_force(&answers, &answers, max_guesses), None);
+        }
+    }
+}
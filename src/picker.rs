@@ -0,0 +1,380 @@
+//! Ways of picking the secret word of a game
+
+use crate::hash::{fnv1a, FNV_OFFSET};
+use crate::hint::Hint;
+use crate::word::Word;
+use rand::distributions::WeightedIndex;
+use rand::prelude::Distribution;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// A way of picking the secret word of a game
+pub trait AnswerPicker {
+    /// Pick the secret word for a new game
+    /// # Returns
+    /// The secret word, or None if there is no answer to pick
+    fn pick(&mut self, answers: &[Word]) -> Option<Word>;
+
+    /// Choose the secret word a guess is scored against
+    /// Pickers that commit to their secret word keep it, which is the default.
+    /// # Arguments
+    /// * `secret` - The secret word so far
+    /// * `guess` - The guess about to be scored
+    /// * `remaining` - The answers consistent with every hint so far, including `secret`
+    fn respond(&mut self, secret: &Word, _guess: &Word, _remaining: &[Word]) -> Word {
+        secret.clone()
+    }
+}
+
+/// Pick every answer with the same probability
+pub struct Uniform {
+    rng: StdRng,
+}
+
+impl Uniform {
+    pub fn new(rng: StdRng) -> Self {
+        Self { rng }
+    }
+}
+
+impl AnswerPicker for Uniform {
+    fn pick(&mut self, answers: &[Word]) -> Option<Word> {
+        answers.choose(&mut self.rng).cloned()
+    }
+}
+
+/// Pick answers in proportion to their weights, with a weight of 1 for unlisted answers
+pub struct Weighted {
+    rng: StdRng,
+    weights: HashMap<Word, f64>,
+}
+
+impl Weighted {
+    pub fn new(rng: StdRng, weights: HashMap<Word, f64>) -> Self {
+        Self { rng, weights }
+    }
+}
+
+impl AnswerPicker for Weighted {
+    fn pick(&mut self, answers: &[Word]) -> Option<Word> {
+        let weights = answers
+            .iter()
+            .map(|w| self.weights.get(w).copied().unwrap_or(1.0).max(0.0));
+        let distribution = WeightedIndex::new(weights).ok()?;
+        Some(answers[distribution.sample(&mut self.rng)].clone())
+    }
+}
+
+/// Pick the same answer for everyone on the same day
+/// The answer is chosen by a stable hash of the day and the answer list, so everyone playing
+/// the same list gets the same word whichever build of Rudle they run.
+pub struct Daily {
+    day: u64,
+}
+
+impl Daily {
+    /// # Arguments
+    /// * `day` - The number of days since the Unix epoch
+    pub fn new(day: u64) -> Self {
+        Self { day }
+    }
+}
+
+impl AnswerPicker for Daily {
+    fn pick(&mut self, answers: &[Word]) -> Option<Word> {
+        let mut hash = fnv1a(FNV_OFFSET, &self.day.to_le_bytes());
+        for answer in answers.iter() {
+            hash = fnv1a(fnv1a(hash, answer.as_bytes()), b"\n");
+        }
+        answers
+            .get((hash % answers.len().max(1) as u64) as usize)
+            .cloned()
+    }
+}
+
+/// Pick uniformly among the answers that have not been played yet
+/// Once every answer has been played, any answer may be picked again.
+pub struct NeverRepeat {
+    rng: StdRng,
+    played: HashSet<Word>,
+}
+
+impl NeverRepeat {
+    pub fn new(rng: StdRng, played: HashSet<Word>) -> Self {
+        Self { rng, played }
+    }
+}
+
+impl AnswerPicker for NeverRepeat {
+    fn pick(&mut self, answers: &[Word]) -> Option<Word> {
+        let fresh: Vec<&Word> = answers
+            .iter()
+            .filter(|w| !self.played.contains(*w))
+            .collect();
+        let secret = match fresh.choose(&mut self.rng) {
+            Some(word) => (*word).clone(),
+            None => answers.choose(&mut self.rng)?.clone(),
+        };
+        self.played.insert(secret.clone());
+        Some(secret)
+    }
+}
+
+/// Avoid committing to a secret word, answering every guess with the hint that keeps the
+/// most answers possible
+pub struct Adversarial;
+
+impl AnswerPicker for Adversarial {
+    fn pick(&mut self, answers: &[Word]) -> Option<Word> {
+        answers.first().cloned()
+    }
+
+    fn respond(&mut self, secret: &Word, guess: &Word, remaining: &[Word]) -> Word {
+        let mut groups: HashMap<u32, Vec<&Word>> = HashMap::new();
+        for answer in remaining.iter() {
+            if let Ok(hint) = Hint::from_guess_and_answer(guess, answer) {
+                groups.entry(hint.to_code()).or_default().push(answer);
+            }
+        }
+
+        // Prefer the largest group, then not solving the game, then the fewest correct letters
+        groups
+            .into_iter()
+            .max_by(|(code_a, a), (code_b, b)| {
+                let unsolved = |group: &[&Word]| group != [guess];
+                a.len()
+                    .cmp(&b.len())
+                    .then(unsolved(a).cmp(&unsolved(b)))
+                    .then(code_b.cmp(code_a))
+            })
+            .map(|(_, group)| match group.contains(&secret) {
+                true => secret.clone(),
+                false => group[0].clone(),
+            })
+            .unwrap_or_else(|| secret.clone())
+    }
+}
+
+/// Pick answers about as hard as the player can handle, judged by their recent win rate
+///
+/// Answers are ranked by how hard they are: rarer words (by their weights, or else by how
+/// rare their letters are) and words with a repeated letter are harder. The secret word is
+/// picked among the answers around the same quantile of that ranking as the win rate, so a
+/// player who wins 90% of their games gets words harder than 90% of the answers.
+pub struct Adaptive {
+    rng: StdRng,
+    difficulty: f64,
+    weights: HashMap<Word, f64>,
+    guesses_needed: HashMap<Word, f64>,
+}
+
+/// How much a repeated letter adds to the difficulty of an answer, out of 1
+const REPEAT_DIFFICULTY: f64 = 0.3;
+
+/// The share of the answers `Adaptive` picks among, centered on the target difficulty
+const ADAPTIVE_WINDOW: f64 = 0.2;
+
+impl Adaptive {
+    /// # Arguments
+    /// * `difficulty` - How hard the answers should be, from 0 (easiest) to 1 (hardest)
+    /// * `weights` - How common each answer is, or empty to judge rarity by the letters
+    pub fn new(rng: StdRng, difficulty: f64, weights: HashMap<Word, f64>) -> Self {
+        Self {
+            rng,
+            difficulty: difficulty.clamp(0.0, 1.0),
+            weights,
+            guesses_needed: HashMap::new(),
+        }
+    }
+
+    /// Rank the answers by the guesses a solver needed for each instead of by rarity
+    /// Answers missing from `guesses_needed` rank among the hardest.
+    pub fn with_guesses_needed(mut self, guesses_needed: HashMap<Word, f64>) -> Self {
+        self.guesses_needed = guesses_needed;
+        self
+    }
+
+    /// Rank the answers from the easiest to the hardest
+    fn rank(&self, answers: &[Word]) -> Vec<Word> {
+        if !self.guesses_needed.is_empty() {
+            let needed = |w: &Word| self.guesses_needed.get(w).copied().unwrap_or(f64::MAX);
+            let mut ranked = answers.to_vec();
+            ranked.sort_by(|a, b| needed(a).total_cmp(&needed(b)).then(a.cmp(b)));
+            return ranked;
+        }
+
+        // Without weights, a word is as common as the letters it is made of
+        let mut containing: HashMap<char, usize> = HashMap::new();
+        for answer in answers.iter() {
+            let letters: HashSet<char> = answer.iter().collect();
+            for c in letters {
+                *containing.entry(c).or_insert(0) += 1;
+            }
+        }
+        let commonness = |w: &Word| match self.weights.is_empty() {
+            false => self.weights.get(w).copied().unwrap_or(1.0),
+            true => {
+                let letters: HashSet<char> = w.iter().collect();
+                letters.iter().map(|c| containing[c] as f64).sum()
+            }
+        };
+
+        let mut by_rarity: Vec<(&Word, f64)> = answers.iter().map(|w| (w, commonness(w))).collect();
+        by_rarity.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(b.0)));
+        let n = by_rarity.len().max(2) as f64 - 1.0;
+        let mut ranked: Vec<(f64, &Word)> = by_rarity
+            .into_iter()
+            .enumerate()
+            .map(|(i, (w, _))| {
+                let repeated = w.iter().collect::<HashSet<char>>().len() < w.len();
+                let rarity = i as f64 / n;
+                let repeat = if repeated { REPEAT_DIFFICULTY } else { 0.0 };
+                (rarity * (1.0 - REPEAT_DIFFICULTY) + repeat, w)
+            })
+            .collect();
+        ranked.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(b.1)));
+        ranked.into_iter().map(|(_, w)| w.clone()).collect()
+    }
+}
+
+impl AnswerPicker for Adaptive {
+    fn pick(&mut self, answers: &[Word]) -> Option<Word> {
+        let ranked = self.rank(answers);
+        let last = ranked.len().checked_sub(1)? as f64;
+        let low = ((self.difficulty - ADAPTIVE_WINDOW / 2.0).max(0.0) * last).floor() as usize;
+        let high = ((self.difficulty + ADAPTIVE_WINDOW / 2.0).min(1.0) * last).ceil() as usize;
+        ranked[low..=high].choose(&mut self.rng).cloned()
+    }
+}
+
+/// The built-in ways of picking the secret word, as selected on the command line
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum PickerKind {
+    Uniform,
+    Weighted,
+    Daily,
+    NeverRepeat,
+    Adversarial,
+    Adaptive,
+}
+
+impl PickerKind {
+    pub const ALL: [PickerKind; 6] = [
+        PickerKind::Uniform,
+        PickerKind::Weighted,
+        PickerKind::Daily,
+        PickerKind::NeverRepeat,
+        PickerKind::Adversarial,
+        PickerKind::Adaptive,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PickerKind::Uniform => "uniform",
+            PickerKind::Weighted => "weighted",
+            PickerKind::Daily => "daily",
+            PickerKind::NeverRepeat => "never-repeat",
+            PickerKind::Adversarial => "adversarial",
+            PickerKind::Adaptive => "adaptive",
+        }
+    }
+}
+
+impl fmt::Display for PickerKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for PickerKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PickerKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = PickerKind::ALL.iter().map(|k| k.name()).collect();
+                format!(
+                    "unknown answer picker '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    fn words(words: &[&str]) -> Vec<Word> {
+        words
+            .iter()
+            .map(|w| Word::from_string(w).unwrap())
+            .collect()
+    }
+
+    const ANSWERS: [&str; 12] = [
+        "baker", "cater", "eater", "hater", "later", "match", "patch", "rider", "tamer", "taper",
+        "water", "wider",
+    ];
+
+    #[test]
+    fn daily_picks_the_same_word_for_the_same_day_and_list() {
+        let answers = words(&ANSWERS);
+        let today = Daily::new(20_000).pick(&answers);
+        assert!(today.is_some());
+        assert_eq!(Daily::new(20_000).pick(&answers), today);
+        let mut daily = Daily::new(20_000);
+        assert_eq!(daily.pick(&answers), daily.pick(&answers));
+        // Over a month the day changes the word
+        let picks: HashSet<Word> = (20_000..20_030)
+            .filter_map(|day| Daily::new(day).pick(&answers))
+            .collect();
+        assert!(picks.len() > 1);
+        assert_eq!(Daily::new(20_000).pick(&[]), None);
+    }
+
+    #[test]
+    fn never_repeat_plays_every_answer_before_repeating_one() {
+        let answers = words(&ANSWERS);
+        let mut picker = NeverRepeat::new(StdRng::seed_from_u64(7), HashSet::new());
+        let picks: HashSet<Word> = (0..answers.len())
+            .map(|_| picker.pick(&answers).unwrap())
+            .collect();
+        assert_eq!(picks.len(), answers.len());
+        // Once the list is exhausted, answers are picked again
+        assert!(picker.pick(&answers).is_some());
+
+        let played: HashSet<Word> = answers[1..].iter().cloned().collect();
+        let mut picker = NeverRepeat::new(StdRng::seed_from_u64(7), played);
+        assert_eq!(picker.pick(&answers), Some(answers[0].clone()));
+    }
+
+    #[test]
+    fn adversarial_stays_consistent_with_every_hint_it_gave() {
+        let answers = words(&ANSWERS);
+        let mut picker = Adversarial;
+        let mut secret = picker.pick(&answers).unwrap();
+        let mut remaining = answers.clone();
+        let mut board: Vec<(Word, Hint)> = vec![];
+        for guess in words(&["later", "patch", "water", "baker"]) {
+            secret = picker.respond(&secret, &guess, &remaining);
+            assert!(remaining.contains(&secret));
+            let hint = Hint::from_guess_and_answer(&guess, &secret).unwrap();
+            remaining.retain(|w| Hint::from_guess_and_answer(&guess, w).as_ref() == Ok(&hint));
+            board.push((guess, hint));
+            for (guess, hint) in board.iter() {
+                assert_eq!(
+                    Hint::from_guess_and_answer(guess, &secret).as_ref(),
+                    Ok(hint)
+                );
+            }
+        }
+    }
+}
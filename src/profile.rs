@@ -0,0 +1,88 @@
+//! Lightweight timing of the solver's hot paths, reported by `--profile`
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Instant;
+
+/// A kind of work that `--profile` reports the time spent on
+#[derive(Clone, Copy, Debug)]
+pub enum Phase {
+    HintComputation,
+    HintMatrix,
+    Partitioning,
+    Scoring,
+    Sorting,
+}
+
+impl Phase {
+    /// Every phase, in the order they are reported
+    pub const ALL: [Phase; 5] = [
+        Phase::HintComputation,
+        Phase::HintMatrix,
+        Phase::Partitioning,
+        Phase::Scoring,
+        Phase::Sorting,
+    ];
+
+    /// A human-readable name for the phase
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::HintComputation => "hint computation",
+            Phase::HintMatrix => "hint matrix",
+            Phase::Partitioning => "partitioning",
+            Phase::Scoring => "scoring",
+            Phase::Sorting => "sorting",
+        }
+    }
+}
+
+static PROFILING: AtomicBool = AtomicBool::new(false);
+static PHASE_NANOS: [AtomicU64; 5] = [const { AtomicU64::new(0) }; 5];
+static PHASE_CALLS: [AtomicU64; 5] = [const { AtomicU64::new(0) }; 5];
+
+/// Turn timing of the profiled phases on or off for the whole process
+pub fn set_enabled(enabled: bool) {
+    PROFILING.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether the profiled phases are being timed
+pub fn is_enabled() -> bool {
+    PROFILING.load(Ordering::Relaxed)
+}
+
+/// Run `f`, adding its running time to the phase's total when profiling is enabled
+pub fn profiled<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+    if !PROFILING.load(Ordering::Relaxed) {
+        return f();
+    }
+
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed().as_nanos() as u64;
+    PHASE_NANOS[phase as usize].fetch_add(elapsed, Ordering::Relaxed);
+    PHASE_CALLS[phase as usize].fetch_add(1, Ordering::Relaxed);
+    result
+}
+
+/// Print the time spent in each phase
+/// Times are summed across threads. Phases nest: hint computation is also counted within
+/// the hint matrix, scoring and partitioning.
+pub fn print_profile() {
+    println!("\nPhase            |       Calls |    Total (s) |    Mean (µs)");
+    println!("-----------------|-------------|--------------|-------------");
+    for phase in Phase::ALL {
+        let nanos = PHASE_NANOS[phase as usize].load(Ordering::Relaxed);
+        let calls = PHASE_CALLS[phase as usize].load(Ordering::Relaxed);
+        let mean = if calls > 0 {
+            nanos as f64 / calls as f64 / 1000.0
+        } else {
+            0.0
+        };
+        println!(
+            "{:<16} | {:>11} | {:>12.3} | {:>12.3}",
+            phase.name(),
+            calls,
+            nanos as f64 / 1e9,
+            mean
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Which guesses are allowed under the easy, hard and ultra-hard rules
+
+use crate::error::RuleViolation;
+use crate::hint::{Hint, LetterHint};
+use crate::word::Word;
+use std::collections::HashMap;
+use std::fmt;
+use std::iter::zip;
+use std::str::FromStr;
+
+/// The rules a guess must follow, given the hints of the earlier guesses
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Ruleset {
+    /// Any word in the word list may be guessed
+    Easy,
+    /// Correct letters must be reused in place and misplaced letters must be reused
+    Hard,
+    /// Every guess must be a possible answer given all of the hints so far
+    UltraHard,
+}
+
+impl Ruleset {
+    pub const ALL: [Ruleset; 3] = [Ruleset::Easy, Ruleset::Hard, Ruleset::UltraHard];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Ruleset::Easy => "easy",
+            Ruleset::Hard => "hard",
+            Ruleset::UltraHard => "ultra-hard",
+        }
+    }
+
+    /// Check whether a guess is allowed after the given guesses and their hints
+    /// Whether the guess is in the word list is left to the caller.
+    pub fn check(self, guess: &Word, history: &[(Word, Hint)]) -> Result<(), RuleViolation> {
+        if self == Ruleset::Easy {
+            return Ok(());
+        }
+
+        for (previous, hint) in history.iter() {
+            check_revealed_letters(guess, previous, hint)?;
+        }
+
+        if self == Ruleset::UltraHard {
+            for (previous, hint) in history.iter() {
+                if Hint::from_guess_and_answer(previous, guess).as_ref() != Ok(hint) {
+                    return Err(RuleViolation::Inconsistent {
+                        guess: previous.to_string(),
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Check the hard mode rules for a single earlier guess
+fn check_revealed_letters(guess: &Word, previous: &Word, hint: &Hint) -> Result<(), RuleViolation> {
+    let mut revealed: HashMap<char, usize> = HashMap::new();
+    for (position, ((letter, &h), g)) in
+        zip(zip(previous.iter(), hint.iter()), guess.iter()).enumerate()
+    {
+        if h == LetterHint::Correct && g != letter {
+            return Err(RuleViolation::MissingCorrect { position, letter });
+        }
+        if h != LetterHint::Incorrect {
+            *revealed.entry(letter).or_insert(0) += 1;
+        }
+    }
+
+    // Report missing letters in the order they were guessed
+    for letter in previous.iter() {
+        let count = revealed.get(&letter).copied().unwrap_or(0);
+        if guess.iter().filter(|&c| c == letter).count() < count {
+            return Err(RuleViolation::MissingMisplaced { letter, count });
+        }
+    }
+
+    Ok(())
+}
+
+impl fmt::Display for Ruleset {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Ruleset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ruleset::ALL
+            .into_iter()
+            .find(|ruleset| ruleset.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Ruleset::ALL.iter().map(|r| r.name()).collect();
+                format!(
+                    "unknown mode '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str) -> Word {
+        Word::from_string(word).unwrap()
+    }
+
+    /// A guess with the hint it got against the answer
+    fn row(guess: &str, answer: &str) -> (Word, Hint) {
+        let hint = Hint::from_guess_and_answer(&word(guess), &word(answer)).unwrap();
+        (word(guess), hint)
+    }
+
+    #[test]
+    fn a_repeated_letter_marked_misplaced_and_absent_must_be_reused_once() {
+        // Only the first E of EERIE is in CREPT, so the second is marked absent
+        let history = [row("eerie", "crept")];
+        assert_eq!(
+            history[0].1.iter().take(2).collect::<Vec<_>>(),
+            [&LetterHint::Misplaced, &LetterHint::Incorrect]
+        );
+        assert_eq!(Ruleset::Hard.check(&word("trend"), &history), Ok(()));
+        assert_eq!(Ruleset::Hard.check(&word("reeve"), &history), Ok(()));
+        assert_eq!(
+            Ruleset::Hard.check(&word("train"), &history),
+            Err(RuleViolation::MissingMisplaced {
+                letter: 'E',
+                count: 1
+            })
+        );
+    }
+
+    #[test]
+    fn a_correct_letter_must_stay_in_place() {
+        let history = [row("crane", "crust")];
+        assert_eq!(Ruleset::Hard.check(&word("crumb"), &history), Ok(()));
+        assert_eq!(
+            Ruleset::Hard.check(&word("scrub"), &history),
+            Err(RuleViolation::MissingCorrect {
+                position: 0,
+                letter: 'C'
+            })
+        );
+        assert_eq!(Ruleset::Easy.check(&word("scrub"), &history), Ok(()));
+    }
+
+    #[test]
+    fn ultra_hard_mode_rejects_a_misplaced_letter_in_the_same_slot() {
+        // The C of CRANE is misplaced, so CRATE reuses it where it cannot be
+        let history = [row("crane", "trace")];
+        assert_eq!(Ruleset::Hard.check(&word("crate"), &history), Ok(()));
+        assert_eq!(
+            Ruleset::UltraHard.check(&word("crate"), &history),
+            Err(RuleViolation::Inconsistent {
+                guess: "CRANE".to_string()
+            })
+        );
+        assert_eq!(Ruleset::UltraHard.check(&word("trace"), &history), Ok(()));
+    }
+}
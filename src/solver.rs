@@ -0,0 +1,839 @@
+//! Scoring guesses and tracking the answers that remain possible
+
+use crate::hint::Hint;
+use crate::profile::{profiled, Phase};
+use crate::word::Word;
+#[cfg(feature = "cli")]
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// The possible answers after each hint, stored as one alive-bitset per generation
+///
+/// Generation 0 has every word alive and each applied hint pushes a new generation, so
+/// undoing a hint is just dropping the newest bitset and ruled-out words are never copied.
+pub struct AnswerSet {
+    words: Vec<Word>,
+    generations: Vec<Vec<u64>>,
+    counts: Vec<usize>,
+}
+
+impl AnswerSet {
+    /// Create a set with every word alive
+    /// `words` must be sorted, as returned by `load_words`.
+    pub fn new(words: Vec<Word>) -> Self {
+        let mut alive = vec![u64::MAX; words.len().div_ceil(64)];
+        if !words.len().is_multiple_of(64) {
+            if let Some(last) = alive.last_mut() {
+                *last = (1 << (words.len() % 64)) - 1;
+            }
+        }
+        let count = words.len();
+        Self {
+            words,
+            generations: vec![alive],
+            counts: vec![count],
+        }
+    }
+
+    fn is_alive(&self, i: usize) -> bool {
+        let alive = self
+            .generations
+            .last()
+            .expect("AnswerSet has no generations");
+        alive[i / 64] & (1 << (i % 64)) != 0
+    }
+
+    /// The number of answers still possible
+    pub fn len(&self) -> usize {
+        *self.counts.last().expect("AnswerSet has no generations")
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The number of answers removed by each hint, oldest first
+    pub fn removed_per_generation(&self) -> Vec<usize> {
+        self.counts.windows(2).map(|w| w[0] - w[1]).collect()
+    }
+
+    /// Whether the word is still a possible answer
+    pub fn contains(&self, word: &Word) -> bool {
+        // load_words sorts the list, so alive words can be found by binary search
+        self.words
+            .binary_search(word)
+            .is_ok_and(|i| self.is_alive(i))
+    }
+
+    /// Every answer the set started with, including the ones ruled out since
+    pub fn all(&self) -> &[Word] {
+        &self.words
+    }
+
+    /// Iterate over the answers that are still possible, in sorted order
+    pub fn iter(&self) -> impl Iterator<Item = &Word> {
+        self.words
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| self.is_alive(*i))
+            .map(|(_, w)| w)
+    }
+
+    /// Collect the answers that are still possible
+    pub fn to_vec(&self) -> Vec<Word> {
+        self.iter().cloned().collect()
+    }
+
+    /// Start a new generation keeping only the answers for which `keep` is true
+    /// # Returns
+    /// The number of answers that were removed
+    pub fn retain(&mut self, keep: impl Fn(&Word) -> bool) -> usize {
+        let mut alive = self
+            .generations
+            .last()
+            .expect("AnswerSet has no generations")
+            .clone();
+        profiled(Phase::Partitioning, || {
+            for (i, word) in self.words.iter().enumerate() {
+                if alive[i / 64] & (1 << (i % 64)) != 0 && !keep(word) {
+                    alive[i / 64] &= !(1 << (i % 64));
+                }
+            }
+        });
+        let count = alive.iter().map(|bits| bits.count_ones() as usize).sum();
+        let removed = self.len() - count;
+        self.generations.push(alive);
+        self.counts.push(count);
+        removed
+    }
+
+    /// Drop the newest generation, restoring the answers it removed
+    /// # Returns
+    /// False if there is no generation left to undo
+    pub fn undo(&mut self) -> bool {
+        if self.generations.len() == 1 {
+            return false;
+        }
+        self.generations.pop();
+        self.counts.pop();
+        true
+    }
+}
+
+/// Score every guess by how much it narrows down the answers
+///
+/// Each entry is `(guess, expected, worst_case)`: the expected percentage of the answers a
+/// guess eliminates (derived from the entropy of its hint distribution) and the percentage
+/// it eliminates in the worst case. Entries are sorted by expected score, best first, with
+/// ties broken alphabetically.
+/// # Arguments
+/// * `guesses` - The words that may be guessed
+/// * `answers` - The words that may still be the answer
+/// * `show_progress` - Whether to draw a progress bar while scoring
+pub fn get_scores(
+    guesses: &[Word],
+    answers: &[Word],
+    show_progress: bool,
+) -> Vec<(Word, f32, f32)> {
+    get_scores_within(guesses, answers, show_progress, None).0
+}
+
+/// Score guesses like `get_scores`, stopping once the time budget runs out
+///
+/// With a budget, guesses are scored in order of `rank_by_coverage` so that the guesses most
+/// likely to be good are scored first, and guesses that were not reached in time are left
+/// out of the result.
+/// # Returns
+/// The sorted scores, and whether every guess was scored
+pub fn get_scores_within(
+    guesses: &[Word],
+    answers: &[Word],
+    show_progress: bool,
+    budget: Option<Duration>,
+) -> (Vec<(Word, f32, f32)>, bool) {
+    score_guesses(guesses, answers, show_progress, budget, |guess| {
+        let mut hint_counts = HashMap::new();
+
+        // Accumulate frequencies for all possible answers, keyed by the packed hint
+        for answer in answers.iter() {
+            let code = Hint::from_guess_and_answer(guess, answer).map(|h| h.to_code());
+            *hint_counts.entry(code.ok()).or_insert(0.0) += 1.0;
+        }
+        hint_counts.into_values().collect()
+    })
+}
+
+/// Score guesses like `get_scores_within`, counting each answer by its weight
+///
+/// With the weights as a prior over the answers, the scores are the expected and worst-case
+/// percentage of the answers' total weight a guess eliminates, so splitting up the likely
+/// answers counts for more than splitting up the obscure ones. Answers missing from `weights`
+/// weigh 1.
+pub fn get_weighted_scores_within(
+    guesses: &[Word],
+    answers: &[Word],
+    weights: &HashMap<Word, f64>,
+    show_progress: bool,
+    budget: Option<Duration>,
+) -> (Vec<(Word, f32, f32)>, bool) {
+    let answer_weights: Vec<f32> = answers
+        .iter()
+        .map(|w| weights.get(w).copied().unwrap_or(1.0).max(0.0) as f32)
+        .collect();
+    score_guesses(guesses, answers, show_progress, budget, |guess| {
+        let mut hint_weights = HashMap::new();
+        for (answer, &weight) in answers.iter().zip(answer_weights.iter()) {
+            let code = Hint::from_guess_and_answer(guess, answer).map(|h| h.to_code());
+            *hint_weights.entry(code.ok()).or_insert(0.0) += weight;
+        }
+        hint_weights.into_values().collect()
+    })
+}
+
+/// Order guesses by a cheap estimate of how well they split the answers, best first
+///
+/// A letter splits the answers best when it is in half of them, so each distinct letter of
+/// a guess earns the size of the smaller side of the split it makes, and likewise for each
+/// letter in its position. This takes one pass over the answers rather than computing a hint
+/// for every pair, which makes it useful for picking which guesses to score fully first.
+pub fn rank_by_coverage(guesses: &[Word], answers: &[Word]) -> Vec<Word> {
+    let mut containing: HashMap<char, usize> = HashMap::new();
+    let mut in_position: HashMap<(usize, char), usize> = HashMap::new();
+    for answer in answers.iter() {
+        let mut seen = vec![];
+        for (i, c) in answer.iter().enumerate() {
+            *in_position.entry((i, c)).or_insert(0) += 1;
+            if !seen.contains(&c) {
+                seen.push(c);
+                *containing.entry(c).or_insert(0) += 1;
+            }
+        }
+    }
+
+    let split = |count: Option<&usize>| {
+        let count = count.copied().unwrap_or(0);
+        count.min(answers.len() - count)
+    };
+    let coverage = |guess: &Word| {
+        let mut seen = vec![];
+        let mut total = 0;
+        for (i, c) in guess.iter().enumerate() {
+            total += split(in_position.get(&(i, c)));
+            if !seen.contains(&c) {
+                seen.push(c);
+                total += split(containing.get(&c));
+            }
+        }
+        total
+    };
+
+    let mut ranked: Vec<(usize, &Word)> = guesses.iter().map(|g| (coverage(g), g)).collect();
+    ranked.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| Ord::cmp(a.1, b.1)));
+    ranked.into_iter().map(|(_, g)| g.clone()).collect()
+}
+
+/// The number of guesses `prefilter` keeps for full scoring
+pub const PREFILTER_SIZE: usize = 2000;
+
+/// Pick the guesses worth scoring fully: the `n` best by `rank_by_coverage`, plus every guess
+/// that could still be the answer so that those can always be ranked
+/// The guesses are returned in their original order.
+pub fn prefilter(guesses: &[Word], answers: &[Word], n: usize) -> Vec<Word> {
+    if guesses.len() <= n {
+        return guesses.to_vec();
+    }
+
+    let mut candidates: Vec<Word> = rank_by_coverage(guesses, answers)
+        .into_iter()
+        .take(n)
+        .collect();
+    candidates.sort();
+    guesses
+        .iter()
+        .filter(|w| candidates.binary_search(w).is_ok() || answers.contains(w))
+        .cloned()
+        .collect()
+}
+
+/// Score guesses from the number of answers that give each of their hints, in parallel
+/// `count_hints` returns the size of every group of answers that share a hint for the guess,
+/// or its total weight when the answers are weighted.
+fn score_guesses(
+    guesses: &[Word],
+    answers: &[Word],
+    show_progress: bool,
+    budget: Option<Duration>,
+    count_hints: impl Fn(&Word) -> Vec<f32> + Sync,
+) -> (Vec<(Word, f32, f32)>, bool) {
+    let pb = progress_bar(
+        guesses.len(),
+        show_progress,
+        "Calculating new word scores...",
+    );
+
+    // Without a budget everything is scored in one go. With one, the most promising guesses
+    // are scored in batches, checking the clock between batches.
+    let deadline = budget.map(|budget| Instant::now() + budget);
+    let ranked;
+    let batches: Vec<&[Word]> = match deadline {
+        None => vec![guesses],
+        Some(_) => {
+            ranked = rank_by_coverage(guesses, answers);
+            ranked.chunks(1000).collect()
+        }
+    };
+
+    let mut scores: Vec<(Word, f32, f32)> = Vec::with_capacity(guesses.len());
+    for batch in batches {
+        if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            break;
+        }
+
+        // Process words in chunks of size 100 in parallel
+        let batch_scores: Vec<(Word, f32, f32)> = profiled(Phase::Scoring, || {
+            batch
+                .par_chunks(100)
+                .map(|chunk| {
+                    let mut chunk_scores = Vec::with_capacity(chunk.len());
+
+                    // Process each word in the current chunk (sequentially here)
+                    for guess in chunk {
+                        let hint_counts = count_hints(guess);
+                        let total = hint_counts.iter().sum::<f32>();
+
+                        // Calculate score using the accumulated frequencies
+                        let entropy = -hint_counts
+                            .iter()
+                            .filter(|&&c| c > 0.0)
+                            .map(|&c| c / total)
+                            .map(|p| p * f32::ln(p))
+                            .sum::<f32>();
+
+                        let min_score = hint_counts
+                            .iter()
+                            .map(|&c| 100.0 * (1.0 - c / total))
+                            .fold(100.0_f32, |a, b| a.min(b));
+
+                        let avg_score = (1.0 - f32::exp(-entropy)) * 100.0;
+                        chunk_scores.push((guess.clone(), avg_score, min_score));
+                    }
+
+                    // To reduce contention, update once per chunk
+                    pb.inc(chunk.len() as u64);
+
+                    chunk_scores
+                })
+                .flat_map_iter(|chunk_scores| chunk_scores)
+                .collect()
+        });
+        scores.extend(batch_scores);
+    }
+
+    let complete = scores.len() == guesses.len();
+    if complete {
+        pb.finish_with_message("Scoring complete!");
+    } else {
+        pb.abandon_with_message("Time budget reached.");
+    }
+
+    let mut sorted_scores = scores;
+    // Sort by score descending, breaking ties alphabetically
+    profiled(Phase::Sorting, || {
+        sorted_scores.sort_by(|a, b| {
+            b.1.partial_cmp(&a.1)
+                .unwrap()
+                .then_with(|| Ord::cmp(&a.0, &b.0))
+        })
+    });
+    (sorted_scores, complete)
+}
+
+/// Create a progress bar, printing `message` above it, or a hidden one
+#[cfg(feature = "cli")]
+pub(crate) fn progress_bar(len: usize, show_progress: bool, message: &str) -> ProgressBar {
+    let pb = if show_progress {
+        println!("{}", message);
+        ProgressBar::new(len as u64)
+    } else {
+        ProgressBar::hidden()
+    };
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} {msg}")
+            .expect("Invalid progress bar template")
+            .progress_chars("##-"),
+    );
+    pb
+}
+
+/// Without the `cli` feature there is no terminal to draw in, so progress is never shown
+#[cfg(not(feature = "cli"))]
+pub(crate) fn progress_bar(_len: usize, _show_progress: bool, _message: &str) -> ProgressBar {
+    ProgressBar
+}
+
+/// A progress bar that draws nothing, standing in for indicatif's without the `cli` feature
+#[cfg(not(feature = "cli"))]
+pub(crate) struct ProgressBar;
+
+#[cfg(not(feature = "cli"))]
+impl ProgressBar {
+    pub fn inc(&self, _delta: u64) {}
+    pub fn finish_with_message(&self, _message: &'static str) {}
+    pub fn abandon_with_message(&self, _message: &'static str) {}
+    pub fn finish_and_clear(&self) {}
+}
+
+/// The hint of every guess against every answer, computed once and looked up when scoring
+///
+/// Hints are packed with `Hint::to_code`, so the matrix takes two bytes per guess/answer pair.
+/// After it has been built, scoring only counts codes instead of recomputing hints.
+pub struct HintMatrix {
+    guesses: Vec<Word>,
+    answers: Vec<Word>,
+    /// One row of `answers.len()` codes per guess
+    codes: Vec<u16>,
+    n_codes: usize,
+}
+
+impl HintMatrix {
+    /// The largest number of guess/answer pairs a matrix is built for (256 MiB of codes)
+    pub const MAX_ENTRIES: usize = 1 << 27;
+
+    /// Compute the hint of every guess against every answer
+    /// # Returns
+    /// None if the matrix would exceed `MAX_ENTRIES` or the words are too long for
+    /// their hints to fit in a `u16`
+    pub fn new(guesses: &[Word], answers: &[Word], show_progress: bool) -> Option<Self> {
+        let word_size = answers.first().map_or(0, Word::len);
+        let n_codes = 3_usize.checked_pow(word_size as u32)?;
+        if n_codes > u16::MAX as usize + 1 || guesses.len() * answers.len() > Self::MAX_ENTRIES {
+            return None;
+        }
+
+        let mut guesses = guesses.to_vec();
+        guesses.sort();
+        let mut answers = answers.to_vec();
+        answers.sort();
+
+        let pb = progress_bar(guesses.len(), show_progress, "Precomputing hints...");
+        let codes = profiled(Phase::HintMatrix, || {
+            guesses
+                .par_chunks(100)
+                .map(|chunk| {
+                    let mut rows = Vec::with_capacity(chunk.len() * answers.len());
+                    for guess in chunk {
+                        rows.extend(answers.iter().map(|answer| {
+                            Hint::from_guess_and_answer(guess, answer)
+                                .map_or(0, |hint| hint.to_code() as u16)
+                        }));
+                    }
+                    pb.inc(chunk.len() as u64);
+                    rows
+                })
+                .flatten_iter()
+                .collect()
+        });
+        pb.finish_and_clear();
+
+        Some(Self {
+            guesses,
+            answers,
+            codes,
+            n_codes,
+        })
+    }
+
+    /// The guesses the matrix was built for, sorted
+    pub fn guesses(&self) -> &[Word] {
+        &self.guesses
+    }
+
+    /// The answers the matrix was built for, sorted
+    pub fn answers(&self) -> &[Word] {
+        &self.answers
+    }
+
+    /// The number of distinct codes a hint can have
+    pub fn n_codes(&self) -> usize {
+        self.n_codes
+    }
+
+    /// The code of the hint the guess at index `guess` gets against the answer at `answer`
+    pub fn code(&self, guess: usize, answer: usize) -> usize {
+        self.codes[guess * self.answers.len() + answer] as usize
+    }
+
+    /// Score the guesses like `get_scores`, looking hints up in the matrix
+    /// Falls back to `get_scores` if any of the words are not in the matrix.
+    pub fn get_scores(
+        &self,
+        guesses: &[Word],
+        answers: &[Word],
+        show_progress: bool,
+    ) -> Vec<(Word, f32, f32)> {
+        self.get_scores_within(guesses, answers, show_progress, None)
+            .0
+    }
+
+    /// Score the guesses like `get_scores_within`, looking hints up in the matrix
+    pub fn get_scores_within(
+        &self,
+        guesses: &[Word],
+        answers: &[Word],
+        show_progress: bool,
+        budget: Option<Duration>,
+    ) -> (Vec<(Word, f32, f32)>, bool) {
+        let answer_indices: Option<Vec<usize>> = answers
+            .iter()
+            .map(|w| self.answers.binary_search(w).ok())
+            .collect();
+        let all_guesses_known = guesses
+            .iter()
+            .all(|w| self.guesses.binary_search(w).is_ok());
+        let Some(answer_indices) = answer_indices.filter(|_| all_guesses_known) else {
+            return get_scores_within(guesses, answers, show_progress, budget);
+        };
+
+        score_guesses(guesses, answers, show_progress, budget, |guess| {
+            let row = self
+                .guesses
+                .binary_search(guess)
+                .expect("Guess not in matrix");
+            let row = &self.codes[row * self.answers.len()..(row + 1) * self.answers.len()];
+
+            let mut counts = vec![0; self.n_codes];
+            let mut seen = vec![];
+            for &i in answer_indices.iter() {
+                let code = row[i] as usize;
+                if counts[code] == 0 {
+                    seen.push(code);
+                }
+                counts[code] += 1;
+            }
+            seen.into_iter().map(|code| counts[code] as f32).collect()
+        })
+    }
+}
+
+/// The number of top guesses `lookahead_scores` re-ranks
+pub const LOOKAHEAD_CANDIDATES: usize = 20;
+
+/// The number of follow-up guesses `lookahead_scores` tries for each hint
+const LOOKAHEAD_FOLLOW_UPS: usize = 200;
+
+/// Re-rank the best guesses by looking one guess further ahead
+///
+/// One-ply entropy only values the information a guess gives right away, which can favor a
+/// guess whose groups of answers are hard to split afterwards. For each of the first
+/// `LOOKAHEAD_CANDIDATES` of the sorted `scores`, this adds the expected information of the
+/// best follow-up guess for every hint it can get. Their expected score becomes the share of
+/// answers eliminated after both guesses; the rest of the scores are left as they are.
+pub fn lookahead_scores(
+    scores: Vec<(Word, f32, f32)>,
+    guesses: &[Word],
+    answers: &[Word],
+) -> Vec<(Word, f32, f32)> {
+    let n_candidates = scores.len().min(LOOKAHEAD_CANDIDATES);
+    let mut scores = scores;
+    let rest = scores.split_off(n_candidates);
+
+    let mut candidates: Vec<(Word, f32, f32)> = scores
+        .into_par_iter()
+        .map(|(guess, _, min_score)| {
+            let mut groups: HashMap<u32, Vec<Word>> = HashMap::new();
+            for answer in answers.iter() {
+                if let Ok(hint) = Hint::from_guess_and_answer(&guess, answer) {
+                    groups
+                        .entry(hint.to_code())
+                        .or_default()
+                        .push(answer.clone());
+                }
+            }
+
+            let mut entropy = 0.0;
+            for group in groups.values() {
+                let p = group.len() as f32 / answers.len() as f32;
+                entropy -= p * f32::ln(p);
+                if group.len() > 1 {
+                    let follow_ups = prefilter(guesses, group, LOOKAHEAD_FOLLOW_UPS);
+                    let best = follow_ups
+                        .iter()
+                        .map(|g| entropy_against(g, group))
+                        .fold(0.0_f32, f32::max);
+                    entropy += p * best;
+                }
+            }
+
+            let avg_score = (1.0 - f32::exp(-entropy)) * 100.0;
+            (guess, avg_score, min_score)
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap()
+            .then_with(|| Ord::cmp(&a.0, &b.0))
+    });
+    candidates.extend(rest);
+    candidates
+}
+
+/// The entropy of the hints a guess gets against the answers
+fn entropy_against(guess: &Word, answers: &[Word]) -> f32 {
+    let mut hint_counts: HashMap<Option<u32>, usize> = HashMap::new();
+    for answer in answers.iter() {
+        let code = Hint::from_guess_and_answer(guess, answer).map(|h| h.to_code());
+        *hint_counts.entry(code.ok()).or_insert(0) += 1;
+    }
+    -hint_counts
+        .values()
+        .map(|&c| c as f32 / answers.len() as f32)
+        .map(|p| p * f32::ln(p))
+        .sum::<f32>()
+}
+
+/// Estimate the number of guesses it takes to find the answer among `n` answers
+///
+/// One answer takes one guess and two take 1.5 on average. Beyond that the estimate grows
+/// with the entropy of the answers by `GUESSES_PER_BIT`, which matches a good solver taking
+/// about 3.4 guesses over the 2,315 original Wordle answers.
+pub fn estimated_guesses(n: usize) -> f32 {
+    match n {
+        0 => 0.0,
+        1 => 1.0,
+        n => 1.5 + ((n as f32).log2() - 1.0) * GUESSES_PER_BIT,
+    }
+}
+
+/// How many more guesses `estimated_guesses` expects each bit of entropy to take
+const GUESSES_PER_BIT: f32 = 0.19;
+
+/// How many times smaller `solvable_within` expects each guess to make a group of answers
+const GROUP_SPLIT: usize = 10;
+
+/// Whether a group of answers can likely still be solved within a number of guesses
+///
+/// A group counts as solvable in `g` guesses when it has at most `GROUP_SPLIT` to the power
+/// `g - 1` answers: the last guess must be the answer, and a good guess before it rarely
+/// splits a group more finely than that.
+pub fn solvable_within(n_answers: usize, guesses: usize) -> bool {
+    match guesses {
+        0 => n_answers == 0,
+        g => n_answers <= GROUP_SPLIT.saturating_pow(g as u32 - 1),
+    }
+}
+
+/// The expected number of guesses to solve the game if `guess` is played next, including it
+///
+/// The guess solves its own group of answers right away, and every other group it leaves is
+/// estimated by `estimated_guesses`.
+pub fn expected_guesses(guess: &Word, answers: &[Word]) -> f32 {
+    let mut group_sizes: HashMap<Option<u32>, usize> = HashMap::new();
+    for answer in answers.iter().filter(|&answer| answer != guess) {
+        let code = Hint::from_guess_and_answer(guess, answer).map(|h| h.to_code());
+        *group_sizes.entry(code.ok()).or_insert(0) += 1;
+    }
+    let n_answers = answers.len() as f32;
+    1.0 + group_sizes
+        .values()
+        .map(|&size| size as f32 / n_answers * estimated_guesses(size))
+        .sum::<f32>()
+}
+
+/// Pick the guess to play from sorted scores
+///
+/// Among the guesses tied for the best expected score, one that could be the answer is
+/// preferred, and once only two answers remain the solver simply guesses one of them.
+pub fn best_guess<'a>(scores: &'a [(Word, f32, f32)], answers: &'a [Word]) -> Option<&'a Word> {
+    if answers.len() <= 2 {
+        return answers.first();
+    }
+
+    let (_, best_score, _) = scores.first()?;
+    scores
+        .iter()
+        .take_while(|(_, score, _)| score == best_score)
+        .find(|(w, _, _)| answers.contains(w))
+        .or(scores.first())
+        .map(|(w, _, _)| w)
+}
+
+/// The number of guesses `suggest_quick` scores, picked by `rank_by_coverage`
+pub const QUICK_CANDIDATES: usize = 100;
+
+/// The most answers `suggest_quick` scores its candidates against
+const QUICK_SAMPLE: usize = 500;
+
+/// Pick a decent guess within milliseconds, without scoring every guess
+///
+/// With `scores` for the current answers (such as cached opening scores) this is just
+/// `best_guess`. Otherwise only the guesses that `rank_by_coverage` puts first, and the
+/// answers it puts first, are scored, against an evenly spaced sample of the answers. This
+/// usually finds one of the best few guesses, but unlike `get_scores` it is not exact.
+pub fn suggest_quick(
+    guesses: &[Word],
+    answers: &[Word],
+    scores: Option<&[(Word, f32, f32)]>,
+) -> Option<Word> {
+    if answers.len() <= 2 {
+        return answers.first().cloned();
+    }
+    if let Some(scores) = scores.filter(|scores| !scores.is_empty()) {
+        return best_guess(scores, answers).cloned();
+    }
+
+    let mut candidates: Vec<Word> = rank_by_coverage(guesses, answers)
+        .into_iter()
+        .take(QUICK_CANDIDATES)
+        .collect();
+    for answer in rank_by_coverage(answers, answers)
+        .into_iter()
+        .take(QUICK_CANDIDATES / 5)
+    {
+        if !candidates.contains(&answer) {
+            candidates.push(answer);
+        }
+    }
+    let step = answers.len().div_ceil(QUICK_SAMPLE);
+    let sample: Vec<Word> = answers.iter().step_by(step).cloned().collect();
+    let scores = get_scores(&candidates, &sample, false);
+    best_guess(&scores, answers).cloned()
+}
+
+/// Identify the partition that a guess induces on the answers
+///
+/// Answers are numbered by the order in which their hint is first seen, so two guesses get the
+/// same key exactly when they split the answers into the same groups, even if the hints differ.
+pub fn partition_key(guess: &Word, answers: &[Word]) -> Vec<usize> {
+    let mut bucket_ids: HashMap<Option<u32>, usize> = HashMap::new();
+    answers
+        .iter()
+        .map(|answer| {
+            let code = Hint::from_guess_and_answer(guess, answer).ok();
+            let next_id = bucket_ids.len();
+            *bucket_ids
+                .entry(code.map(|h| h.to_code()))
+                .or_insert(next_id)
+        })
+        .collect()
+}
+
+/// Take the first n scores, collapsing guesses that partition the answers identically
+/// # Returns
+/// One representative per class of equivalent guesses with the number of other guesses in it
+pub fn collapse_equivalent<'a>(
+    scores: &'a [(Word, f32, f32)],
+    answers: &[Word],
+    n: usize,
+) -> Vec<(&'a (Word, f32, f32), usize)> {
+    let mut collapsed = vec![];
+
+    // Equivalent guesses always have equal scores, so only runs of tied scores need comparing
+    let mut start = 0;
+    while start < scores.len() && collapsed.len() < n {
+        let (_, avg_score, min_score) = &scores[start];
+        let end = start
+            + scores[start..]
+                .iter()
+                .take_while(|(_, a, m)| a == avg_score && m == min_score)
+                .count();
+
+        let mut keys: Vec<Vec<usize>> = vec![];
+        let mut classes: Vec<(&(Word, f32, f32), usize)> = vec![];
+        for score in scores[start..end].iter() {
+            let key = partition_key(&score.0, answers);
+            match keys.iter().position(|k| *k == key) {
+                Some(i) => classes[i].1 += 1,
+                None => {
+                    keys.push(key);
+                    classes.push((score, 0));
+                }
+            }
+        }
+        collapsed.extend(classes);
+        start = end;
+    }
+
+    collapsed.truncate(n);
+    collapsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(words: &[&str]) -> Vec<Word> {
+        let mut words: Vec<Word> = words
+            .iter()
+            .map(|w| Word::from_string(w).unwrap())
+            .collect();
+        words.sort();
+        words
+    }
+
+    #[test]
+    fn answer_sets_undo_each_generation() {
+        let mut set = AnswerSet::new(words(&["crane", "slate", "trace", "sheep"]));
+        assert_eq!(set.len(), 4);
+        assert!(!set.undo());
+
+        assert_eq!(set.retain(|w| w.iter().any(|c| c == 'T')), 2);
+        assert_eq!(set.retain(|w| w.iter().next() == Some('S')), 1);
+        assert_eq!(set.to_vec(), words(&["slate"]));
+        assert_eq!(set.removed_per_generation(), vec![2, 1]);
+        assert!(!set.contains(&Word::from_string("trace").unwrap()));
+
+        assert!(set.undo());
+        assert_eq!(set.to_vec(), words(&["slate", "trace"]));
+        assert!(set.contains(&Word::from_string("trace").unwrap()));
+        assert!(set.undo());
+        assert_eq!(set.len(), 4);
+        assert!(!set.undo());
+    }
+
+    #[test]
+    fn answer_sets_span_several_bitset_words() {
+        // 125 three-letter words, so the alive bits take two u64s
+        let letters = ['A', 'B', 'C', 'D', 'E'];
+        let mut all = vec![];
+        for a in letters {
+            for b in letters {
+                for c in letters {
+                    all.push(Word::new(vec![a, b, c]).unwrap());
+                }
+            }
+        }
+        let mut set = AnswerSet::new(all);
+        assert_eq!(set.len(), 125);
+        assert_eq!(set.retain(|w| w.iter().next() == Some('E')), 100);
+        assert_eq!(set.iter().count(), 25);
+        assert!(set.undo());
+        assert_eq!(set.iter().count(), 125);
+    }
+
+    #[test]
+    fn hint_matrices_match_the_hints_they_pack() {
+        let guesses = words(&["crane", "slate", "trace", "sheep", "geese"]);
+        let answers = words(&["slate", "sheep", "geese"]);
+        let matrix = HintMatrix::new(&guesses, &answers, false).unwrap();
+        assert_eq!(matrix.n_codes(), 243);
+        for (i, guess) in matrix.guesses().iter().enumerate() {
+            for (j, answer) in matrix.answers().iter().enumerate() {
+                let hint = Hint::from_guess_and_answer(guess, answer).unwrap();
+                assert_eq!(matrix.code(i, j), hint.to_code() as usize);
+            }
+        }
+    }
+
+    #[test]
+    fn hint_matrices_are_not_built_for_codes_past_u16() {
+        // 3^10 codes fit in a u16, 3^11 do not
+        let ten = words(&["abcdefghij", "bcdefghija"]);
+        assert!(HintMatrix::new(&ten, &ten, false).is_some());
+        let eleven = words(&["abcdefghijk", "bcdefghijka"]);
+        assert!(HintMatrix::new(&eleven, &eleven, false).is_none());
+    }
+}
@@ -0,0 +1,543 @@
+//! Ways of choosing the next guess, and letting them play games on their own
+
+use crate::hint::{Hint, LetterHint};
+use crate::solver::{best_guess, expected_guesses, get_scores};
+use crate::word::Word;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// A way of choosing the next guess
+///
+/// Strategies are shared between the games `bench` plays in parallel, so they must be `Sync`.
+pub trait Strategy: Sync {
+    /// Choose the next guess
+    /// # Arguments
+    /// * `guesses` - The words that may be guessed
+    /// * `answers` - The words that may still be the answer, sorted
+    /// * `history` - The guesses played so far and their hints
+    /// # Returns
+    /// The guess to play, or None if there is nothing left to guess
+    fn choose(&self, guesses: &[Word], answers: &[Word], history: &[(Word, Hint)]) -> Option<Word>;
+}
+
+/// Guess the word with the highest expected information, as ranked by `get_scores`
+pub struct Entropy;
+
+impl Strategy for Entropy {
+    fn choose(&self, guesses: &[Word], answers: &[Word], _: &[(Word, Hint)]) -> Option<Word> {
+        let scores = get_scores(guesses, answers, false);
+        best_guess(&scores, answers).cloned()
+    }
+}
+
+/// Guess the word that leaves the fewest answers in the worst case
+pub struct Minimax;
+
+impl Strategy for Minimax {
+    fn choose(&self, guesses: &[Word], answers: &[Word], _: &[(Word, Hint)]) -> Option<Word> {
+        if answers.len() <= 2 {
+            return answers.first().cloned();
+        }
+
+        // Compare worst cases first, then expected scores, then prefer possible answers
+        let scores = get_scores(guesses, answers, false);
+        scores
+            .iter()
+            .max_by(|a, b| {
+                let possible = |w: &Word| answers.binary_search(w).is_ok();
+                a.2.total_cmp(&b.2)
+                    .then(a.1.total_cmp(&b.1))
+                    .then(possible(&a.0).cmp(&possible(&b.0)))
+                    .then(Ord::cmp(&b.0, &a.0))
+            })
+            .map(|(w, _, _)| w.clone())
+    }
+}
+
+/// Guess the word that leaves the fewest guesses to go on average, by `expected_guesses`
+///
+/// Ties go to possible answers, and with two answers left it guesses one of them.
+pub struct ExpectedGuesses;
+
+impl Strategy for ExpectedGuesses {
+    fn choose(&self, guesses: &[Word], answers: &[Word], _: &[(Word, Hint)]) -> Option<Word> {
+        if answers.len() <= 2 {
+            return answers.first().cloned();
+        }
+
+        let possible = |w: &Word| answers.binary_search(w).is_ok();
+        guesses
+            .par_iter()
+            .map(|w| (w, expected_guesses(w, answers)))
+            .min_by(|a, b| {
+                a.1.total_cmp(&b.1)
+                    .then(possible(b.0).cmp(&possible(a.0)))
+                    .then(Ord::cmp(a.0, b.0))
+            })
+            .map(|(w, _)| w.clone())
+    }
+}
+
+/// Guess the word that solves the most answers within two guesses: the guess itself if it is
+/// the answer, and every answer its hint singles out
+///
+/// Ties go to the fewest guesses expected, then to possible answers, and with two answers
+/// left it guesses one of them.
+pub struct WinInTwo;
+
+impl Strategy for WinInTwo {
+    fn choose(&self, guesses: &[Word], answers: &[Word], _: &[(Word, Hint)]) -> Option<Word> {
+        if answers.len() <= 2 {
+            return answers.first().cloned();
+        }
+
+        let possible = |w: &Word| answers.binary_search(w).is_ok();
+        let singled_out = |guess: &Word| {
+            let mut group_sizes: HashMap<Option<u32>, usize> = HashMap::new();
+            for answer in answers.iter() {
+                let code = Hint::from_guess_and_answer(guess, answer).map(|h| h.to_code());
+                *group_sizes.entry(code.ok()).or_insert(0) += 1;
+            }
+            group_sizes.values().filter(|&&size| size == 1).count()
+        };
+        guesses
+            .par_iter()
+            .map(|w| (w, singled_out(w), expected_guesses(w, answers)))
+            .max_by(|a, b| {
+                a.1.cmp(&b.1)
+                    .then(b.2.total_cmp(&a.2))
+                    .then(possible(a.0).cmp(&possible(b.0)))
+                    .then(Ord::cmp(b.0, a.0))
+            })
+            .map(|(w, _, _)| w.clone())
+    }
+}
+
+/// Guess the possible answer whose distinct letters appear in the most possible answers
+///
+/// This is roughly how people play, and is much cheaper than scoring every guess, but it
+/// never plays a word that is already ruled out to learn more.
+pub struct FrequentLetters;
+
+impl Strategy for FrequentLetters {
+    fn choose(&self, _: &[Word], answers: &[Word], _: &[(Word, Hint)]) -> Option<Word> {
+        let mut containing: HashMap<char, usize> = HashMap::new();
+        for answer in answers.iter() {
+            let mut letters: Vec<char> = answer.iter().collect();
+            letters.sort();
+            letters.dedup();
+            for c in letters {
+                *containing.entry(c).or_insert(0) += 1;
+            }
+        }
+
+        let frequency = |w: &Word| {
+            let mut letters: Vec<char> = w.iter().collect();
+            letters.sort();
+            letters.dedup();
+            letters.iter().map(|c| containing[c]).sum::<usize>()
+        };
+        answers
+            .iter()
+            .max_by(|a, b| frequency(a).cmp(&frequency(b)).then(Ord::cmp(b, a)))
+            .cloned()
+    }
+}
+
+/// Guess a random possible answer
+pub struct Random {
+    seed: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+}
+
+impl Strategy for Random {
+    fn choose(&self, _: &[Word], answers: &[Word], history: &[(Word, Hint)]) -> Option<Word> {
+        // Derive the generator from the position, so that games played in parallel
+        // stay reproducible for a given seed
+        let position = (history.len() as u64) << 32 | answers.len() as u64;
+        let mut rng = StdRng::seed_from_u64(self.seed ^ position);
+        answers.choose(&mut rng).cloned()
+    }
+}
+
+/// The built-in strategies, as selected on the command line
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum StrategyKind {
+    Entropy,
+    Minimax,
+    FrequentLetters,
+    Random,
+    ExpectedGuesses,
+    WinInTwo,
+}
+
+impl StrategyKind {
+    pub const ALL: [StrategyKind; 6] = [
+        StrategyKind::Entropy,
+        StrategyKind::Minimax,
+        StrategyKind::FrequentLetters,
+        StrategyKind::Random,
+        StrategyKind::ExpectedGuesses,
+        StrategyKind::WinInTwo,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            StrategyKind::Entropy => "entropy",
+            StrategyKind::Minimax => "minimax",
+            StrategyKind::FrequentLetters => "frequent-letters",
+            StrategyKind::Random => "random",
+            StrategyKind::ExpectedGuesses => "expected-guesses",
+            StrategyKind::WinInTwo => "win-in-2",
+        }
+    }
+
+    /// Create the strategy, seeding it with `seed` if it is random
+    pub fn build(self, seed: u64) -> Box<dyn Strategy> {
+        match self {
+            StrategyKind::Entropy => Box::new(Entropy),
+            StrategyKind::Minimax => Box::new(Minimax),
+            StrategyKind::FrequentLetters => Box::new(FrequentLetters),
+            StrategyKind::Random => Box::new(Random::new(seed)),
+            StrategyKind::ExpectedGuesses => Box::new(ExpectedGuesses),
+            StrategyKind::WinInTwo => Box::new(WinInTwo),
+        }
+    }
+}
+
+impl fmt::Display for StrategyKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for StrategyKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        StrategyKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = StrategyKind::ALL.iter().map(|k| k.name()).collect();
+                format!(
+                    "unknown strategy '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// What the solver aims for, as selected on the command line instead of a strategy
+///
+/// Each objective picks the strategy that ranks guesses by it, which comes with the tie-breaks
+/// and the endgame that suit it:
+/// * `expected-guesses` - The fewest guesses on average; ties go to possible answers
+/// * `max-info` - The most expected information; ties go to possible answers
+/// * `min-worst` - The fewest answers left in the worst case; ties go to the most expected
+///   information, then to possible answers
+/// * `win-in-2` - The most answers solved within two guesses; ties go to the fewest guesses
+///   on average, then to possible answers
+///
+/// All of them guess a possible answer once only two are left.
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Objective {
+    ExpectedGuesses,
+    MaxInfo,
+    MinWorst,
+    WinInTwo,
+}
+
+impl Objective {
+    pub const ALL: [Objective; 4] = [
+        Objective::ExpectedGuesses,
+        Objective::MaxInfo,
+        Objective::MinWorst,
+        Objective::WinInTwo,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Objective::ExpectedGuesses => "expected-guesses",
+            Objective::MaxInfo => "max-info",
+            Objective::MinWorst => "min-worst",
+            Objective::WinInTwo => "win-in-2",
+        }
+    }
+
+    /// The strategy that plays for the objective
+    pub fn strategy(self) -> StrategyKind {
+        match self {
+            Objective::ExpectedGuesses => StrategyKind::ExpectedGuesses,
+            Objective::MaxInfo => StrategyKind::Entropy,
+            Objective::MinWorst => StrategyKind::Minimax,
+            Objective::WinInTwo => StrategyKind::WinInTwo,
+        }
+    }
+}
+
+impl fmt::Display for Objective {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Objective {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Objective::ALL
+            .into_iter()
+            .find(|objective| objective.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Objective::ALL.iter().map(|o| o.name()).collect();
+                format!(
+                    "unknown objective '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Let a strategy play against a known answer
+/// # Arguments
+/// * `strategy` - How to choose each guess after the first
+/// * `guesses` - The words that may be guessed
+/// * `answers` - The words that may be the answer, sorted
+/// * `secret` - The answer the strategy plays against
+/// * `opener` - The first guess, which is the same in every game and so can be computed once
+/// * `max_guesses` - The number of guesses after which the strategy gives up
+/// # Returns
+/// Each guess with its hint and the number of answers it leaves, ending with the secret
+/// if the strategy found it
+pub fn auto_play(
+    strategy: &dyn Strategy,
+    guesses: &[Word],
+    answers: &[Word],
+    secret: &Word,
+    opener: &Word,
+    max_guesses: usize,
+) -> Vec<(Word, Hint, usize)> {
+    let game = play(
+        strategy,
+        guesses,
+        answers,
+        secret,
+        opener,
+        max_guesses,
+        |hint| hint,
+    );
+    game.history
+}
+
+/// A game played by `auto_play_noisy`
+pub struct NoisyGame {
+    /// Each guess with the hint as entered and the number of answers it leaves
+    pub history: Vec<(Word, Hint, usize)>,
+    /// The number of times the hints contradicted each other, leaving no answer
+    pub n_contradictions: usize,
+}
+
+/// Let a strategy play against a known answer with hints that are sometimes entered wrong
+///
+/// Each letter of each hint is replaced by one of the other two letter hints with probability
+/// `noise`, as when a player mis-enters the feedback. Once the hints contradict each other, the
+/// strategy drops the hint whose removal leaves the most answers and carries on from there.
+/// The other arguments are those of `auto_play`.
+#[allow(clippy::too_many_arguments)]
+pub fn auto_play_noisy(
+    strategy: &dyn Strategy,
+    guesses: &[Word],
+    answers: &[Word],
+    secret: &Word,
+    opener: &Word,
+    max_guesses: usize,
+    noise: f64,
+    rng: &mut impl Rng,
+) -> NoisyGame {
+    play(
+        strategy,
+        guesses,
+        answers,
+        secret,
+        opener,
+        max_guesses,
+        |hint| {
+            let letter_hints = hint
+                .iter()
+                .map(|&h| match rng.gen_bool(noise) {
+                    true => {
+                        let others: Vec<LetterHint> =
+                            LetterHint::ALL.into_iter().filter(|&o| o != h).collect();
+                        *others.choose(rng).expect("Every letter hint has others")
+                    }
+                    false => h,
+                })
+                .collect();
+            Hint::new(letter_hints)
+        },
+    )
+}
+
+/// Play a game, passing each hint through `enter_hint` before the strategy sees it
+fn play(
+    strategy: &dyn Strategy,
+    guesses: &[Word],
+    answers: &[Word],
+    secret: &Word,
+    opener: &Word,
+    max_guesses: usize,
+    mut enter_hint: impl FnMut(Hint) -> Hint,
+) -> NoisyGame {
+    let consistent = |hints: &[(Word, Hint)]| -> Vec<Word> {
+        answers
+            .iter()
+            .filter(|w| {
+                hints
+                    .iter()
+                    .all(|(guess, hint)| Hint::from_guess_and_answer(guess, w).as_ref() == Ok(hint))
+            })
+            .cloned()
+            .collect()
+    };
+
+    let mut remaining_answers = answers.to_vec();
+    let mut history: Vec<(Word, Hint)> = vec![];
+    // The hints the strategy still trusts, which are all of them until they contradict
+    let mut trusted: Vec<(Word, Hint)> = vec![];
+    let mut n_remaining = vec![];
+    let mut n_contradictions = 0;
+    let mut guess = opener.clone();
+
+    while history.len() < max_guesses {
+        let Ok(hint) = Hint::from_guess_and_answer(&guess, secret) else {
+            break;
+        };
+        let hint = enter_hint(hint);
+        remaining_answers.retain(|w| Hint::from_guess_and_answer(&guess, w).as_ref() == Ok(&hint));
+        let solved = guess == *secret;
+        history.push((guess.clone(), hint.clone()));
+        trusted.push((guess, hint));
+
+        if remaining_answers.is_empty() && !solved {
+            n_contradictions += 1;
+            let (dropped, answers) = (0..trusted.len())
+                .rev()
+                .map(|i| {
+                    let mut kept = trusted.clone();
+                    kept.remove(i);
+                    (i, consistent(&kept))
+                })
+                .max_by_key(|(_, answers)| answers.len())
+                .expect("A hint was just added");
+            trusted.remove(dropped);
+            remaining_answers = answers;
+        }
+        n_remaining.push(remaining_answers.len());
+        if solved {
+            break;
+        }
+
+        match strategy.choose(guesses, &remaining_answers, &history) {
+            Some(next) => guess = next,
+            None => break,
+        }
+    }
+
+    let history = history
+        .into_iter()
+        .zip(n_remaining)
+        .map(|((guess, hint), n)| (guess, hint, n))
+        .collect();
+    NoisyGame {
+        history,
+        n_contradictions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small list, sorted as the strategies expect the answers to be
+    fn words() -> Vec<Word> {
+        let mut words: Vec<Word> = [
+            "bumpy", "crane", "crate", "dwarf", "fight", "grace", "joker", "plaid", "quick",
+            "shout", "trace", "vixen",
+        ]
+        .iter()
+        .map(|w| Word::from_string(w).unwrap())
+        .collect();
+        words.sort();
+        words
+    }
+
+    #[test]
+    fn every_strategy_solves_every_answer_of_a_small_list() {
+        let words = words();
+        let opener = Word::from_string("crane").unwrap();
+        for kind in StrategyKind::ALL {
+            let strategy = kind.build(7);
+            for answer in words.iter() {
+                let history = auto_play(strategy.as_ref(), &words, &words, answer, &opener, 6);
+                assert!(
+                    history.last().is_some_and(|(guess, _, _)| guess == answer),
+                    "{} did not solve {} in 6 guesses",
+                    kind,
+                    answer
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn random_plays_the_same_games_for_the_same_seed() {
+        let words = words();
+        let opener = Word::from_string("crane").unwrap();
+        let games = |seed| -> Vec<Vec<(Word, Hint, usize)>> {
+            let strategy = Random::new(seed);
+            words
+                .iter()
+                .map(|answer| auto_play(&strategy, &words, &words, answer, &opener, 6))
+                .collect()
+        };
+        assert_eq!(games(42), games(42));
+    }
+
+    #[test]
+    fn noiseless_hints_play_the_same_game_without_contradictions() {
+        let words = words();
+        let opener = Word::from_string("crane").unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+        for kind in StrategyKind::ALL {
+            let strategy = kind.build(7);
+            for answer in words.iter() {
+                let game = auto_play_noisy(
+                    strategy.as_ref(),
+                    &words,
+                    &words,
+                    answer,
+                    &opener,
+                    6,
+                    0.0,
+                    &mut rng,
+                );
+                let expected = auto_play(strategy.as_ref(), &words, &words, answer, &opener, 6);
+                assert_eq!(game.history, expected);
+                assert_eq!(game.n_contradictions, 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,230 @@
+//! Decision trees that record how a strategy plays against every answer
+
+use crate::hint::Hint;
+use crate::solver::progress_bar;
+use crate::strategy::Strategy;
+use crate::word::Word;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// The guesses a strategy plays, and the guesses it follows them with after each hint
+pub struct DecisionTree {
+    /// The guess to play
+    pub guess: Word,
+    /// The number of answers still possible when the guess is played
+    pub n_answers: usize,
+    /// What happens after each hint the guess can get, except the hint that solves the game,
+    /// sorted by hint
+    pub branches: Vec<Branch>,
+}
+
+/// The answers that get one hint, and the tree to follow for them
+pub struct Branch {
+    pub hint: Hint,
+    pub n_answers: usize,
+    /// None if the strategy gave up or ran out of guesses
+    pub next: Option<DecisionTree>,
+}
+
+impl DecisionTree {
+    /// Play a strategy against every answer at once, splitting the answers by hint
+    /// # Arguments
+    /// * `strategy` - How to choose each guess after the first
+    /// * `guesses` - The words that may be guessed
+    /// * `answers` - The words that may be the answer, sorted
+    /// * `opener` - The first guess
+    /// * `max_guesses` - The depth after which the strategy gives up
+    /// * `show_progress` - Show a progress bar of the answers placed in the tree
+    pub fn build(
+        strategy: &dyn Strategy,
+        guesses: &[Word],
+        answers: &[Word],
+        opener: &Word,
+        max_guesses: usize,
+        show_progress: bool,
+    ) -> Self {
+        let never = || false;
+        let (tree, _) = Self::build_until(
+            strategy,
+            guesses,
+            answers,
+            opener,
+            max_guesses,
+            show_progress,
+            &never,
+        );
+        tree
+    }
+
+    /// Build the tree like `build`, until `stop` returns true
+    ///
+    /// `stop` is called before each guess is chosen. Once it returns true no more guesses are
+    /// chosen, so the branches not reached end there, as if the strategy had given up.
+    /// # Returns
+    /// The tree, and whether it was finished before `stop` returned true
+    pub fn build_until(
+        strategy: &dyn Strategy,
+        guesses: &[Word],
+        answers: &[Word],
+        opener: &Word,
+        max_guesses: usize,
+        show_progress: bool,
+        stop: &(dyn Fn() -> bool + Sync),
+    ) -> (Self, bool) {
+        let pb = progress_bar(
+            answers.len(),
+            show_progress,
+            "Building the decision tree...",
+        );
+        let stopped = AtomicBool::new(false);
+        let builder = Builder {
+            strategy,
+            guesses,
+            on_placed: &|n| pb.inc(n as u64),
+            stop: &|| {
+                let stop = stop();
+                if stop {
+                    stopped.store(true, Ordering::Relaxed);
+                }
+                stop
+            },
+        };
+        let tree = builder.build_node(answers, opener.clone(), &[], max_guesses);
+        pb.finish_and_clear();
+        (tree, !stopped.load(Ordering::Relaxed))
+    }
+
+    /// The tree to follow after the guesses and hints of a game so far
+    /// # Returns
+    /// None if the game left the tree or the strategy gave up
+    pub fn follow(&self, history: &[(Word, Hint)]) -> Option<&DecisionTree> {
+        let mut node = self;
+        for (guess, hint) in history.iter() {
+            if *guess != node.guess {
+                return None;
+            }
+            let branch = node.branches.iter().find(|b| b.hint == *hint)?;
+            node = branch.next.as_ref()?;
+        }
+        Some(node)
+    }
+
+    /// Whether the guess can be the answer
+    pub fn solves(&self) -> bool {
+        self.branches.iter().map(|b| b.n_answers).sum::<usize>() < self.n_answers
+    }
+
+    /// The number of guesses needed over the answers the tree solves, and the number of
+    /// answers it does not solve
+    pub fn total_guesses(&self) -> (usize, usize) {
+        let mut total = if self.solves() { 1 } else { 0 };
+        let mut n_failed = 0;
+        for branch in self.branches.iter() {
+            match &branch.next {
+                Some(next) => {
+                    let (next_total, next_failed) = next.total_guesses();
+                    // Every answer solved further down also needed this guess
+                    total += next_total + branch.n_answers - next_failed;
+                    n_failed += next_failed;
+                }
+                None => n_failed += branch.n_answers,
+            }
+        }
+        (total, n_failed)
+    }
+}
+
+/// What building every node of a tree needs
+struct Builder<'a> {
+    strategy: &'a dyn Strategy,
+    guesses: &'a [Word],
+    /// Called with the number of answers placed in the tree, once they are solved or given up on
+    on_placed: &'a (dyn Fn(usize) + Sync),
+    stop: &'a (dyn Fn() -> bool + Sync),
+}
+
+impl Builder<'_> {
+    fn build_node(
+        &self,
+        answers: &[Word],
+        guess: Word,
+        history: &[(Word, Hint)],
+        guesses_left: usize,
+    ) -> DecisionTree {
+        let mut groups: HashMap<Hint, Vec<Word>> = HashMap::new();
+        for answer in answers.iter().filter(|&answer| *answer != guess) {
+            if let Ok(hint) = Hint::from_guess_and_answer(&guess, answer) {
+                groups.entry(hint).or_default().push(answer.clone());
+            }
+        }
+        if answers.contains(&guess) {
+            (self.on_placed)(1);
+        }
+        let mut groups: Vec<(Hint, Vec<Word>)> = groups.into_iter().collect();
+        groups.sort_by_key(|(hint, _)| hint.to_code());
+
+        let branches = groups
+            .into_par_iter()
+            .map(|(hint, group)| {
+                let mut history = history.to_vec();
+                history.push((guess.clone(), hint.clone()));
+                let next = match guesses_left > 1 && !(self.stop)() {
+                    true => self.strategy.choose(self.guesses, &group, &history),
+                    false => None,
+                };
+                let next =
+                    next.map(|next| self.build_node(&group, next, &history, guesses_left - 1));
+                if next.is_none() {
+                    (self.on_placed)(group.len());
+                }
+                Branch {
+                    hint,
+                    n_answers: group.len(),
+                    next,
+                }
+            })
+            .collect();
+
+        DecisionTree {
+            guess,
+            n_answers: answers.len(),
+            branches,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strategy::Entropy;
+
+    fn words() -> Vec<Word> {
+        ["CRANE", "SLATE", "TRACE", "BRICK", "PLUMB", "GRATE"]
+            .map(|w| Word::from_string(w).unwrap())
+            .to_vec()
+    }
+
+    #[test]
+    fn trees_built_to_the_end_solve_every_answer() {
+        let words = words();
+        let opener = Word::from_string("CRANE").unwrap();
+        let (tree, complete) =
+            DecisionTree::build_until(&Entropy, &words, &words, &opener, 6, false, &|| false);
+        assert!(complete);
+        assert_eq!(tree.n_answers, words.len());
+        assert_eq!(tree.total_guesses().1, 0);
+    }
+
+    #[test]
+    fn stopped_trees_give_up_on_the_branches_not_built() {
+        let words = words();
+        let opener = Word::from_string("CRANE").unwrap();
+        let (tree, complete) =
+            DecisionTree::build_until(&Entropy, &words, &words, &opener, 6, false, &|| true);
+        assert!(!complete);
+        assert!(tree.branches.iter().all(|branch| branch.next.is_none()));
+        // Only the opener itself is solved
+        assert_eq!(tree.total_guesses(), (1, words.len() - 1));
+    }
+}
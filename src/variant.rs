@@ -0,0 +1,76 @@
+//! Presets for popular Wordle variants
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The official Wordle word list, embedded so the `wordle` variant works without any files
+const WORDLE_WORDS: &str = include_str!("../wordle_words.txt");
+
+/// A popular Wordle variant, bundling its word length, attempt count and word list
+#[derive(PartialEq, Eq, Copy, Clone, Debug)]
+pub enum Variant {
+    /// The original: 5 letters, 6 attempts
+    Wordle,
+    /// 6 letters, 7 attempts
+    Sixes,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 2] = [Variant::Wordle, Variant::Sixes];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Variant::Wordle => "wordle",
+            Variant::Sixes => "sixes",
+        }
+    }
+
+    /// The number of letters in each word
+    pub fn word_size(self) -> usize {
+        match self {
+            Variant::Wordle => 5,
+            Variant::Sixes => 6,
+        }
+    }
+
+    /// The number of guesses allowed per game
+    pub fn max_attempts(self) -> usize {
+        match self {
+            Variant::Wordle => 6,
+            Variant::Sixes => 7,
+        }
+    }
+
+    /// The word list shipped with the binary for this variant, one word per line
+    /// Variants without one fall back to the word list given with `--file`.
+    pub fn embedded_words(self) -> Option<&'static str> {
+        match self {
+            Variant::Wordle => Some(WORDLE_WORDS),
+            Variant::Sixes => None,
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Variant::ALL
+            .into_iter()
+            .find(|variant| variant.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Variant::ALL.iter().map(|v| v.name()).collect();
+                format!(
+                    "unknown variant '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
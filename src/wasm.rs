@@ -0,0 +1,137 @@
+//! wasm-bindgen exports of the [`web`](crate::web) game and solver for browser front ends
+//!
+//! Built with `--no-default-features --features wasm --target wasm32-unknown-unknown`, e.g.
+//! through `wasm-pack build -- --no-default-features --features wasm`. In JavaScript:
+//!
+//! ```js
+//! const game = new Game(wordList, 5, 6, BigInt(Date.now()));
+//! game.guess("crane"); // e.g. "bgyby"
+//! const solver = new Solver(wordList, 5);
+//! solver.hint("crane", "bgyby");
+//! solver.suggest();
+//! ```
+
+use crate::web;
+use wasm_bindgen::prelude::*;
+
+/// A game against a secret word picked from a word list
+#[wasm_bindgen]
+pub struct Game(web::Game);
+
+#[wasm_bindgen]
+impl Game {
+    /// Start a game against a word picked at random with a seed
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        words: &str,
+        word_size: usize,
+        max_attempts: usize,
+        seed: u64,
+    ) -> Result<Game, JsError> {
+        web::Game::new(words, word_size, max_attempts, seed)
+            .map(Game)
+            .map_err(|e| JsError::new(&e))
+    }
+
+    /// Start the daily puzzle of a day, counted in days since the Unix epoch
+    pub fn daily(
+        words: &str,
+        word_size: usize,
+        max_attempts: usize,
+        day: u64,
+    ) -> Result<Game, JsError> {
+        web::Game::daily(words, word_size, max_attempts, day)
+            .map(Game)
+            .map_err(|e| JsError::new(&e))
+    }
+
+    /// Play a guess, returning its hint in g/y/b letters
+    pub fn guess(&mut self, guess: &str) -> Result<String, JsError> {
+        self.0.guess(guess).map_err(|e| JsError::new(&e))
+    }
+
+    #[wasm_bindgen(js_name = isWon)]
+    pub fn is_won(&self) -> bool {
+        self.0.is_won()
+    }
+
+    #[wasm_bindgen(js_name = isOver)]
+    pub fn is_over(&self) -> bool {
+        self.0.is_over()
+    }
+
+    pub fn attempts(&self) -> usize {
+        self.0.attempts()
+    }
+
+    #[wasm_bindgen(js_name = maxAttempts)]
+    pub fn max_attempts(&self) -> usize {
+        self.0.max_attempts()
+    }
+
+    /// The secret word once the game is over, and undefined before
+    pub fn answer(&self) -> Option<String> {
+        self.0.answer()
+    }
+
+    /// The emoji share grid of the game
+    pub fn share(&self, title: &str) -> String {
+        self.0.share(title)
+    }
+}
+
+/// A guess with the percentages of the answers it is expected to remove and removes at worst
+#[wasm_bindgen(getter_with_clone)]
+pub struct ScoredGuess {
+    pub word: String,
+    pub expected: f32,
+    #[wasm_bindgen(js_name = worstCase)]
+    pub worst_case: f32,
+}
+
+/// The solver, narrowing down the answers as hints are added
+#[wasm_bindgen]
+pub struct Solver(web::Solver);
+
+#[wasm_bindgen]
+impl Solver {
+    #[wasm_bindgen(constructor)]
+    pub fn new(words: &str, word_size: usize) -> Result<Solver, JsError> {
+        web::Solver::new(words, word_size)
+            .map(Solver)
+            .map_err(|e| JsError::new(&e))
+    }
+
+    /// Add a guess and its hint, returning the number of answers left
+    pub fn hint(&mut self, guess: &str, hint: &str) -> Result<usize, JsError> {
+        self.0.hint(guess, hint).map_err(|e| JsError::new(&e))
+    }
+
+    /// Take back the last hint, returning whether there was one
+    pub fn undo(&mut self) -> bool {
+        self.0.undo()
+    }
+
+    /// The answers left, in alphabetical order
+    pub fn remaining(&self) -> Vec<String> {
+        self.0.remaining()
+    }
+
+    /// The best `n` guesses, best first
+    pub fn top(&self, n: usize) -> Vec<ScoredGuess> {
+        self.0
+            .top(n)
+            .into_iter()
+            .map(|g| ScoredGuess {
+                word: g.word,
+                expected: g.expected,
+                worst_case: g.worst_case,
+            })
+            .collect()
+    }
+
+    /// The guess to play next, or undefined if no answer is left
+    pub fn suggest(&self) -> Option<String> {
+        self.0.suggest()
+    }
+}
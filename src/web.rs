@@ -0,0 +1,282 @@
+//! The game and the solver as a browser front end uses them
+//!
+//! Everything goes in and out as strings and numbers, and nothing touches a terminal or a
+//! file, so the `wasm` module can export these types through wasm-bindgen as they are. Word
+//! lists are given as text, one word per line, as in a word list file. Hints are written
+//! with a letter per square: g for a correct letter, y for a misplaced one and b for one not
+//! in the word.
+
+use crate::hint::{share_grid, FeedbackSymbols};
+use crate::picker::{AnswerPicker, Daily, Uniform};
+use crate::solver::{best_guess, get_scores};
+use crate::word::{parse_words, MAX_WORD_SIZE};
+use crate::{Hint, LetterHint, Word};
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+
+/// Read a word list given as text
+fn parse_list(text: &str, word_size: usize) -> Result<Vec<Word>, String> {
+    if !(1..=MAX_WORD_SIZE).contains(&word_size) {
+        return Err(format!(
+            "words must have from 1 to {} letters",
+            MAX_WORD_SIZE
+        ));
+    }
+    let words = parse_words(text, word_size);
+    match words.is_empty() {
+        true => Err(format!("the list has no {}-letter words", word_size)),
+        false => Ok(words),
+    }
+}
+
+/// Write a hint with a letter per square: g, y or b
+fn colors(hint: &Hint) -> String {
+    hint.iter()
+        .map(|h| match h {
+            LetterHint::Correct => 'g',
+            LetterHint::Misplaced => 'y',
+            LetterHint::Incorrect => 'b',
+        })
+        .collect()
+}
+
+/// A game against a secret word picked from a word list
+pub struct Game {
+    words: Vec<Word>,
+    secret: Word,
+    max_attempts: usize,
+    board: Vec<(Word, Hint)>,
+}
+
+impl Game {
+    /// Start a game against a word picked at random with a seed
+    pub fn new(
+        words: &str,
+        word_size: usize,
+        max_attempts: usize,
+        seed: u64,
+    ) -> Result<Self, String> {
+        let mut picker = Uniform::new(StdRng::seed_from_u64(seed));
+        Self::with_picker(words, word_size, max_attempts, &mut picker)
+    }
+
+    /// Start the daily puzzle of a day, counted in days since the Unix epoch, which is the
+    /// puzzle `rudle play --daily` plays with the same list
+    pub fn daily(
+        words: &str,
+        word_size: usize,
+        max_attempts: usize,
+        day: u64,
+    ) -> Result<Self, String> {
+        Self::with_picker(words, word_size, max_attempts, &mut Daily::new(day))
+    }
+
+    fn with_picker(
+        words: &str,
+        word_size: usize,
+        max_attempts: usize,
+        picker: &mut dyn AnswerPicker,
+    ) -> Result<Self, String> {
+        let words = parse_list(words, word_size)?;
+        let secret = picker.pick(&words).expect("The word list is not empty");
+        Ok(Self {
+            words,
+            secret,
+            max_attempts: max_attempts.max(1),
+            board: vec![],
+        })
+    }
+
+    /// Play a guess
+    /// # Returns
+    /// The hint for the guess, or why it cannot be played
+    pub fn guess(&mut self, guess: &str) -> Result<String, String> {
+        if self.is_over() {
+            return Err("the game is over".to_string());
+        }
+        let guess = Word::from_string(guess.trim()).map_err(|e| e.to_string())?;
+        if self.words.binary_search(&guess).is_err() {
+            return Err(format!("{} is not in the word list", guess));
+        }
+        let hint = Hint::from_guess_and_answer(&guess, &self.secret).map_err(|e| e.to_string())?;
+        let colors = colors(&hint);
+        self.board.push((guess, hint));
+        Ok(colors)
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.board
+            .last()
+            .is_some_and(|(guess, _)| guess == &self.secret)
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.is_won() || self.board.len() >= self.max_attempts
+    }
+
+    /// The number of guesses played
+    pub fn attempts(&self) -> usize {
+        self.board.len()
+    }
+
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+
+    /// The secret word, once the game is over
+    pub fn answer(&self) -> Option<String> {
+        self.is_over().then(|| self.secret.to_string())
+    }
+
+    /// The emoji share grid of the game, headed by the title and the score
+    pub fn share(&self, title: &str) -> String {
+        share_grid(
+            title,
+            &self.board,
+            &[],
+            self.max_attempts,
+            &FeedbackSymbols::default(),
+        )
+    }
+}
+
+/// A guess with its scores: the percentage of the answers it is expected to remove, and the
+/// percentage it removes at worst
+pub struct ScoredGuess {
+    pub word: String,
+    pub expected: f32,
+    pub worst_case: f32,
+}
+
+/// The solver, narrowing down the answers as hints are added
+pub struct Solver {
+    words: Vec<Word>,
+    /// The answers left before each hint, and after the last one
+    answers: Vec<Vec<Word>>,
+}
+
+impl Solver {
+    pub fn new(words: &str, word_size: usize) -> Result<Self, String> {
+        let words = parse_list(words, word_size)?;
+        Ok(Self {
+            answers: vec![words.clone()],
+            words,
+        })
+    }
+
+    fn remaining_answers(&self) -> &[Word] {
+        self.answers
+            .last()
+            .expect("There is always a list of answers")
+    }
+
+    /// Add a guess and its hint, in g/y/b letters or in the format the REPL's `hint` reads
+    /// # Returns
+    /// The number of answers left
+    pub fn hint(&mut self, guess: &str, hint: &str) -> Result<usize, String> {
+        let guess = Word::from_string(guess.trim()).map_err(|e| e.to_string())?;
+        let hint = Hint::from_string(hint.trim(), &guess).map_err(|e| e.to_string())?;
+        let left: Vec<Word> = self
+            .remaining_answers()
+            .iter()
+            .filter(|w| Hint::from_guess_and_answer(&guess, w).as_ref() == Ok(&hint))
+            .cloned()
+            .collect();
+        self.answers.push(left);
+        Ok(self.remaining_answers().len())
+    }
+
+    /// Take back the last hint
+    /// # Returns
+    /// Whether there was a hint to take back
+    pub fn undo(&mut self) -> bool {
+        match self.answers.len() > 1 {
+            true => self.answers.pop().is_some(),
+            false => false,
+        }
+    }
+
+    /// The answers left, in alphabetical order
+    pub fn remaining(&self) -> Vec<String> {
+        self.remaining_answers()
+            .iter()
+            .map(Word::to_string)
+            .collect()
+    }
+
+    /// The best `n` guesses, best first
+    pub fn top(&self, n: usize) -> Vec<ScoredGuess> {
+        get_scores(&self.words, self.remaining_answers(), false)
+            .into_iter()
+            .take(n)
+            .map(|(word, expected, worst_case)| ScoredGuess {
+                word: word.to_string(),
+                expected,
+                worst_case,
+            })
+            .collect()
+    }
+
+    /// The guess to play next, preferring a possible answer when scores tie
+    pub fn suggest(&self) -> Option<String> {
+        let scores = get_scores(&self.words, self.remaining_answers(), false);
+        best_guess(&scores, self.remaining_answers()).map(Word::to_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORDS: &str = "crane\nslate\ntrace\nbrick\nplumb\n";
+
+    #[test]
+    fn games_are_played_to_the_end() {
+        let mut game = Game::new(WORDS, 5, 6, 7).unwrap();
+        assert_eq!(game.answer(), None);
+        assert!(game.guess("zzzzz").is_err());
+        assert!(game.guess("cran").is_err());
+        assert_eq!(game.attempts(), 0);
+
+        let secret = game.secret.to_string();
+        let hint = game.guess(&secret).unwrap();
+        assert_eq!(hint, "ggggg");
+        assert!(game.is_won() && game.is_over());
+        assert_eq!(game.answer(), Some(secret));
+        assert!(game.guess("crane").is_err());
+        assert!(game.share("Rudle").starts_with("Rudle 1/6"));
+    }
+
+    #[test]
+    fn daily_games_pick_the_daily_answer() {
+        let words = parse_words(WORDS, 5);
+        let expected = Daily::new(20_000).pick(&words).unwrap();
+        assert_eq!(Game::daily(WORDS, 5, 6, 20_000).unwrap().secret, expected);
+    }
+
+    #[test]
+    fn lists_without_words_of_the_size_are_rejected() {
+        assert!(Game::new(WORDS, 4, 6, 0).is_err());
+        assert!(Solver::new(WORDS, MAX_WORD_SIZE + 1).is_err());
+        assert!(Solver::new("", 5).is_err());
+    }
+
+    #[test]
+    fn the_solver_narrows_and_undoes() {
+        let mut solver = Solver::new(WORDS, 5).unwrap();
+        assert_eq!(solver.remaining().len(), 5);
+        assert_eq!(solver.top(2).len(), 2);
+
+        // CRANE against SLATE in colors, and against TRACE in the REPL's format
+        assert_eq!(solver.hint("crane", "bbgbg"), Ok(1));
+        assert_eq!(solver.remaining(), ["SLATE"]);
+        assert!(solver.undo());
+        assert_eq!(solver.hint("crane", "*ra_e"), Ok(1));
+        assert_eq!(solver.remaining(), ["TRACE"]);
+        assert_eq!(solver.suggest().as_deref(), Some("TRACE"));
+        assert!(solver.hint("crane", "gg").is_err());
+
+        assert!(solver.undo());
+        assert!(!solver.undo());
+    }
+}
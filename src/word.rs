@@ -0,0 +1,259 @@
+use crate::error::ParseError;
+use smallvec::SmallVec;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+/// A word made of uppercase ASCII letters
+///
+/// The letters are stored as ASCII bytes inline, so words of up to `INLINE_LEN` letters
+/// never allocate. Longer words still work but spill onto the heap.
+#[derive(PartialEq, Clone, Hash, Eq, PartialOrd, Ord, Debug)]
+pub struct Word {
+    letters: SmallVec<[u8; INLINE_LEN]>,
+}
+
+/// The longest word that is stored without a heap allocation
+pub const INLINE_LEN: usize = 16;
+
+/// The longest words the solver works with, as every hint of such a word must have a code
+/// from `Hint::to_code` that fits in a `u32`: 3^20 does and 3^21 does not
+pub const MAX_WORD_SIZE: usize = 20;
+
+impl Word {
+    /// Create a word from uppercase ASCII letters
+    pub fn new(chars: Vec<char>) -> Result<Self, ParseError> {
+        if let Some(&c) = chars.iter().find(|c| !c.is_alphabetic()) {
+            return Err(ParseError::NonAlphabetic(c));
+        }
+
+        if let Some(&c) = chars.iter().find(|c| !c.is_ascii_uppercase()) {
+            return Err(ParseError::NotUppercase(c));
+        }
+
+        let letters = chars.into_iter().map(|c| c as u8).collect();
+        Ok(Self { letters })
+    }
+
+    /// Parse a word from a string of ASCII letters in any case
+    pub fn from_string(s: &str) -> Result<Self, ParseError> {
+        if let Some(c) = s.chars().find(|c| !c.is_alphabetic()) {
+            return Err(ParseError::NonAlphabetic(c));
+        }
+
+        // Only ASCII letters are valid, so anything else is rejected before uppercasing
+        if let Some(c) = s.chars().find(|c| !c.is_ascii()) {
+            return Err(ParseError::NotUppercase(c));
+        }
+
+        let letters = s.bytes().map(|b| b.to_ascii_uppercase()).collect();
+        Ok(Self { letters })
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = char> + ExactSizeIterator + '_ {
+        self.letters.iter().map(|&b| b as char)
+    }
+
+    /// The letters as uppercase ASCII bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.letters
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+}
+
+impl fmt::Display for Word {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // Words only ever hold ASCII letters, which are valid UTF-8 on their own
+        let s = std::str::from_utf8(&self.letters).expect("Word is not ASCII");
+        f.write_str(s)
+    }
+}
+
+impl Iterator for Word {
+    type Item = char;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.letters.first().map(|&b| b as char)
+    }
+}
+
+/// The words that may be guessed and the words that may be the answer
+///
+/// Real Wordle accepts far more guesses than it ever picks as answers, so keeping the
+/// lists apart lets the solver score every guess while only partitioning the answers.
+#[derive(Clone, Debug)]
+pub struct WordLists {
+    /// Every word that may be guessed, including all of the answers
+    pub guesses: Vec<Word>,
+    /// The words that may be the answer
+    pub answers: Vec<Word>,
+}
+
+impl WordLists {
+    /// Combine a guess list and an answer list, adding any answers missing from the guesses
+    /// Both lists must be sorted and free of duplicates, as `load_words` returns them.
+    pub fn new(mut guesses: Vec<Word>, answers: Vec<Word>) -> Self {
+        let missing: Vec<Word> = answers
+            .iter()
+            .filter(|w| guesses.binary_search(w).is_err())
+            .cloned()
+            .collect();
+        if !missing.is_empty() {
+            guesses.extend(missing);
+            guesses.sort();
+        }
+        Self { guesses, answers }
+    }
+
+    /// Use the same words as both guesses and answers
+    pub fn single(words: Vec<Word>) -> Self {
+        Self {
+            guesses: words.clone(),
+            answers: words,
+        }
+    }
+}
+
+/// Load words from a file and return a Vec of unique words of the given length
+/// # Arguments
+/// * `path` - The file containing the word list, one word per line, optionally followed by
+///   a tab and the word's frequency
+/// * `word_size` - The number of letters a word must have to be kept
+/// # Returns
+/// A sorted Vec of unique, uppercase words of length `word_size`
+/// # Errors
+/// If the file cannot be opened, or `word_size` is above `MAX_WORD_SIZE`
+pub fn load_words(path: impl AsRef<Path>, word_size: usize) -> Result<Vec<Word>, io::Error> {
+    if word_size > MAX_WORD_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "words of {} letters are too long, the most is {}",
+                word_size, MAX_WORD_SIZE
+            ),
+        ));
+    }
+    let file = File::open(path)?;
+    let reader = BufReader::new(file);
+    Ok(collect_words(
+        reader.lines().map_while(Result::ok),
+        word_size,
+    ))
+}
+
+/// Load the weights of words from one `WORD WEIGHT` line per word, skipping malformed lines
+///
+/// The word and weight may be separated by a tab, as in a word list with a frequency column,
+/// or by spaces. Lines without a weight, such as those of a plain word list, are skipped.
+/// # Errors
+/// If the file cannot be read
+pub fn load_weights(path: impl AsRef<Path>) -> Result<HashMap<Word, f64>, io::Error> {
+    let contents = fs::read_to_string(path)?;
+    let weights = contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let word = Word::from_string(fields.next()?).ok()?;
+            let weight = fields.next()?.parse().ok()?;
+            Some((word, weight))
+        })
+        .collect();
+    Ok(weights)
+}
+
+/// Parse a word list held in memory, such as one embedded in the binary
+/// # Arguments
+/// * `text` - The word list, one word per line
+/// * `word_size` - The number of letters a word must have to be kept
+/// # Returns
+/// A sorted Vec of unique, uppercase words of length `word_size`
+pub fn parse_words(text: &str, word_size: usize) -> Vec<Word> {
+    collect_words(text.lines(), word_size)
+}
+
+fn collect_words<S: AsRef<str>>(lines: impl Iterator<Item = S>, word_size: usize) -> Vec<Word> {
+    // Use a HashSet to remove duplicates
+    let mut unique_words: HashSet<Word> = HashSet::new();
+
+    for line in lines {
+        // A frequency column after a tab belongs to `load_weights`
+        let word = line.as_ref().split('\t').next().unwrap_or_default();
+        if !is_valid_word(word, word_size) {
+            continue;
+        }
+        if let Ok(word) = Word::from_string(word) {
+            unique_words.insert(word);
+        }
+    }
+
+    // Sort so that the word order does not depend on the HashSet's hashing
+    let mut words: Vec<Word> = unique_words.into_iter().collect();
+    words.sort();
+    words
+}
+
+fn is_valid_word(word: &str, word_size: usize) -> bool {
+    word.len() == word_size && word.chars().all(char::is_alphabetic)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_round_trip_through_strings() {
+        let word = Word::from_string("crane").unwrap();
+        assert_eq!(word.to_string(), "CRANE");
+        assert_eq!(word.len(), 5);
+        assert_eq!(Word::from_string(&word.to_string()).unwrap(), word);
+        assert_eq!(Word::new(word.iter().collect()).unwrap(), word);
+    }
+
+    #[test]
+    fn words_longer_than_the_inline_length_spill_intact() {
+        let letters = "pneumonoultramicroscopic";
+        assert!(letters.len() > INLINE_LEN);
+        let word = Word::from_string(letters).unwrap();
+        assert_eq!(word.len(), letters.len());
+        assert_eq!(word.to_string(), letters.to_uppercase());
+        assert_eq!(word.iter().next_back(), Some('C'));
+
+        // Spilled and inline words still compare letter by letter
+        let shorter = Word::from_string(&letters[..INLINE_LEN]).unwrap();
+        assert!(shorter < word);
+        assert_ne!(shorter, word);
+    }
+
+    #[test]
+    fn words_reject_anything_but_ascii_letters() {
+        assert_eq!(
+            Word::from_string("cr4ne"),
+            Err(ParseError::NonAlphabetic('4'))
+        );
+        assert_eq!(
+            Word::from_string("crâne"),
+            Err(ParseError::NotUppercase('â'))
+        );
+        assert_eq!(
+            Word::new(vec!['C', 'r']),
+            Err(ParseError::NotUppercase('r'))
+        );
+    }
+
+    #[test]
+    fn words_longer_than_the_hints_allow_are_not_loaded() {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("words.txt");
+        assert!(load_words(&path, MAX_WORD_SIZE).is_ok());
+        let e = load_words(&path, MAX_WORD_SIZE + 1).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+    }
+}